@@ -6,7 +6,8 @@ use crate::tables::Tables;
 use crate::{TableIndex, TimeStamp};
 
 use chalk_ir::interner::Interner;
-use chalk_ir::{Goal, InEnvironment, Substitution, UCanonical};
+use chalk_ir::{AnswerSubst, Canonical, Goal, InEnvironment, Substitution, UCanonical};
+use std::fmt;
 use tracing::debug;
 
 pub(crate) struct Forest<I: Interner> {
@@ -17,6 +18,12 @@ pub(crate) struct Forest<I: Interner> {
     /// This effectively gives us way to track what depth
     /// and loop a table or strand was last followed.
     pub(crate) clock: TimeStamp,
+
+    /// Set once a table could not be created because of the table limit.
+    /// The tables that needed it hold floundered results that a solve
+    /// without the limit in the way would not give, so they must not be
+    /// reused.
+    pub(crate) table_limit_reached: bool,
 }
 
 impl<I: Interner> Forest<I> {
@@ -24,6 +31,7 @@ impl<I: Interner> Forest<I> {
         Forest {
             tables: Tables::new(),
             clock: TimeStamp::default(),
+            table_limit_reached: false,
         }
     }
 
@@ -51,12 +59,60 @@ impl<I: Interner> Forest<I> {
             answer,
         }
     }
+
+    /// Returns a snapshot of every table in the forest: its goal, the
+    /// answers cached so far and the number of strands still pending.
+    /// Useful for figuring out where a solve that does not terminate is
+    /// spending its time.
+    pub fn dump(&self) -> ForestDump<I> {
+        let tables = self
+            .tables
+            .iter()
+            .map(|(index, table)| TableDump {
+                index,
+                goal: table.table_goal.clone(),
+                answers: table.answers().map(|answer| answer.subst.clone()).collect(),
+                strands: table.strands().count(),
+            })
+            .collect();
+        ForestDump { tables }
+    }
+}
+
+/// The state of a forest, as produced by `Forest::dump`.
+#[derive(Debug)]
+pub struct ForestDump<I: Interner> {
+    pub tables: Vec<TableDump<I>>,
+}
+
+/// The state of a single table within a `ForestDump`.
+#[derive(Debug)]
+pub struct TableDump<I: Interner> {
+    pub index: TableIndex,
+    pub goal: UCanonical<InEnvironment<Goal<I>>>,
+    pub answers: Vec<Canonical<AnswerSubst<I>>>,
+    pub strands: usize,
+}
+
+impl<I: Interner> fmt::Display for ForestDump<I> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for table in &self.tables {
+            writeln!(fmt, "{:?}: {:?}", table.index, table.goal)?;
+            for answer in &table.answers {
+                writeln!(fmt, "    answer: {:?}", answer)?;
+            }
+            writeln!(fmt, "    strands: {}", table.strands)?;
+        }
+        Ok(())
+    }
 }
 
 struct ForestSolver<'me, I: Interner> {
     forest: &'me mut Forest<I>,
     context: &'me SlgContextOps<'me, I>,
-    table: TableIndex,
+    /// `None` if the table limit kept us from creating a table for
+    /// the goal; the goal is then reported as floundered.
+    table: Option<TableIndex>,
     answer: AnswerIndex,
 }
 
@@ -65,11 +121,12 @@ impl<'me, I: Interner> AnswerStream<I> for ForestSolver<'me, I> {
     ///
     /// Panics if a negative cycle was detected.
     fn peek_answer(&mut self, should_continue: impl Fn() -> bool) -> AnswerResult<I> {
+        let table = match self.table {
+            Some(table) => table,
+            None => return AnswerResult::Floundered,
+        };
         loop {
-            match self
-                .forest
-                .root_answer(self.context, self.table, self.answer)
-            {
+            match self.forest.root_answer(self.context, table, self.answer) {
                 Ok(answer) => {
                     debug!(answer = ?(&answer));
                     return AnswerResult::Answer(answer);
@@ -111,6 +168,9 @@ impl<'me, I: Interner> AnswerStream<I> for ForestSolver<'me, I> {
     }
 
     fn any_future_answer(&self, test: impl Fn(&Substitution<I>) -> bool) -> bool {
-        self.forest.any_future_answer(self.table, self.answer, test)
+        match self.table {
+            Some(table) => self.forest.any_future_answer(table, self.answer, test),
+            None => false,
+        }
     }
 }
@@ -60,15 +60,14 @@ use chalk_derive::{Fold, HasInterner, Visit};
 use chalk_ir::interner::Interner;
 use chalk_ir::visit::ControlFlow;
 use chalk_ir::{
-    AnswerSubst, Canonical, ConstrainedSubst, Constraint, DebruijnIndex, Goal, InEnvironment,
-    Substitution,
+    AnswerSubst, Canonical, ConstrainedSubst, Constraint, DebruijnIndex, Goal, ImplId,
+    InEnvironment, Substitution,
 };
 
 pub mod context;
 mod derived;
 pub mod forest;
 mod logic;
-mod normalize_deep;
 mod simplify;
 pub mod slg;
 pub mod solve;
@@ -117,6 +116,10 @@ pub struct ExClause<I: Interner> {
     /// List of subgoals that have floundered. See `FlounderedSubgoal`
     /// for more information.
     pub floundered_subgoals: Vec<FlounderedSubgoal<I>>,
+
+    /// The impl that the program clause we resolved with came from, if
+    /// any. This is carried over to the answer.
+    pub impl_id: Option<ImplId<I>>,
 }
 
 /// The "time stamp" is a simple clock that gets incremented each time
@@ -193,6 +196,10 @@ pub struct Answer<I: Interner> {
     /// nor disproven. This could be the size of the answer exceeded
     /// `max_size` or because of a negative loop (e.g., `P :- not { P }`).
     pub ambiguous: bool,
+
+    /// The impl whose program clause this answer was derived from, if
+    /// any.
+    pub impl_id: Option<ImplId<I>>,
 }
 
 #[derive(Clone, Debug)]
@@ -1,6 +1,5 @@
 use crate::forest::Forest;
-use crate::normalize_deep::DeepNormalizer;
-use crate::slg::{ResolventOps, SlgContext, SlgContextOps};
+use crate::slg::{ResolventOps, SlgContext, SlgContextOps, SubstitutionExt};
 use crate::stack::{Stack, StackIndex};
 use crate::strand::{CanonicalStrand, SelectedSubgoal, Strand};
 use crate::table::{AnswerIndex, Table};
@@ -184,7 +183,7 @@ impl<I: Interner> Forest<I> {
 
         debug!(?ucanonical_subgoal, ?universe_map);
 
-        let table = self.get_or_create_table_for_ucanonical_goal(context, ucanonical_subgoal);
+        let table = self.get_or_create_table_for_ucanonical_goal(context, ucanonical_subgoal)?;
 
         Some((table, universe_map))
     }
@@ -193,6 +192,9 @@ impl<I: Interner> Forest<I> {
     /// one is found, it is returned, but otherwise a new table is
     /// created (and populated with its initial set of strands).
     ///
+    /// Returns `None` if no table exists yet and the table limit of
+    /// `context` has been reached.
+    ///
     /// In terms of the NFTD paper, creating a new table corresponds
     /// to the *New Subgoal* step as well as the *Program Clause
     /// Resolution* steps.
@@ -201,10 +203,18 @@ impl<I: Interner> Forest<I> {
         &mut self,
         context: &SlgContextOps<I>,
         goal: UCanonical<InEnvironment<Goal<I>>>,
-    ) -> TableIndex {
+    ) -> Option<TableIndex> {
         if let Some(table) = self.tables.index_of(&goal) {
             debug!(?table, "found existing table");
-            return table;
+            return Some(table);
+        }
+
+        if let Some(table_limit) = context.table_limit() {
+            if self.tables.next_index().value >= table_limit {
+                info!(table_limit, "table limit reached, not creating table");
+                self.table_limit_reached = true;
+                return None;
+            }
         }
 
         info!(
@@ -213,7 +223,7 @@ impl<I: Interner> Forest<I> {
             goal,
         );
         let table = Self::build_table(context, self.tables.next_index(), goal);
-        self.tables.insert(table)
+        Some(self.tables.insert(table))
     }
 
     /// When a table is first created, this function is invoked to
@@ -256,10 +266,7 @@ impl<I: Interner> Forest<I> {
                 };
 
                 match program_clauses_that_could_match(db, &canon_domain_goal) {
-                    Ok(mut clauses) => {
-                        clauses.retain(could_match);
-                        clauses.extend(db.custom_clauses().into_iter().filter(could_match));
-
+                    Ok(program_clauses) => {
                         let (infer, subst, goal) =
                             chalk_solve::infer::InferenceTable::from_canonical(
                                 context.program().interner(),
@@ -267,12 +274,19 @@ impl<I: Interner> Forest<I> {
                                 canon_domain_goal.canonical,
                             );
 
-                        clauses.extend(
-                            db.program_clauses_for_env(&goal.environment)
-                                .iter(db.interner())
-                                .cloned()
-                                .filter(could_match),
-                        );
+                        // Strands are pursued in the order they are
+                        // enqueued, so the clauses from the environment
+                        // (e.g. the hypotheses of an `if` goal) go first,
+                        // then those from the program, then the custom
+                        // clauses.
+                        let mut clauses: Vec<_> = db
+                            .program_clauses_for_env(&goal.environment)
+                            .iter(db.interner())
+                            .cloned()
+                            .filter(could_match)
+                            .collect();
+                        clauses.extend(program_clauses.into_iter().filter(could_match));
+                        clauses.extend(db.custom_clauses().into_iter().filter(could_match));
 
                         let InEnvironment { environment, goal } = goal;
 
@@ -327,8 +341,7 @@ impl<I: Interner> Forest<I> {
                 match Self::simplify_goal(context, &mut infer, subst, environment, goal) {
                     FallibleOrFloundered::Ok(ex_clause) => {
                         info!(
-                            ex_clause = ?DeepNormalizer::normalize_deep(
-                                &mut infer,
+                            ex_clause = ?infer.normalize_deep(
                                 context.program().interner(),
                                 ex_clause.clone(),
                             ),
@@ -974,7 +987,7 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
                 negative: TimeStamp::MAX,
             };
 
-            if self.top_of_stack_is_coinductive_from(cyclic_depth) {
+            if self.context.coinduction() && self.top_of_stack_is_coinductive_from(cyclic_depth) {
                 debug!("table is coinductive");
                 return self.on_coinductive_subgoal(canonical_strand);
             }
@@ -1126,6 +1139,7 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
                 delayed_subgoals: Vec::new(),
                 answer_time: TimeStamp::default(),
                 floundered_subgoals: Vec::new(),
+                impl_id: answer.impl_id,
             },
             selected_subgoal: None,
             last_pursued_time: TimeStamp::default(),
@@ -1356,6 +1370,27 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
                     num_universes,
                     canonical_strand.clone(),
                 );
+
+                // A negative literal that the environment assumes to be
+                // false is proven without creating a table for it.
+                if let Literal::Negative(subgoal) = &strand.ex_clause.subgoals[subgoal_index] {
+                    let interner = self.context.program().interner();
+                    if let Some(subgoal) = infer.invert(interner, subgoal.clone()) {
+                        if subgoal
+                            .environment
+                            .assumes_not_holds(interner, &subgoal.goal)
+                        {
+                            debug!("negative literal proven by assumption");
+                            canonical_strand
+                                .value
+                                .ex_clause
+                                .subgoals
+                                .remove(subgoal_index);
+                            continue;
+                        }
+                    }
+                }
+
                 match self.forest.get_or_create_table_for_subgoal(
                     self.context,
                     &mut infer,
@@ -1373,7 +1408,7 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
                     None => {
                         // If we failed to create a table for the subgoal,
                         // that is because we have a floundered negative
-                        // literal.
+                        // literal, or because we hit the table limit.
                         self.flounder_subgoal(&mut canonical_strand.value.ex_clause, subgoal_index);
                     }
                 }
@@ -1424,6 +1459,7 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
             delayed_subgoals,
             answer_time: _,
             floundered_subgoals,
+            impl_id,
         } = strand.ex_clause;
         // If there are subgoals left, they should be followed
         assert!(subgoals.is_empty());
@@ -1490,9 +1526,16 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
                 delayed_subgoals: filtered_delayed_subgoals,
             },
         };
+        if cfg!(debug_assertions) {
+            crate::slg::assert_no_inference_vars(self.context.program().interner(), &subst);
+        }
         debug!(?table, ?subst, ?floundered, "found answer");
 
-        let answer = Answer { subst, ambiguous };
+        let answer = Answer {
+            subst,
+            ambiguous,
+            impl_id,
+        };
 
         // A "trivial" answer is one that is 'just true for all cases'
         // -- in other words, it gives no information back to the
@@ -1563,6 +1606,26 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
                     .is_empty(self.context.program().interner())
         };
 
+        // More generally, a complete answer without region constraints or
+        // delayed subgoals subsumes any strand whose substitution is an
+        // instance of it: every answer that strand could produce is already
+        // covered, so pursuing it would be wasted work. For example, once
+        // we have the answer `?0 := Foo` for `?0: Bar<?1>`, a strand that
+        // can only produce `?0 := Foo, ?1 := Baz` can be dropped.
+        let subsuming_subst = if !ambiguous
+            && !is_trivial_answer
+            && answer
+                .subst
+                .value
+                .constraints
+                .is_empty(self.context.program().interner())
+            && answer.subst.value.delayed_subgoals.is_empty()
+        {
+            Some(answer.subst.value.subst.clone())
+        } else {
+            None
+        };
+
         if let Some(answer_index) = self.forest.tables[table].push_answer(answer) {
             // See above, if we have a *complete* and trivial answer, we don't
             // want to follow any more strands
@@ -1570,6 +1633,13 @@ impl<'forest, I: Interner> SolveState<'forest, I> {
                 self.forest.tables[table].take_strands();
             }
 
+            if let Some(answer_subst) = subsuming_subst {
+                let interner = self.context.program().interner();
+                self.forest.tables[table].retain_strands(|strand| {
+                    !answer_subst.subsumes(interner, &strand.value.ex_clause.subst)
+                });
+            }
+
             Some(answer_index)
         } else {
             info!("answer: not a new answer, returning None");
@@ -30,12 +30,14 @@ impl<I: Interner> Forest<I> {
             delayed_subgoals: vec![],
             answer_time: TimeStamp::default(),
             floundered_subgoals: vec![],
+            impl_id: None,
         };
 
         // A stack of higher-level goals to process.
         let mut pending_goals = vec![(initial_environment, initial_goal)];
 
         while let Some((environment, goal)) = pending_goals.pop() {
+            let goal = goal.simplify(context.program().interner());
             match goal.data(context.program().interner()) {
                 GoalData::Quantified(QuantifierKind::ForAll, subgoal) => {
                     let subgoal = infer.instantiate_binders_universally(
@@ -2,6 +2,7 @@ use crate::ExClause;
 
 use chalk_derive::HasInterner;
 use chalk_ir::interner::Interner;
+use chalk_ir::visit::{ControlFlow, Visit, VisitExt, Visitor};
 use chalk_ir::*;
 use chalk_solve::infer::InferenceTable;
 use chalk_solve::RustIrDatabase;
@@ -34,6 +35,9 @@ pub(crate) struct SlgContextOps<'me, I: Interner> {
     program: &'me dyn RustIrDatabase<I>,
     max_size: usize,
     expected_answers: Option<usize>,
+    /// The table index at which to stop creating tables, if any.
+    table_limit: Option<usize>,
+    coinduction: bool,
 }
 
 impl<I: Interner> SlgContextOps<'_, I> {
@@ -41,11 +45,15 @@ impl<I: Interner> SlgContextOps<'_, I> {
         program: &dyn RustIrDatabase<I>,
         max_size: usize,
         expected_answers: Option<usize>,
+        table_limit: Option<usize>,
+        coinduction: bool,
     ) -> SlgContextOps<'_, I> {
         SlgContextOps {
             program,
             max_size,
             expected_answers,
+            table_limit,
+            coinduction,
         }
     }
 
@@ -77,6 +85,16 @@ impl<I: Interner> SlgContextOps<'_, I> {
         self.max_size
     }
 
+    pub(crate) fn table_limit(&self) -> Option<usize> {
+        self.table_limit
+    }
+
+    /// Whether cycles through coinductive goals are accepted. If not,
+    /// they are treated like any other cycle.
+    pub(crate) fn coinduction(&self) -> bool {
+        self.coinduction
+    }
+
     pub(crate) fn unification_database(&self) -> &dyn UnificationDatabase<I> {
         self.program.unification_database()
     }
@@ -109,8 +127,15 @@ pub trait ResolventOps<I: Interner> {
     ) -> Fallible<()>;
 }
 
-trait SubstitutionExt<I: Interner> {
+pub(crate) trait SubstitutionExt<I: Interner> {
     fn may_invalidate(&self, interner: &I, subst: &Canonical<Substitution<I>>) -> bool;
+
+    /// Returns true if every answer that a strand with the substitution
+    /// `strand_subst` could produce is an instance of `self`, the
+    /// substitution of an answer. This is conservative: each parameter of
+    /// `self` must either be a variable that appears nowhere else, or be
+    /// identical to the strand's parameter and mention no variables.
+    fn subsumes(&self, interner: &I, strand_subst: &Substitution<I>) -> bool;
 }
 
 impl<I: Interner> SubstitutionExt<I> for Substitution<I> {
@@ -119,6 +144,27 @@ impl<I: Interner> SubstitutionExt<I> for Substitution<I> {
             .zip(subst.value.iter(interner))
             .any(|(new, current)| MayInvalidate { interner }.aggregate_generic_args(new, current))
     }
+
+    fn subsumes(&self, interner: &I, strand_subst: &Substitution<I>) -> bool {
+        let mut seen_vars = Vec::new();
+        self.iter(interner)
+            .zip(strand_subst.iter(interner))
+            .all(|(answer_arg, strand_arg)| {
+                let bound_var = match answer_arg.data(interner) {
+                    GenericArgData::Ty(ty) => ty.bound_var(interner),
+                    GenericArgData::Lifetime(lifetime) => lifetime.bound_var(interner),
+                    GenericArgData::Const(constant) => constant.bound_var(interner),
+                };
+                match bound_var {
+                    Some(bound_var) if !seen_vars.contains(&bound_var) => {
+                        seen_vars.push(bound_var);
+                        true
+                    }
+                    Some(_) => false,
+                    None => answer_arg == strand_arg && !answer_arg.has_free_vars(interner),
+                }
+            })
+    }
 }
 
 // This is a struct in case we need to add state at any point like in AntiUnifier
@@ -376,3 +422,86 @@ impl<I: Interner> MayInvalidate<'_, I> {
             .any(|(new, current)| self.aggregate_generic_args(new, current))
     }
 }
+
+/// Asserts that a canonicalized answer contains no inference variables.
+/// Canonicalization is supposed to replace every inference variable with
+/// a bound variable, so finding one here means that the answer has leaked
+/// state from the inference table it was created in.
+pub(crate) fn assert_no_inference_vars<I: Interner>(
+    interner: &I,
+    answer: &Canonical<AnswerSubst<I>>,
+) {
+    let _ = answer.visit_with(
+        &mut InferenceVarLeakVisitor { interner, answer },
+        DebruijnIndex::INNERMOST,
+    );
+}
+
+struct InferenceVarLeakVisitor<'i, I: Interner> {
+    interner: &'i I,
+    answer: &'i Canonical<AnswerSubst<I>>,
+}
+
+impl<'i, I: Interner> Visitor<'i, I> for InferenceVarLeakVisitor<'i, I> {
+    type BreakTy = ();
+
+    fn as_dyn(&mut self) -> &mut dyn Visitor<'i, I, BreakTy = Self::BreakTy> {
+        self
+    }
+
+    fn interner(&self) -> &'i I {
+        self.interner
+    }
+
+    fn visit_inference_var(
+        &mut self,
+        var: InferenceVar,
+        _outer_binder: DebruijnIndex,
+    ) -> ControlFlow<()> {
+        panic!(
+            "inference variable `{:?}` leaked into canonicalized answer `{:?}`",
+            var, self.answer
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chalk_integration::interner::ChalkIr;
+
+    #[test]
+    fn canonical_answer_has_no_inference_vars() {
+        let interner = &ChalkIr;
+        let mut table: InferenceTable<ChalkIr> = InferenceTable::new();
+        let var = table.new_variable(UniverseIndex::ROOT).to_ty(interner);
+        let answer = table
+            .canonicalize(
+                interner,
+                AnswerSubst {
+                    subst: Substitution::from1(interner, var),
+                    constraints: Constraints::empty(interner),
+                    delayed_subgoals: vec![],
+                },
+            )
+            .quantified;
+        assert_no_inference_vars(interner, &answer);
+    }
+
+    #[test]
+    #[should_panic(expected = "leaked into canonicalized answer")]
+    fn leaked_inference_var_is_detected() {
+        let interner = &ChalkIr;
+        let ty =
+            TyKind::InferenceVar(InferenceVar::from(0), TyVariableKind::General).intern(interner);
+        let answer = Canonical {
+            binders: CanonicalVarKinds::empty(interner),
+            value: AnswerSubst {
+                subst: Substitution::from1(interner, ty),
+                constraints: Constraints::empty(interner),
+                delayed_subgoals: vec![],
+            },
+        };
+        assert_no_inference_vars(interner, &answer);
+    }
+}
@@ -31,7 +31,10 @@ impl<I: Interner> AggregateOps<I> for SlgContextOps<'_, I> {
         should_continue: impl std::ops::Fn() -> bool,
     ) -> Option<Solution<I>> {
         let interner = self.program.interner();
-        let CompleteAnswer { subst, ambiguous } = match answers.next_answer(|| should_continue()) {
+        let CompleteAnswer {
+            mut subst,
+            ambiguous,
+        } = match answers.next_answer(|| should_continue()) {
             AnswerResult::NoMoreSolutions => {
                 // No answers at all
                 return None;
@@ -46,8 +49,27 @@ impl<I: Interner> AggregateOps<I> for SlgContextOps<'_, I> {
             }
         };
 
+        // Answers that agree with the first one on the substitution and
+        // differ only in their region constraints are folded into it. See
+        // `merge_constraints`.
+        let mut next_answer = answers.peek_answer(&should_continue);
+        while let AnswerResult::Answer(answer) = &next_answer {
+            if ambiguous
+                || answer.ambiguous
+                || answer.subst.binders != subst.binders
+                || answer.subst.value.subst != subst.value.subst
+            {
+                break;
+            }
+            match merge_constraints(interner, &subst, &answer.subst) {
+                Some(merged) => subst = merged,
+                None => break,
+            }
+            answers.next_answer(&should_continue);
+            next_answer = answers.peek_answer(&should_continue);
+        }
+
         // Exactly 1 unconditional answer?
-        let next_answer = answers.peek_answer(|| should_continue());
         if next_answer.is_quantum_exceeded() {
             if subst.value.subst.is_identity_subst(interner) {
                 return Some(Solution::Ambig(Guidance::Unknown));
@@ -66,13 +88,7 @@ impl<I: Interner> AggregateOps<I> for SlgContextOps<'_, I> {
         // **guidance**, and with guidance, the caller doesn't get
         // back any region constraints. So drop them from our `subst`
         // variable.
-        //
-        // FIXME-- there is actually a 3rd possibility. We could have
-        // >1 answer where all the answers have the same substitution,
-        // but different region constraints. We should collapse those
-        // cases into an `OR` region constraint at some point, but I
-        // leave that for future work. This is basically
-        // rust-lang/rust#21974.
+
         let mut subst = subst.map(interner, |cs| cs.subst);
 
         // Extract answers and merge them into `subst`. Stop once we have
@@ -122,6 +138,34 @@ impl<I: Interner> AggregateOps<I> for SlgContextOps<'_, I> {
     }
 }
 
+/// Combines two answers with the same substitution but different
+/// region constraints into one answer.
+///
+/// The caller only needs to prove the constraints of *one* of the
+/// answers, so if one answer's constraints are a subset of the other's,
+/// the weaker answer subsumes the stronger one. Otherwise we would need
+/// an `OR` of region constraints, which we have no way to express (this
+/// is basically rust-lang/rust#21974), so we return `None` and the
+/// answers are aggregated into guidance as usual.
+fn merge_constraints<I: Interner>(
+    interner: &I,
+    answer: &Canonical<ConstrainedSubst<I>>,
+    other: &Canonical<ConstrainedSubst<I>>,
+) -> Option<Canonical<ConstrainedSubst<I>>> {
+    let constraints = answer.value.constraints.as_slice(interner);
+    let other_constraints = other.value.constraints.as_slice(interner);
+    let is_subset = |a: &[InEnvironment<Constraint<I>>], b: &[InEnvironment<Constraint<I>>]| {
+        a.iter().all(|constraint| b.contains(constraint))
+    };
+    if is_subset(constraints, other_constraints) {
+        Some(answer.clone())
+    } else if is_subset(other_constraints, constraints) {
+        Some(other.clone())
+    } else {
+        None
+    }
+}
+
 /// Given a current substitution used as guidance for `root_goal`, and
 /// a new possible answer to `root_goal`, returns a new set of
 /// guidance that encompasses both of them. This is often more general
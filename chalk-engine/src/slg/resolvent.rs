@@ -1,4 +1,3 @@
-use crate::normalize_deep::DeepNormalizer;
 use crate::slg::ResolventOps;
 use crate::{ExClause, Literal, TimeStamp};
 use chalk_ir::cast::Caster;
@@ -79,6 +78,7 @@ impl<I: Interner> ResolventOps<I> for InferenceTable<I> {
             conditions,
             constraints,
             priority: _,
+            impl_id,
         } = {
             let ProgramClauseData(implication) = clause.data(interner);
 
@@ -105,6 +105,7 @@ impl<I: Interner> ResolventOps<I> for InferenceTable<I> {
             delayed_subgoals: vec![],
             answer_time: TimeStamp::default(),
             floundered_subgoals: vec![],
+            impl_id,
         };
 
         // Add the subgoals/region-constraints that unification gave us.
@@ -221,7 +222,7 @@ impl<I: Interner> ResolventOps<I> for InferenceTable<I> {
         answer_table_goal: &Canonical<InEnvironment<Goal<I>>>,
         canonical_answer_subst: Canonical<AnswerSubst<I>>,
     ) -> Fallible<()> {
-        debug!(selected_goal = ?DeepNormalizer::normalize_deep(self, interner, selected_goal.clone()));
+        debug!(selected_goal = ?self.normalize_deep(interner, selected_goal.clone()));
 
         // C' is now `answer`. No variables in common with G.
         let AnswerSubst {
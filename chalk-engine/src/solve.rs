@@ -1,10 +1,13 @@
 use crate::context::{AnswerResult, AnswerStream};
-use crate::forest::Forest;
+use crate::forest::{Forest, ForestDump};
 use crate::slg::aggregate::AggregateOps;
 use crate::slg::SlgContextOps;
 use chalk_ir::interner::Interner;
-use chalk_ir::{Canonical, ConstrainedSubst, Goal, InEnvironment, UCanonical};
-use chalk_solve::{RustIrDatabase, Solution, Solver, SubstitutionResult};
+use chalk_ir::{
+    Canonical, ConstrainedSubst, DomainGoal, Goal, GoalData, ImplId, InEnvironment, UCanonical,
+    WhereClause,
+};
+use chalk_solve::{FailureReason, RustIrDatabase, Solution, Solver, SubstitutionResult};
 
 use std::fmt;
 
@@ -12,16 +15,62 @@ pub struct SLGSolver<I: Interner> {
     pub(crate) forest: Forest<I>,
     pub(crate) max_size: usize,
     pub(crate) expected_answers: Option<usize>,
+    pub(crate) max_tables: Option<usize>,
+    pub(crate) coinduction: bool,
 }
 
 impl<I: Interner> SLGSolver<I> {
-    pub fn new(max_size: usize, expected_answers: Option<usize>) -> Self {
+    /// Creates a new solver. If `max_tables` is given, the solver
+    /// creates at most that many tables for each goal it is asked to
+    /// solve; subgoals that would need more flounder instead.
+    pub fn new(
+        max_size: usize,
+        expected_answers: Option<usize>,
+        max_tables: Option<usize>,
+    ) -> Self {
         Self {
             forest: Forest::new(),
             max_size,
             expected_answers,
+            max_tables,
+            coinduction: true,
         }
     }
+
+    /// Turns the handling of coinductive cycles on or off. With it off,
+    /// a goal that refers back to itself through coinductive goals (such
+    /// as auto traits) is treated like any other cycle, and fails. This is
+    /// a debugging aid for finding out whether coinduction is what makes a
+    /// goal succeed.
+    ///
+    /// This discards the tables built so far, since they were computed
+    /// with the old setting.
+    pub fn set_coinduction(&mut self, coinduction: bool) {
+        self.coinduction = coinduction;
+        self.forest = Forest::new();
+    }
+
+    /// Discards the forest if an earlier solve ran into the table limit,
+    /// so that its floundered tables don't leak into later answers.
+    fn discard_floundered_tables(&mut self) {
+        if self.forest.table_limit_reached {
+            self.forest = Forest::new();
+        }
+    }
+
+    /// The table index at which the current solve must stop creating
+    /// tables. Tables left in the forest by earlier goals don't count
+    /// against `max_tables`.
+    fn table_limit(&self) -> Option<usize> {
+        self.max_tables
+            .map(|max_tables| self.forest.tables.next_index().value + max_tables)
+    }
+
+    /// Returns a snapshot of the tables built up by this solver so far;
+    /// see `Forest::dump`.
+    pub fn dump(&self) -> ForestDump<I> {
+        self.forest.dump()
+    }
 }
 
 impl<I: Interner> fmt::Debug for SLGSolver<I> {
@@ -36,7 +85,14 @@ impl<I: Interner> Solver<I> for SLGSolver<I> {
         program: &dyn RustIrDatabase<I>,
         goal: &UCanonical<InEnvironment<Goal<I>>>,
     ) -> Option<Solution<I>> {
-        let ops = SlgContextOps::new(program, self.max_size, self.expected_answers);
+        self.discard_floundered_tables();
+        let ops = SlgContextOps::new(
+            program,
+            self.max_size,
+            self.expected_answers,
+            self.table_limit(),
+            self.coinduction,
+        );
         ops.make_solution(goal, self.forest.iter_answers(&ops, goal), || true)
     }
 
@@ -46,17 +102,118 @@ impl<I: Interner> Solver<I> for SLGSolver<I> {
         goal: &UCanonical<InEnvironment<Goal<I>>>,
         should_continue: &dyn std::ops::Fn() -> bool,
     ) -> Option<Solution<I>> {
-        let ops = SlgContextOps::new(program, self.max_size, self.expected_answers);
+        self.discard_floundered_tables();
+        let ops = SlgContextOps::new(
+            program,
+            self.max_size,
+            self.expected_answers,
+            self.table_limit(),
+            self.coinduction,
+        );
         ops.make_solution(goal, self.forest.iter_answers(&ops, goal), should_continue)
     }
 
+    fn solve_with_explanation(
+        &mut self,
+        program: &dyn RustIrDatabase<I>,
+        goal: &UCanonical<InEnvironment<Goal<I>>>,
+    ) -> (Option<Solution<I>>, Option<FailureReason<I>>) {
+        let solution = self.solve(program, goal);
+        if solution.is_some() {
+            return (solution, None);
+        }
+
+        // Our own forest may hold tables from earlier goals, so solve
+        // again from scratch to know which tables belong to this one.
+        let mut solver = SLGSolver {
+            forest: Forest::new(),
+            ..*self
+        };
+        solver.solve(program, goal);
+
+        // Tables are created as the search goes deeper, so the last one
+        // without answers is usually the innermost subgoal that failed.
+        // `FromEnv` goals only come up while looking for implied bounds,
+        // which is rarely what the user wants to hear about.
+        let interner = program.interner();
+        let reason = solver
+            .forest
+            .tables
+            .iter()
+            .map(|(_, table)| table)
+            .filter(|table| {
+                table.table_goal != *goal
+                    && table.answers().next().is_none()
+                    && !matches!(
+                        table.table_goal.canonical.value.goal.data(interner),
+                        GoalData::DomainGoal(DomainGoal::FromEnv(_))
+                    )
+            })
+            .last()
+            .map(|table| FailureReason {
+                goal: table.table_goal.clone(),
+            });
+        (None, reason)
+    }
+
+    fn selected_impl(
+        &mut self,
+        program: &dyn RustIrDatabase<I>,
+        goal: &UCanonical<InEnvironment<Goal<I>>>,
+    ) -> Option<ImplId<I>> {
+        let interner = program.interner();
+        if !matches!(
+            goal.canonical.value.goal.data(interner),
+            GoalData::DomainGoal(DomainGoal::Holds(WhereClause::Implemented(_)))
+        ) {
+            return None;
+        }
+        if !self.solve(program, goal)?.is_unique() {
+            return None;
+        }
+
+        // A unique solution is built from all the answers of the goal's
+        // table, and each answer records the impl it was derived from.
+        let table = self.forest.tables.index_of(goal)?;
+        let mut impl_ids = self.forest.tables[table]
+            .answers()
+            .map(|answer| answer.impl_id);
+        let impl_id = impl_ids.next()??;
+        if impl_ids.all(|other| other == Some(impl_id)) {
+            Some(impl_id)
+        } else {
+            None
+        }
+    }
+
+    fn dump_tables(&self) -> Option<String> {
+        Some(self.dump().to_string())
+    }
+
+    fn table_goals(&self) -> Option<Vec<UCanonical<InEnvironment<Goal<I>>>>> {
+        Some(
+            self.dump()
+                .tables
+                .into_iter()
+                .map(|table| table.goal)
+                .collect(),
+        )
+    }
+
     fn solve_multiple(
         &mut self,
         program: &dyn RustIrDatabase<I>,
         goal: &UCanonical<InEnvironment<Goal<I>>>,
         f: &mut dyn FnMut(SubstitutionResult<Canonical<ConstrainedSubst<I>>>, bool) -> bool,
     ) -> bool {
-        let ops = SlgContextOps::new(program, self.max_size, self.expected_answers);
+        self.discard_floundered_tables();
+        let ops = SlgContextOps::new(
+            program,
+            self.max_size,
+            self.expected_answers,
+            self.table_limit(),
+            self.coinduction,
+        );
         let mut answers = self.forest.iter_answers(&ops, goal);
         loop {
             let subst = match answers.next_answer(|| true) {
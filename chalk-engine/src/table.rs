@@ -86,6 +86,11 @@ impl<I: Interner> Table<I> {
         mem::replace(&mut self.strands, VecDeque::new())
     }
 
+    /// Drops the strands that do not meet the given criteria.
+    pub(crate) fn retain_strands(&mut self, test: impl FnMut(&CanonicalStrand<I>) -> bool) {
+        self.strands.retain(test);
+    }
+
     /// Remove the next strand from the queue that meets the given criteria
     pub(crate) fn dequeue_next_strand_that(
         &mut self,
@@ -163,6 +168,10 @@ impl<I: Interner> Table<I> {
         self.answers.get(index.value)
     }
 
+    pub(super) fn answers(&self) -> impl Iterator<Item = &Answer<I>> {
+        self.answers.iter()
+    }
+
     pub(super) fn next_answer_index(&self) -> AnswerIndex {
         AnswerIndex::from(self.answers.len())
     }
@@ -46,6 +46,14 @@ impl<I: Interner> Tables<I> {
     ) -> Option<TableIndex> {
         self.table_indices.get(literal).cloned()
     }
+
+    /// Iterates over all tables, in the order they were created.
+    pub(super) fn iter(&self) -> impl Iterator<Item = (TableIndex, &Table<I>)> {
+        self.tables
+            .iter()
+            .enumerate()
+            .map(|(value, table)| (TableIndex { value }, table))
+    }
 }
 
 impl<I: Interner> Index<TableIndex> for Tables<I> {
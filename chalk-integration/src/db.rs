@@ -3,25 +3,30 @@ use crate::{
     interner::ChalkIr,
     lowering::lower_goal,
     program::Program,
-    query::{Lowering, LoweringDatabase},
+    query::{self, Lowering, LoweringDatabase},
     tls, SolverChoice,
 };
+use chalk_ir::could_match::CouldMatch;
 use chalk_ir::{
     AdtId, AssocTypeId, Binders, Canonical, CanonicalVarKinds, ClosureId, ConstrainedSubst,
-    Environment, FnDefId, GeneratorId, GenericArg, Goal, ImplId, InEnvironment, OpaqueTyId,
-    ProgramClause, ProgramClauses, Substitution, TraitId, Ty, TyKind, UCanonical,
+    Environment, FnDefId, GeneratorId, GenericArg, Goal, GoalData, ImplId, InEnvironment,
+    OpaqueTyId, ProgramClause, ProgramClauses, Substitution, TraitId, Ty, TyKind, UCanonical,
     UnificationDatabase, Variances,
 };
+use chalk_solve::ext::GoalExt;
 use chalk_solve::rust_ir::{
     AdtDatum, AdtRepr, AssociatedTyDatum, AssociatedTyValue, AssociatedTyValueId, ClosureKind,
     FnDefDatum, FnDefInputsAndOutputDatum, GeneratorDatum, GeneratorWitnessDatum, ImplDatum,
     OpaqueTyDatum, TraitDatum, WellKnownTrait,
 };
-use chalk_solve::{RustIrDatabase, Solution, SubstitutionResult};
+use chalk_solve::{FailureReason, RustIrDatabase, Solution, SubstitutionResult};
 use salsa::Database;
 use std::fmt;
 use std::sync::Arc;
 
+/// A lowered goal, peeled and ready to be handed to a solver.
+pub type PeeledGoal = UCanonical<InEnvironment<Goal<ChalkIr>>>;
+
 #[salsa::database(Lowering)]
 #[derive(Default)]
 pub struct ChalkDatabase {
@@ -35,12 +40,54 @@ impl ChalkDatabase {
         let mut db = ChalkDatabase::default();
         db.set_program_text(Arc::new(program_text.to_string()));
         db.set_solver_choice(solver_choice);
+        db.set_whole_program(false);
         db
     }
 
+    /// Creates a database for `program_text`, checks the program, and
+    /// lowers each of `goals`, peeling them so that they are ready to be
+    /// handed to a solver. Errors in the program are reported as is;
+    /// errors in a goal mention the goal that caused them.
+    pub fn with_program_and_goals(
+        program_text: &str,
+        solver_choice: SolverChoice,
+        goals: &[&str],
+    ) -> Result<(Self, Vec<PeeledGoal>), ChalkError> {
+        let db = ChalkDatabase::with(program_text, solver_choice);
+        let program = db.checked_program()?;
+        let goals = goals
+            .iter()
+            .map(|text| {
+                let goal = chalk_parse::parse_goal(text)
+                    .and_then(|goal| Ok(lower_goal(&goal, &program)?))
+                    .map_err(|error| -> Box<dyn std::error::Error> {
+                        format!("error in goal `{}`: {}", text, error).into()
+                    })?;
+                Ok(goal.into_peeled_goal(db.interner()))
+            })
+            .collect::<Result<_, ChalkError>>()?;
+        Ok((db, goals))
+    }
+
     pub fn with_program<R>(&self, op: impl FnOnce(&Program) -> R) -> R {
         let program = &self.checked_program().unwrap();
-        tls::set_current_program(&program, || op(&program))
+        let _guard = tls::set_current_program(program);
+        op(program)
+    }
+
+    /// Like `checked_program`, but rather than stopping at the first
+    /// error, runs the coherence, orphan and WF checks on every item and
+    /// reports all the errors found. A program that fails to parse or
+    /// lower reports only that error, as the checks need the lowered
+    /// program.
+    pub fn lower_and_check(&self) -> Result<Arc<Program>, Vec<ChalkError>> {
+        let program = self.program_ir().map_err(|error| vec![error])?;
+        let errors = query::check_program(self, &program, false);
+        if errors.is_empty() {
+            Ok(program)
+        } else {
+            Err(errors)
+        }
     }
 
     pub fn parse_and_lower_goal(&self, text: &str) -> Result<Goal<ChalkIr>, ChalkError> {
@@ -57,6 +104,18 @@ impl ChalkDatabase {
         solution
     }
 
+    /// Like `solve`, but if there is no solution, also returns a subgoal
+    /// that failed, if the solver can name one. See
+    /// `Solver::solve_with_explanation`.
+    pub fn solve_with_explanation(
+        &self,
+        goal: &UCanonical<InEnvironment<Goal<ChalkIr>>>,
+    ) -> (Option<Solution<ChalkIr>>, Option<FailureReason<ChalkIr>>) {
+        let solver = self.solver();
+        let result = solver.lock().unwrap().solve_with_explanation(self, goal);
+        result
+    }
+
     /// Solves a given goal, producing the solution. This will do only
     /// as much work towards `goal` as it has to (and that works is
     /// cached for future attempts). Calls provided function `f` to
@@ -70,6 +129,41 @@ impl ChalkDatabase {
         let solution = solver.lock().unwrap().solve_multiple(self, goal, f);
         solution
     }
+
+    /// Describes the tables cached by the solver, if it keeps any. See
+    /// `Solver::dump_tables`.
+    pub fn dump_tables(&self) -> Option<String> {
+        let solver = self.solver();
+        let dump = solver.lock().unwrap().dump_tables();
+        dump
+    }
+
+    /// Returns the custom clauses of the program that could not have been
+    /// used by any goal solved so far, i.e. those that do not match the
+    /// goal of any table the solver created. Returns `None` if the solver
+    /// does not keep tables.
+    pub fn unused_custom_clauses(&self) -> Option<Vec<ProgramClause<ChalkIr>>> {
+        let solver = self.solver();
+        let goals = solver.lock().unwrap().table_goals()?;
+        let interner = self.interner();
+        let domain_goals: Vec<_> = goals
+            .iter()
+            .filter_map(|goal| match goal.canonical.value.goal.data(interner) {
+                GoalData::DomainGoal(domain_goal) => Some(domain_goal),
+                _ => None,
+            })
+            .collect();
+        Some(
+            self.custom_clauses()
+                .into_iter()
+                .filter(|clause| {
+                    !domain_goals.iter().any(|domain_goal| {
+                        clause.could_match(interner, self.unification_database(), *domain_goal)
+                    })
+                })
+                .collect(),
+        )
+    }
 }
 
 impl UnificationDatabase<ChalkIr> for ChalkDatabase {
@@ -246,6 +340,10 @@ impl RustIrDatabase<ChalkIr> for ChalkDatabase {
     fn discriminant_type(&self, ty: Ty<ChalkIr>) -> Ty<ChalkIr> {
         self.program_ir().unwrap().discriminant_type(ty)
     }
+
+    fn is_whole_program(&self) -> bool {
+        self.whole_program()
+    }
 }
 
 impl fmt::Debug for ChalkDatabase {
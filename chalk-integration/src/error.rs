@@ -1,8 +1,8 @@
 use crate::interner::ChalkIr;
-use chalk_parse::ast::{Identifier, Kind};
+use crate::TypeSort;
+use chalk_parse::ast::{Identifier, Kind, Span};
 use chalk_solve::coherence::CoherenceError;
 use chalk_solve::wf::WfError;
-use string_cache::DefaultAtom as Atom;
 
 /// Wrapper type for the various errors that can occur during chalk
 /// processing.
@@ -11,12 +11,22 @@ pub struct ChalkError {
     /// For now, we just convert the error into a string, which makes
     /// it trivially hashable etc.
     error_text: String,
+    /// Where in the program text the error occurred, if known.
+    span: Option<Span>,
+}
+
+impl ChalkError {
+    /// The byte range in the program text that caused this error, if known.
+    pub fn span(&self) -> Option<Span> {
+        self.span
+    }
 }
 
 impl From<Box<dyn std::error::Error>> for ChalkError {
     fn from(value: Box<dyn std::error::Error>) -> Self {
         ChalkError {
             error_text: value.to_string(),
+            span: None,
         }
     }
 }
@@ -25,6 +35,7 @@ impl From<WfError<ChalkIr>> for ChalkError {
     fn from(value: WfError<ChalkIr>) -> Self {
         ChalkError {
             error_text: value.to_string(),
+            span: None,
         }
     }
 }
@@ -33,6 +44,7 @@ impl From<CoherenceError<ChalkIr>> for ChalkError {
     fn from(value: CoherenceError<ChalkIr>) -> Self {
         ChalkError {
             error_text: value.to_string(),
+            span: None,
         }
     }
 }
@@ -41,6 +53,7 @@ impl From<RustIrError> for ChalkError {
     fn from(value: RustIrError) -> Self {
         ChalkError {
             error_text: value.to_string(),
+            span: value.span(),
         }
     }
 }
@@ -58,13 +71,17 @@ pub enum RustIrError {
     InvalidParameterName(Identifier),
     InvalidTraitName(Identifier),
     NotTrait(Identifier),
+    NotTraitSort(Identifier, TypeSort),
     NotStruct(Identifier),
     DuplicateOrShadowedParameters,
     AutoTraitAssociatedTypes(Identifier),
     AutoTraitParameters(Identifier),
     AutoTraitWhereClauses(Identifier),
+    /// No longer produced: fundamental types may have any number of type
+    /// parameters. Kept so that existing matches on this enum still compile.
     InvalidFundamentalTypesParameters(Identifier),
     NegativeImplAssociatedValues(Identifier),
+    TraitAliasImpl(Identifier),
     MissingAssociatedType(Identifier),
     IncorrectNumberOfVarianceParameters {
         identifier: Identifier,
@@ -97,7 +114,43 @@ pub enum RustIrError {
         actual: Kind,
     },
     CannotApplyTypeParameter(Identifier),
-    InvalidExternAbi(Atom),
+    InvalidExternAbi(Identifier),
+    QuantifierNestingTooDeep {
+        identifier: Identifier,
+        limit: usize,
+    },
+}
+
+impl RustIrError {
+    /// The byte range in the program text that caused this error. This is
+    /// `None` only for `DuplicateOrShadowedParameters`, which is detected
+    /// after the parameter names have lost their spans.
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            RustIrError::InvalidParameterName(name)
+            | RustIrError::InvalidTraitName(name)
+            | RustIrError::NotTrait(name)
+            | RustIrError::NotTraitSort(name, _)
+            | RustIrError::NotStruct(name)
+            | RustIrError::AutoTraitAssociatedTypes(name)
+            | RustIrError::AutoTraitParameters(name)
+            | RustIrError::AutoTraitWhereClauses(name)
+            | RustIrError::InvalidFundamentalTypesParameters(name)
+            | RustIrError::NegativeImplAssociatedValues(name)
+            | RustIrError::TraitAliasImpl(name)
+            | RustIrError::MissingAssociatedType(name)
+            | RustIrError::CannotApplyTypeParameter(name)
+            | RustIrError::InvalidExternAbi(name) => Some(name.span),
+            RustIrError::IncorrectNumberOfVarianceParameters { identifier, .. }
+            | RustIrError::IncorrectNumberOfTypeParameters { identifier, .. }
+            | RustIrError::IncorrectNumberOfAssociatedTypeParameters { identifier, .. }
+            | RustIrError::IncorrectParameterKind { identifier, .. }
+            | RustIrError::IncorrectTraitParameterKind { identifier, .. }
+            | RustIrError::IncorrectAssociatedTypeParameterKind { identifier, .. }
+            | RustIrError::QuantifierNestingTooDeep { identifier, .. } => Some(identifier.span),
+            RustIrError::DuplicateOrShadowedParameters => None,
+        }
+    }
 }
 
 impl std::fmt::Display for RustIrError {
@@ -112,6 +165,13 @@ impl std::fmt::Display for RustIrError {
                 "expected a trait, found `{}`, which is not a trait",
                 name
             ),
+            RustIrError::NotTraitSort(name, sort) => {
+                let article = match sort {
+                    TypeSort::Enum | TypeSort::Opaque => "an",
+                    _ => "a",
+                };
+                write!(f, "`{}` is {} {}, not a trait", name, article, sort)
+            }
             RustIrError::NotStruct(name) => write!(
                 f,
                 "expected a struct, found `{}`, which is not a struct",
@@ -139,6 +199,9 @@ impl std::fmt::Display for RustIrError {
                 "negative impl for trait `{}` cannot define associated values",
                 name
             ),
+            RustIrError::TraitAliasImpl(name) => {
+                write!(f, "cannot implement trait alias `{}`", name)
+            }
             RustIrError::MissingAssociatedType(name) => {
                 write!(f, "no associated type `{}` defined in trait", name)
             }
@@ -200,6 +263,11 @@ impl std::fmt::Display for RustIrError {
                 write!(f, "cannot apply type parameter `{}`", name)
             }
             RustIrError::InvalidExternAbi(abi) => write!(f, "invalid extern ABI `{}`", abi),
+            RustIrError::QuantifierNestingTooDeep { identifier, limit } => write!(
+                f,
+                "quantifier binding `{}` is nested more than {} quantifiers deep",
+                identifier, limit
+            ),
         }
     }
 }
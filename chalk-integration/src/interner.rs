@@ -15,6 +15,7 @@ use chalk_ir::{
 };
 use std::fmt;
 use std::fmt::Debug;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use string_cache::DefaultAtom;
 
@@ -55,6 +56,29 @@ impl Debug for ChalkFnAbi {
 #[derive(Debug, Copy, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
 pub struct ChalkIr;
 
+static INTERNED_TYS: AtomicUsize = AtomicUsize::new(0);
+static INTERNED_GOALS: AtomicUsize = AtomicUsize::new(0);
+
+/// How many nodes `ChalkIr` has interned since the process started,
+/// as returned by [`ChalkIr::intern_stats`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct InternStats {
+    pub tys: usize,
+    pub goals: usize,
+}
+
+impl ChalkIr {
+    /// Returns the number of types and goals interned so far, across all
+    /// programs. Since `ChalkIr` never deduplicates, each of these is a
+    /// separate allocation.
+    pub fn intern_stats(&self) -> InternStats {
+        InternStats {
+            tys: INTERNED_TYS.load(Ordering::Relaxed),
+            goals: INTERNED_GOALS.load(Ordering::Relaxed),
+        }
+    }
+}
+
 impl Interner for ChalkIr {
     type InternedType = Arc<TyData<ChalkIr>>;
     type InternedLifetime = LifetimeData<ChalkIr>;
@@ -213,6 +237,7 @@ impl Interner for ChalkIr {
         tls::with_current_program(|prog| {
             Some(prog?.debug_separator_trait_ref(separator_trait_ref, fmt))
         })
+        .or_else(|| Some(write!(fmt, "{:?}", separator_trait_ref.debug(&ChalkIr))))
     }
 
     fn debug_quantified_where_clauses(
@@ -238,6 +263,7 @@ impl Interner for ChalkIr {
 
     fn intern_ty(&self, kind: TyKind<ChalkIr>) -> Arc<TyData<ChalkIr>> {
         let flags = kind.compute_flags(self);
+        INTERNED_TYS.fetch_add(1, Ordering::Relaxed);
         Arc::new(TyData { kind, flags })
     }
 
@@ -277,6 +303,7 @@ impl Interner for ChalkIr {
     }
 
     fn intern_goal(&self, goal: GoalData<ChalkIr>) -> Arc<GoalData<ChalkIr>> {
+        INTERNED_GOALS.fetch_add(1, Ordering::Relaxed);
         Arc::new(goal)
     }
 
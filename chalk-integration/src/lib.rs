@@ -22,7 +22,9 @@ pub use interner::{Identifier, RawId};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum TypeSort {
-    Adt,
+    Struct,
+    Enum,
+    Union,
     FnDef,
     Closure,
     Trait,
@@ -30,6 +32,21 @@ pub enum TypeSort {
     Generator,
 }
 
+impl std::fmt::Display for TypeSort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TypeSort::Struct => write!(f, "struct"),
+            TypeSort::Enum => write!(f, "enum"),
+            TypeSort::Union => write!(f, "union"),
+            TypeSort::FnDef => write!(f, "function"),
+            TypeSort::Closure => write!(f, "closure"),
+            TypeSort::Trait => write!(f, "trait"),
+            TypeSort::Opaque => write!(f, "opaque type"),
+            TypeSort::Generator => write!(f, "generator"),
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Unit;
 
@@ -50,6 +67,7 @@ pub enum SolverChoice {
     SLG {
         max_size: usize,
         expected_answers: Option<usize>,
+        max_tables: Option<usize>,
     },
     /// Run the recursive solver.
     Recursive {
@@ -65,15 +83,41 @@ impl SolverChoice {
         SolverChoice::SLG {
             max_size,
             expected_answers,
+            max_tables: None,
+        }
+    }
+
+    /// Returns SLG parameters that cap the number of tables the solver
+    /// may create for each goal. Goals that would need more tables flounder, which
+    /// keeps pathological programs from exhausting memory.
+    pub fn slg_with_max_tables(max_size: usize, max_tables: usize) -> Self {
+        SolverChoice::SLG {
+            max_size,
+            expected_answers: None,
+            max_tables: Some(max_tables),
         }
     }
 
     /// Returns the default SLG parameters.
+    ///
+    /// A `max_size` of 10 is enough for the types that show up in
+    /// typical programs, while keeping goals that grow without bound
+    /// (e.g. `Vec<Vec<Vec<..>>>`) cheap to truncate. Raising it allows
+    /// more precise answers for deeply nested types at the cost of
+    /// exploring more of the search space before floundering. No limit
+    /// on the number of expected answers is imposed.
     pub fn slg_default() -> Self {
         SolverChoice::slg(10, None)
     }
 
     /// Returns the default recursive solver setup.
+    ///
+    /// The recursive solver tracks the depth of its goal stack, so an
+    /// `overflow_depth` of 100 leaves plenty of room for legitimately deep
+    /// proofs while still catching runaway recursion. Since it truncates
+    /// answers less aggressively than SLG, it uses a larger `max_size` of
+    /// 30. Caching is enabled, which pays off when the same solver is used
+    /// for many related goals.
     pub fn recursive_default() -> Self {
         SolverChoice::Recursive {
             overflow_depth: 100,
@@ -96,7 +140,8 @@ impl SolverChoice {
             SolverChoice::SLG {
                 max_size,
                 expected_answers,
-            } => Box::new(SLGSolver::new(max_size, expected_answers)),
+                max_tables,
+            } => Box::new(SLGSolver::new(max_size, expected_answers, max_tables)),
             SolverChoice::Recursive {
                 overflow_depth,
                 caching_enabled,
@@ -116,6 +161,6 @@ impl SolverChoice {
 
 impl Default for SolverChoice {
     fn default() -> Self {
-        SolverChoice::slg(10, None)
+        SolverChoice::slg_default()
     }
 }
@@ -3,11 +3,11 @@ mod program_lowerer;
 
 use chalk_ir::cast::{Cast, Caster};
 use chalk_ir::{
-    self, BoundVar, ClausePriority, DebruijnIndex, ImplId, QuantifiedWhereClauses, Substitution,
-    TyVariableKind,
+    self, BoundVar, DebruijnIndex, ImplId, QuantifiedWhereClauses, Substitution, TyVariableKind,
 };
 use chalk_parse::ast::*;
 use chalk_solve::rust_ir::{self, IntoWhereClauses};
+use chalk_solve::split::Split;
 use program_lowerer::ProgramLowerer;
 use std::collections::BTreeMap;
 use string_cache::DefaultAtom as Atom;
@@ -106,6 +106,13 @@ lower_param_map!(
         Atom::from(SELF),
     ))
 );
+lower_param_map!(
+    TraitAliasDefn,
+    Some(chalk_ir::WithKind::new(
+        chalk_ir::VariableKind::Ty(TyVariableKind::General),
+        Atom::from(SELF),
+    ))
+);
 
 fn get_type_of_u32() -> chalk_ir::Ty<ChalkIr> {
     chalk_ir::TyKind::Scalar(chalk_ir::Scalar::Uint(chalk_ir::UintTy::U32)).intern(&ChalkIr)
@@ -115,7 +122,7 @@ impl Lower for VariableKind {
     type Lowered = chalk_ir::WithKind<ChalkIr, Ident>;
     fn lower(&self) -> Self::Lowered {
         let (kind, n) = match self {
-            VariableKind::Ty(n) => (
+            VariableKind::Ty(n) | VariableKind::MaybeSizedTy(n) => (
                 chalk_ir::VariableKind::Ty(chalk_ir::TyVariableKind::General),
                 n,
             ),
@@ -153,20 +160,63 @@ impl LowerWithEnv for WhereClause {
 
     /// Lower from an AST `where` clause to an internal IR.
     /// Some AST `where` clauses can lower to multiple ones, this is why we return a `Vec`.
-    /// As for now, this is the only the case for `where T: Foo<Item = U>` which lowers to
-    /// `Implemented(T: Foo)` and `ProjectionEq(<T as Foo>::Item = U)`.
+    /// This is the case for `where T: Foo<Item = U>` which lowers to
+    /// `Implemented(T: Foo)` and `ProjectionEq(<T as Foo>::Item = U)`, and
+    /// for `where T: Foo<Item: Bar>` which lowers to `Implemented(T: Foo)`
+    /// and `Implemented(<T as Foo>::Item: Bar)`. A trait reference may carry
+    /// several such bindings, each of which adds its own where clauses.
     fn lower(&self, env: &Env) -> LowerResult<Self::Lowered> {
         Ok(match self {
             WhereClause::Implemented { trait_ref } => {
                 vec![chalk_ir::WhereClause::Implemented(trait_ref.lower(env)?)]
             }
-            WhereClause::ProjectionEq { projection, ty } => vec![
-                chalk_ir::WhereClause::AliasEq(chalk_ir::AliasEq {
-                    alias: chalk_ir::AliasTy::Projection(projection.lower(env)?),
-                    ty: ty.lower(env)?,
-                }),
-                chalk_ir::WhereClause::Implemented(projection.trait_ref.lower(env)?),
-            ],
+            WhereClause::AssocTypeBindings {
+                trait_ref,
+                bindings,
+            } => {
+                let interner = env.interner();
+                let mut equalities = vec![];
+                let mut bounds = vec![];
+                for binding in bindings {
+                    match binding {
+                        AssocTypeBinding::Eq { name, args, ty } => {
+                            let projection = ProjectionTy {
+                                trait_ref: trait_ref.clone(),
+                                name: name.clone(),
+                                args: args.clone(),
+                            };
+                            equalities.push(chalk_ir::WhereClause::AliasEq(chalk_ir::AliasEq {
+                                alias: chalk_ir::AliasTy::Projection(projection.lower(env)?),
+                                ty: ty.lower(env)?,
+                            }));
+                        }
+                        AssocTypeBinding::Bounds {
+                            name,
+                            args,
+                            bounds: trait_bounds,
+                        } => {
+                            let projection = ProjectionTy {
+                                trait_ref: trait_ref.clone(),
+                                name: name.clone(),
+                                args: args.clone(),
+                            };
+                            let alias_ty = chalk_ir::TyKind::Alias(chalk_ir::AliasTy::Projection(
+                                projection.lower(env)?,
+                            ))
+                            .intern(interner);
+                            for bound in trait_bounds {
+                                bounds.push(chalk_ir::WhereClause::Implemented(
+                                    bound.lower(env)?.as_trait_ref(interner, alias_ty.clone()),
+                                ));
+                            }
+                        }
+                    }
+                }
+                let mut where_clauses = equalities;
+                where_clauses.push(chalk_ir::WhereClause::Implemented(trait_ref.lower(env)?));
+                where_clauses.extend(bounds);
+                where_clauses
+            }
             WhereClause::LifetimeOutlives { a, b } => {
                 vec![chalk_ir::WhereClause::LifetimeOutlives(
                     chalk_ir::LifetimeOutlives {
@@ -248,6 +298,9 @@ impl LowerWithEnv for DomainGoal {
             DomainGoal::ObjectSafe { id } => {
                 vec![chalk_ir::DomainGoal::ObjectSafe(env.lookup_trait(id)?)]
             }
+            DomainGoal::NotHolds { trait_ref } => vec![chalk_ir::DomainGoal::NotHolds(
+                chalk_ir::WhereClause::Implemented(trait_ref.lower(env)?),
+            )],
         })
     }
 }
@@ -281,12 +334,6 @@ impl LowerWithEnv for (&AdtDefn, chalk_ir::AdtId<ChalkIr>) {
     fn lower(&self, env: &Env) -> LowerResult<Self::Lowered> {
         let (adt_defn, adt_id) = self;
 
-        if adt_defn.flags.fundamental && adt_defn.all_parameters().len() < 1 {
-            Err(RustIrError::InvalidFundamentalTypesParameters(
-                adt_defn.name.clone(),
-            ))?;
-        }
-
         let binders = env.in_binders(adt_defn.all_parameters(), |env| {
             Ok(rust_ir::AdtDatumBound {
                 variants: adt_defn
@@ -298,7 +345,11 @@ impl LowerWithEnv for (&AdtDefn, chalk_ir::AdtId<ChalkIr>) {
                         Ok(rust_ir::AdtVariantDatum { fields: fields? })
                     })
                     .collect::<LowerResult<_>>()?,
-                where_clauses: adt_defn.where_clauses.lower(env)?,
+                where_clauses: env
+                    .implicit_sized_bounds(adt_defn.declared_parameters())?
+                    .into_iter()
+                    .chain(adt_defn.where_clauses.lower(env)?)
+                    .collect(),
             })
         })?;
 
@@ -340,7 +391,8 @@ impl LowerWithEnv for (&FnDefn, chalk_ir::FnDefId<ChalkIr>) {
         let (fn_defn, fn_def_id) = self;
 
         let binders = env.in_binders(fn_defn.all_parameters(), |env| {
-            let where_clauses = fn_defn.where_clauses.lower(env)?;
+            let mut where_clauses = env.implicit_sized_bounds(fn_defn.declared_parameters())?;
+            where_clauses.extend(fn_defn.where_clauses.lower(env)?);
 
             let inputs_and_output = env.in_binders(vec![], |env| {
                 let args: LowerResult<_> = fn_defn
@@ -383,7 +435,7 @@ impl Lower for FnSig {
 impl Lower for FnAbi {
     type Lowered = LowerResult<ChalkFnAbi>;
     fn lower(&self) -> Self::Lowered {
-        match self.0.as_ref() {
+        match self.0.str.as_ref() {
             "Rust" => Ok(ChalkFnAbi::Rust),
             "C" => Ok(ChalkFnAbi::C),
             _ => Err(RustIrError::InvalidExternAbi(self.0.clone())),
@@ -448,7 +500,7 @@ impl LowerWithEnv for TraitBound {
 
         let k = env.trait_kind(trait_id);
         if k.sort != TypeSort::Trait {
-            Err(RustIrError::NotTrait(self.trait_name.clone()))?;
+            Err(RustIrError::NotTraitSort(self.trait_name.clone(), k.sort))?;
         }
 
         let parameters = self
@@ -606,6 +658,7 @@ impl Lower for TraitFlags {
             fundamental: self.fundamental,
             non_enumerable: self.non_enumerable,
             coinductive: self.coinductive,
+            alias: false,
         }
     }
 }
@@ -751,10 +804,7 @@ impl LowerWithEnv for Ty {
                 }
             }
 
-            Ty::Projection { ref proj } => {
-                chalk_ir::TyKind::Alias(chalk_ir::AliasTy::Projection(proj.lower(env)?))
-                    .intern(interner)
-            }
+            Ty::Projection { ref proj } => chalk_ir::Ty::projection(interner, proj.lower(env)?),
 
             Ty::ForAll {
                 lifetime_names,
@@ -775,7 +825,7 @@ impl LowerWithEnv for Ty {
                     substitution: chalk_ir::FnSubst(Substitution::from_iter(interner, lowered_tys)),
                     sig: sig.lower()?,
                 };
-                chalk_ir::TyKind::Function(function).intern(interner)
+                chalk_ir::Ty::function(interner, function)
             }
             Ty::Tuple { ref types } => chalk_ir::TyKind::Tuple(
                 types.len(),
@@ -901,7 +951,8 @@ impl LowerWithEnv for (&Impl, ImplId<ChalkIr>, &AssociatedTyValueIds) {
                 ))?;
             }
 
-            let where_clauses = impl_.where_clauses.lower(&env)?;
+            let mut where_clauses = env.implicit_sized_bounds(impl_.declared_parameters())?;
+            where_clauses.extend(impl_.where_clauses.lower(&env)?);
             debug!(where_clauses = ?trait_ref);
             Ok(rust_ir::ImplDatumBound {
                 trait_ref,
@@ -933,40 +984,51 @@ impl LowerWithEnv for Clause {
     type Lowered = Vec<chalk_ir::ProgramClause<ChalkIr>>;
 
     fn lower(&self, env: &Env) -> LowerResult<Self::Lowered> {
-        let interner = env.interner();
-        let implications = env.in_binders(self.all_parameters(), |env| {
-            let consequences: Vec<chalk_ir::DomainGoal<ChalkIr>> = self.consequence.lower(env)?;
-
-            let conditions = chalk_ir::Goals::from_fallible(
-                interner,
-                // Subtle: in the SLG solver, we pop conditions from R to
-                // L. To preserve the expected order (L to R), we must
-                // therefore reverse.
-                self.conditions.iter().map(|g| g.lower(env)).rev(),
-            )?;
+        lower_clause(self, env, self.all_parameters(), None)
+    }
+}
 
-            let implications = consequences
-                .into_iter()
-                .map(|consequence| chalk_ir::ProgramClauseImplication {
-                    consequence,
-                    conditions: conditions.clone(),
-                    constraints: chalk_ir::Constraints::empty(interner),
-                    priority: ClausePriority::High,
-                })
-                .collect::<Vec<_>>();
-            Ok(implications)
-        })?;
+/// Lowers `clause`, quantified over `parameters`, adding
+/// `extra_condition` (if any) to its conditions.
+fn lower_clause(
+    clause: &Clause,
+    env: &Env,
+    parameters: Vec<chalk_ir::WithKind<ChalkIr, Ident>>,
+    extra_condition: Option<chalk_ir::Goal<ChalkIr>>,
+) -> LowerResult<Vec<chalk_ir::ProgramClause<ChalkIr>>> {
+    let interner = env.interner();
+    let implications = env.in_binders(parameters, |env| {
+        let consequences: Vec<chalk_ir::DomainGoal<ChalkIr>> = clause.consequence.lower(env)?;
+
+        let conditions = clause
+            .conditions
+            .iter()
+            .map(|g| g.lower(env))
+            .chain(extra_condition.map(Ok))
+            .collect::<LowerResult<Vec<_>>>()?;
 
-        let clauses = implications
+        let implications = consequences
             .into_iter()
-            .map(
-                |implication: chalk_ir::Binders<chalk_ir::ProgramClauseImplication<ChalkIr>>| {
-                    chalk_ir::ProgramClauseData(implication).intern(interner)
-                },
-            )
-            .collect();
-        Ok(clauses)
-    }
+            .map(|consequence| {
+                chalk_ir::ProgramClauseImplication::with_conditions(
+                    interner,
+                    consequence,
+                    conditions.iter().cloned(),
+                )
+            })
+            .collect::<Vec<_>>();
+        Ok(implications)
+    })?;
+
+    let clauses = implications
+        .into_iter()
+        .map(
+            |implication: chalk_ir::Binders<chalk_ir::ProgramClauseImplication<ChalkIr>>| {
+                chalk_ir::ProgramClauseData(implication).intern(interner)
+            },
+        )
+        .collect();
+    Ok(clauses)
 }
 
 impl LowerWithEnv for (&TraitDefn, chalk_ir::TraitId<ChalkIr>) {
@@ -977,7 +1039,7 @@ impl LowerWithEnv for (&TraitDefn, chalk_ir::TraitId<ChalkIr>) {
 
         let all_parameters = trait_defn.all_parameters();
         let all_parameters_len = all_parameters.len();
-        let binders = env.in_binders(all_parameters, |env| {
+        let binders = env.in_binders(all_parameters.clone(), |env| {
             if trait_defn.flags.auto {
                 if all_parameters_len > 1 {
                     Err(RustIrError::AutoTraitParameters(trait_defn.name.clone()))?;
@@ -987,9 +1049,11 @@ impl LowerWithEnv for (&TraitDefn, chalk_ir::TraitId<ChalkIr>) {
                 }
             }
 
-            Ok(rust_ir::TraitDatumBound {
-                where_clauses: trait_defn.where_clauses.lower(env)?,
-            })
+            // `Self` is not among the declared parameters, so it never
+            // gets an implicit `Sized` bound.
+            let mut where_clauses = env.implicit_sized_bounds(trait_defn.declared_parameters())?;
+            where_clauses.extend(trait_defn.where_clauses.lower(env)?);
+            Ok(rust_ir::TraitDatumBound { where_clauses })
         })?;
 
         let associated_ty_ids: Vec<_> = trait_defn
@@ -998,12 +1062,85 @@ impl LowerWithEnv for (&TraitDefn, chalk_ir::TraitId<ChalkIr>) {
             .map(|defn| env.lookup_associated_ty(*trait_id, &defn.name).unwrap().id)
             .collect();
 
+        // A provided clause `C :- G` becomes `forall<Self, P..> { C :- G, Self: Trait<P..> }`,
+        // with the trait's parameters coming before the clause's own ones.
+        let interner = env.interner();
+        let implemented: chalk_ir::Goal<ChalkIr> = chalk_ir::TraitRef {
+            trait_id: *trait_id,
+            substitution: binders.identity_substitution(interner),
+        }
+        .cast(interner);
+        let provided_clauses = trait_defn
+            .provided_clauses
+            .iter()
+            .map(|clause| {
+                let parameters = all_parameters
+                    .iter()
+                    .cloned()
+                    .chain(clause.all_parameters())
+                    .collect();
+                lower_clause(clause, env, parameters, Some(implemented.clone()))
+            })
+            .collect::<LowerResult<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .collect();
+
         let trait_datum = rust_ir::TraitDatum {
             id: *trait_id,
             binders,
             flags: trait_defn.flags.lower(),
             associated_ty_ids,
             well_known: trait_defn.well_known.map(|def| def.lower()),
+            provided_clauses,
+        };
+
+        debug!(?trait_datum);
+
+        Ok(trait_datum)
+    }
+}
+
+impl LowerWithEnv for (&TraitAliasDefn, chalk_ir::TraitId<ChalkIr>) {
+    type Lowered = rust_ir::TraitDatum<ChalkIr>;
+
+    fn lower(&self, env: &Env) -> LowerResult<Self::Lowered> {
+        let (alias_defn, trait_id) = self;
+
+        // `trait Alias = Foo + Bar` is lowered like a trait with the
+        // where clauses `Self: Foo, Self: Bar`.
+        let binders = env.in_binders(alias_defn.all_parameters(), |env| {
+            let interner = env.interner();
+            // `Self` is always the first parameter of a trait.
+            let self_ty = chalk_ir::TyKind::BoundVar(BoundVar::new(DebruijnIndex::INNERMOST, 0))
+                .intern(interner);
+            let mut where_clauses: Vec<_> = alias_defn
+                .bounds
+                .lower(env)?
+                .iter()
+                .flat_map(|qil| qil.into_where_clauses(interner, self_ty.clone()))
+                .collect();
+            where_clauses.extend(alias_defn.where_clauses.lower(env)?);
+
+            Ok(rust_ir::TraitDatumBound { where_clauses })
+        })?;
+
+        // Trait aliases get no implicit `Sized` bounds.
+        let trait_datum = rust_ir::TraitDatum {
+            id: *trait_id,
+            binders,
+            flags: rust_ir::TraitFlags {
+                auto: false,
+                marker: false,
+                upstream: false,
+                fundamental: false,
+                non_enumerable: false,
+                coinductive: false,
+                alias: true,
+            },
+            associated_ty_ids: vec![],
+            well_known: None,
+            provided_clauses: vec![],
         };
 
         debug!(?trait_datum);
@@ -1012,20 +1149,46 @@ impl LowerWithEnv for (&TraitDefn, chalk_ir::TraitId<ChalkIr>) {
     }
 }
 
+/// Options for `lower_goal_with_options`.
+#[derive(Copy, Clone, Debug)]
+pub struct GoalLoweringOptions {
+    /// Goals with more nested `forall`/`exists` quantifiers than this are
+    /// rejected.
+    pub max_quantifier_depth: usize,
+    /// Whether the hypotheses of `if` goals are lowered to `FromEnv`
+    /// clauses, so that their implied bounds (e.g. supertraits) hold too.
+    /// If not, they are lowered as plain clauses.
+    pub elaborate_implied_bounds: bool,
+}
+
+impl Default for GoalLoweringOptions {
+    fn default() -> Self {
+        GoalLoweringOptions {
+            max_quantifier_depth: DEFAULT_MAX_QUANTIFIER_DEPTH,
+            elaborate_implied_bounds: true,
+        }
+    }
+}
+
 pub fn lower_goal(goal: &Goal, program: &LoweredProgram) -> LowerResult<chalk_ir::Goal<ChalkIr>> {
-    let interner = &ChalkIr;
+    lower_goal_with_options(goal, program, GoalLoweringOptions::default())
+}
+
+/// Like `lower_goal`, but with non-default `options`.
+pub fn lower_goal_with_options(
+    goal: &Goal,
+    program: &LoweredProgram,
+    options: GoalLoweringOptions,
+) -> LowerResult<chalk_ir::Goal<ChalkIr>> {
     let associated_ty_lookups: BTreeMap<_, _> = program
         .associated_ty_data
         .iter()
         .map(|(&associated_ty_id, datum)| {
-            let trait_datum = &program.trait_data[&datum.trait_id];
-            let num_trait_params = trait_datum.binders.len(interner);
-            let num_addl_params = datum.binders.len(interner) - num_trait_params;
-            let addl_variable_kinds =
-                datum.binders.binders.as_slice(interner)[..num_addl_params].to_owned();
             let lookup = AssociatedTyLookup {
                 id: associated_ty_id,
-                addl_variable_kinds,
+                addl_variable_kinds: program
+                    .associated_ty_info(associated_ty_id)
+                    .associated_ty_parameter_kinds,
             };
             ((datum.trait_id, datum.name.clone()), lookup)
         })
@@ -1054,6 +1217,13 @@ pub fn lower_goal(goal: &Goal, program: &LoweredProgram) -> LowerResult<chalk_ir
         foreign_ty_ids: &program.foreign_ty_ids,
         parameter_map: BTreeMap::new(),
         auto_traits: &auto_traits,
+        quantifier_depth: 0,
+        sized_trait_id: program
+            .well_known_traits
+            .get(&rust_ir::WellKnownTrait::Sized)
+            .copied(),
+        max_quantifier_depth: options.max_quantifier_depth,
+        elaborate_implied_bounds: options.elaborate_implied_bounds,
     };
 
     goal.lower(&env)
@@ -1072,13 +1242,22 @@ impl LowerWithEnv for Goal {
                 // `T: Trait<Assoc = U>` to `FromEnv(T: Trait)` and `FromEnv(T: Trait<Assoc = U>)`
                 // in the assumptions of an `if` goal, e.g. `if (T: Trait) { ... }` lowers to
                 // `if (FromEnv(T: Trait)) { ... /* this part is untouched */ ... }`.
+                // This can be turned off for comparison.
                 let where_clauses = hyp
                     .iter()
                     .flat_map(|clause| match clause.lower(env) {
                         Ok(v) => v.into_iter().map(Ok).collect(),
                         Err(e) => vec![Err(e)],
                     })
-                    .map(|result| result.map(|h| h.into_from_env_clause(interner)));
+                    .map(|result| {
+                        result.map(|h| {
+                            if env.elaborate_implied_bounds {
+                                h.into_from_env_clause(interner)
+                            } else {
+                                h
+                            }
+                        })
+                    });
                 let where_clauses =
                     chalk_ir::ProgramClauses::from_fallible(interner, where_clauses);
                 Ok(chalk_ir::GoalData::Implies(where_clauses?, g.lower(env)?).intern(interner))
@@ -1092,6 +1271,7 @@ impl LowerWithEnv for Goal {
             }
             Goal::Not(g) => Ok(chalk_ir::GoalData::Not(g.lower(env)?).intern(interner)),
             Goal::Compatible(g) => Ok(g.lower(env)?.compatible(interner)),
+            Goal::Reveal(g) => Ok(g.lower(env)?.reveal(interner)),
             Goal::Leaf(leaf) => {
                 // A where clause can lower to multiple leaf goals; wrap these in Goal::And.
                 Ok(leaf.lower(env)?)
@@ -1111,8 +1291,29 @@ impl LowerWithEnv for (&Goal, chalk_ir::QuantifierKind, &Vec<VariableKind>) {
             return goal.lower(env);
         }
 
+        if env.quantifier_depth >= env.max_quantifier_depth {
+            let identifier = match &variable_kinds[0] {
+                VariableKind::Ty(n)
+                | VariableKind::MaybeSizedTy(n)
+                | VariableKind::IntegerTy(n)
+                | VariableKind::FloatTy(n)
+                | VariableKind::Lifetime(n)
+                | VariableKind::Const(n) => n.clone(),
+            };
+            return Err(RustIrError::QuantifierNestingTooDeep {
+                identifier,
+                limit: env.max_quantifier_depth,
+            });
+        }
+
         let variable_kinds = variable_kinds.iter().map(|k| k.lower());
-        let subgoal = env.in_binders(variable_kinds, |env| goal.lower(env))?;
+        let subgoal = env.in_binders(variable_kinds, |env| {
+            let env = Env {
+                quantifier_depth: env.quantifier_depth + 1,
+                ..env.clone()
+            };
+            goal.lower(&env)
+        })?;
         Ok(chalk_ir::GoalData::Quantified(*quantifier_kind, subgoal).intern(interner))
     }
 }
@@ -54,8 +54,21 @@ pub struct Env<'k> {
     /// GenericArg identifiers are used as keys, therefore
     /// all identifiers in an environment must be unique (no shadowing).
     pub parameter_map: ParameterMap,
+    /// The `#[lang(sized)]` trait, if the program declares one. Type
+    /// parameters get an implicit bound on it unless declared `?Sized`.
+    pub sized_trait_id: Option<TraitId<ChalkIr>>,
+    /// The number of quantified goals (`forall`/`exists`) we are nested in.
+    pub quantifier_depth: usize,
+    /// The maximum allowed `quantifier_depth`; deeper goals are rejected with
+    /// `RustIrError::QuantifierNestingTooDeep` rather than overflowing the stack.
+    pub max_quantifier_depth: usize,
+    /// Whether the hypotheses of `if` goals are lowered to `FromEnv` clauses.
+    pub elaborate_implied_bounds: bool,
 }
 
+/// The default `Env::max_quantifier_depth`.
+pub const DEFAULT_MAX_QUANTIFIER_DEPTH: usize = 256;
+
 /// Information about an associated type **declaration** (i.e., an
 /// `AssociatedTyDatum`). This information is gathered in the first
 /// phase of creating the Rust IR and is then later used to lookup the
@@ -177,15 +190,57 @@ impl Env<'_> {
     }
 
     pub fn lookup_trait(&self, name: &Identifier) -> LowerResult<TraitId<ChalkIr>> {
-        if let Some(_) = self.parameter_map.get(&name.str) {
-            Err(RustIrError::NotTrait(name.clone()))
-        } else if let Some(_) = self.adt_ids.get(&name.str) {
-            Err(RustIrError::NotTrait(name.clone()))
-        } else if let Some(id) = self.trait_ids.get(&name.str) {
-            Ok(*id)
-        } else {
-            Err(RustIrError::InvalidTraitName(name.clone()))
+        if self.parameter_map.contains_key(&name.str) {
+            return Err(RustIrError::NotTrait(name.clone()));
         }
+        if let Some(id) = self.trait_ids.get(&name.str) {
+            return Ok(*id);
+        }
+        let sort = match self.lookup_type(name) {
+            Ok(TypeLookup::Adt(id)) => self.adt_kind(id).sort,
+            Ok(TypeLookup::FnDef(id)) => self.fn_def_kind(id).sort,
+            Ok(TypeLookup::Closure(id)) => self.closure_kind(id).sort,
+            Ok(TypeLookup::Generator(id)) => self.generator_kind(id).sort,
+            Ok(TypeLookup::Opaque(id)) => self.opaque_kind(id).sort,
+            Ok(TypeLookup::Parameter(_))
+            | Ok(TypeLookup::Foreign(_))
+            | Ok(TypeLookup::Trait(_)) => return Err(RustIrError::NotTrait(name.clone())),
+            Err(_) => return Err(RustIrError::InvalidTraitName(name.clone())),
+        };
+        Err(RustIrError::NotTraitSort(name.clone(), sort))
+    }
+
+    /// The implicit `T: Sized` bounds for the type parameters among
+    /// `parameters`, which must already be in scope. Parameters declared
+    /// `?Sized`, as well as integer and float variables, get none; nor
+    /// does anything if the program has no `#[lang(sized)]` trait.
+    pub fn implicit_sized_bounds(
+        &self,
+        parameters: &[VariableKind],
+    ) -> LowerResult<Vec<chalk_ir::QuantifiedWhereClause<ChalkIr>>> {
+        let interner = self.interner();
+        let sized_trait_id = match self.sized_trait_id {
+            Some(id) => id,
+            None => return Ok(vec![]),
+        };
+        parameters
+            .iter()
+            .filter_map(|parameter| match parameter {
+                VariableKind::Ty(name) => Some(name),
+                _ => None,
+            })
+            .map(|name| {
+                self.in_binders(vec![], |env| {
+                    Ok(chalk_ir::WhereClause::Implemented(chalk_ir::TraitRef {
+                        trait_id: sized_trait_id,
+                        substitution: chalk_ir::Substitution::from1(
+                            interner,
+                            env.lookup_generic_arg(name)?,
+                        ),
+                    }))
+                })
+            })
+            .collect()
     }
 
     pub fn trait_kind(&self, id: chalk_ir::TraitId<ChalkIr>) -> &TypeKind {
@@ -39,7 +39,9 @@ pub(super) struct ProgramLowerer {
     trait_kinds: TraitKinds,
     opaque_ty_kinds: OpaqueTyVariableKinds,
     object_safe_traits: HashSet<TraitId<ChalkIr>>,
+    trait_aliases: HashSet<TraitId<ChalkIr>>,
     foreign_ty_ids: ForeignIds,
+    sized_trait_id: Option<TraitId<ChalkIr>>,
 }
 
 impl ProgramLowerer {
@@ -117,6 +119,17 @@ impl ProgramLowerer {
                     if defn.flags.object_safe {
                         self.object_safe_traits.insert(id);
                     }
+                    if defn.well_known == Some(WellKnownTrait::Sized) {
+                        self.sized_trait_id = Some(id);
+                    }
+                }
+                Item::TraitAliasDefn(defn) => {
+                    let type_kind = defn.lower_type_kind()?;
+                    let id = TraitId(raw_id);
+                    self.trait_ids.insert(type_kind.name.clone(), id);
+                    self.trait_kinds.insert(id, type_kind);
+                    self.auto_traits.insert(id, false);
+                    self.trait_aliases.insert(id);
                 }
                 Item::OpaqueTyDefn(defn) => {
                     let type_kind = defn.lower_type_kind()?;
@@ -178,6 +191,10 @@ impl ProgramLowerer {
                 parameter_map: BTreeMap::new(),
                 auto_traits: &self.auto_traits,
                 foreign_ty_ids: &self.foreign_ty_ids,
+                sized_trait_id: self.sized_trait_id,
+                quantifier_depth: 0,
+                max_quantifier_depth: DEFAULT_MAX_QUANTIFIER_DEPTH,
+                elaborate_implied_bounds: true,
             };
 
             match *item {
@@ -310,13 +327,23 @@ impl ProgramLowerer {
                         );
                     }
                 }
+                Item::TraitAliasDefn(ref alias_defn) => {
+                    let trait_id = TraitId(raw_id);
+                    let trait_datum = (alias_defn, trait_id).lower(&empty_env)?;
+                    trait_data.insert(trait_id, Arc::new(trait_datum));
+                }
                 Item::Impl(ref impl_defn) => {
                     let impl_id = ImplId(raw_id);
                     let impl_datum = Arc::new(
                         (impl_defn, impl_id, &self.associated_ty_value_ids).lower(&empty_env)?,
                     );
-                    impl_data.insert(impl_id, impl_datum.clone());
                     let trait_id = impl_datum.trait_id();
+                    if self.trait_aliases.contains(&trait_id) {
+                        return Err(RustIrError::TraitAliasImpl(
+                            impl_defn.trait_ref.trait_name.clone(),
+                        ));
+                    }
+                    impl_data.insert(impl_id, impl_datum.clone());
 
                     for atv in &impl_defn.assoc_ty_values {
                         let atv_id = self.associated_ty_value_ids[&(impl_id, atv.name.str.clone())];
@@ -333,6 +360,7 @@ impl ProgramLowerer {
                         let value = empty_env.in_binders(variable_kinds, |env| {
                             Ok(rust_ir::AssociatedTyValueBound {
                                 ty: atv.value.lower(env)?,
+                                where_clauses: atv.where_clauses.lower(env)?,
                             })
                         })?;
 
@@ -525,7 +553,23 @@ macro_rules! lower_type_kind {
     };
 }
 
-lower_type_kind!(AdtDefn, Adt, |defn: &AdtDefn| defn.all_parameters());
+impl LowerTypeKind for AdtDefn {
+    fn lower_type_kind(&self) -> LowerResult<TypeKind> {
+        Ok(TypeKind {
+            sort: match self.flags.kind {
+                AdtKind::Struct => TypeSort::Struct,
+                AdtKind::Enum => TypeSort::Enum,
+                AdtKind::Union => TypeSort::Union,
+            },
+            name: self.name.str.clone(),
+            binders: chalk_ir::Binders::new(
+                VariableKinds::from_iter(&ChalkIr, self.all_parameters().anonymize()),
+                crate::Unit,
+            ),
+        })
+    }
+}
+
 lower_type_kind!(FnDefn, FnDef, |defn: &FnDefn| defn.all_parameters());
 lower_type_kind!(ClosureDefn, Closure, |defn: &ClosureDefn| defn
     .all_parameters());
@@ -539,6 +583,11 @@ lower_type_kind!(TraitDefn, Trait, |defn: &TraitDefn| defn
     .iter()
     .map(|k| k.lower())
     .collect::<Vec<_>>());
+lower_type_kind!(TraitAliasDefn, Trait, |defn: &TraitAliasDefn| defn
+    .variable_kinds
+    .iter()
+    .map(|k| k.lower())
+    .collect::<Vec<_>>());
 lower_type_kind!(OpaqueTyDefn, Opaque, |defn: &OpaqueTyDefn| defn
     .variable_kinds
     .iter()
@@ -8,7 +8,7 @@ use crate::program::Program;
 use crate::program_environment::ProgramEnvironment;
 use crate::tls;
 use crate::SolverChoice;
-use chalk_ir::{Substitution, TraitId};
+use chalk_ir::{ImplId, Substitution, TraitId};
 use chalk_solve::clauses::builder::ClauseBuilder;
 use chalk_solve::clauses::program_clauses::ToProgramClauses;
 use chalk_solve::coherence::orphan;
@@ -34,6 +34,11 @@ pub trait LoweringDatabase:
     #[salsa::input]
     fn solver_choice(&self) -> SolverChoice;
 
+    /// Whether to treat all crates as one, disabling the orphan rules; see
+    /// `RustIrDatabase::is_whole_program`.
+    #[salsa::input]
+    fn whole_program(&self) -> bool;
+
     fn program_ir(&self) -> Result<Arc<Program>, ChalkError>;
 
     /// Performs coherence check and computes which impls specialize
@@ -47,6 +52,16 @@ pub trait LoweringDatabase:
     /// The lowered IR, with coherence, orphan, and WF checks performed.
     fn checked_program(&self) -> Result<Arc<Program>, ChalkError>;
 
+    /// The impls whose where clauses can never be satisfied, and which
+    /// therefore never apply.
+    fn dead_impls(&self) -> Result<Vec<ImplId<ChalkIr>>, ChalkError>;
+
+    /// The where clauses declared on traits and types that can never be
+    /// satisfied, making the trait or type unusable.
+    fn unsatisfiable_where_clauses(
+        &self,
+    ) -> Result<Vec<wf::UnsatisfiableWhereClause<ChalkIr>>, ChalkError>;
+
     /// The program as logic.
     fn environment(&self) -> Result<Arc<ProgramEnvironment>, ChalkError>;
 
@@ -130,14 +145,13 @@ fn program_ir(db: &dyn LoweringDatabase) -> Result<Arc<Program>, ChalkError> {
 fn orphan_check(db: &dyn LoweringDatabase) -> Result<(), ChalkError> {
     let program = db.program_ir()?;
 
-    tls::set_current_program(&program, || -> Result<(), ChalkError> {
-        let local_impls = program.local_impl_ids();
-        for impl_id in local_impls {
-            let mut solver = db.solver_choice().into_solver();
-            orphan::perform_orphan_check::<ChalkIr>(db.upcast(), &mut *solver, impl_id)?;
-        }
-        Ok(())
-    })
+    let _guard = tls::set_current_program(&program);
+    let local_impls = program.local_impl_ids();
+    for impl_id in local_impls {
+        let mut solver = db.solver_choice().into_solver();
+        orphan::perform_orphan_check::<ChalkIr>(db.upcast(), &mut *solver, impl_id)?;
+    }
+    Ok(())
 }
 
 fn coherence(
@@ -145,9 +159,10 @@ fn coherence(
 ) -> Result<BTreeMap<TraitId<ChalkIr>, Arc<SpecializationPriorities<ChalkIr>>>, ChalkError> {
     let program = db.program_ir()?;
     let solver_choice = db.solver_choice();
-    let priorities_map = tls::set_current_program(&program, || -> Result<_, ChalkError> {
+    let priorities_map = {
+        let _guard = tls::set_current_program(&program);
         let solver_builder = || solver_choice.into_solver();
-        let priorities_map: Result<BTreeMap<_, _>, ChalkError> = program
+        program
             .trait_data
             .keys()
             .map(|&trait_id| {
@@ -156,39 +171,110 @@ fn coherence(
                 let priorities = solver.specialization_priorities()?;
                 Ok((trait_id, priorities))
             })
-            .collect();
-        let priorities_map = priorities_map?;
-        Ok(priorities_map)
-    });
+            .collect::<Result<BTreeMap<_, _>, ChalkError>>()
+    };
     let () = db.orphan_check()?;
     priorities_map
 }
 
 fn checked_program(db: &dyn LoweringDatabase) -> Result<Arc<Program>, ChalkError> {
     let program = db.program_ir()?;
+    match check_program(db, &program, true).into_iter().next() {
+        Some(error) => Err(error),
+        None => Ok(program),
+    }
+}
 
-    db.coherence()?;
-
+/// Runs the coherence, orphan and WF checks on `program`, in that order,
+/// and returns the errors they find. With `stop_at_first`, no checks are
+/// run after the first error.
+pub(crate) fn check_program(
+    db: &dyn LoweringDatabase,
+    program: &Arc<Program>,
+    stop_at_first: bool,
+) -> Vec<ChalkError> {
     let solver_choice = db.solver_choice();
-    let () = tls::set_current_program(&program, || -> Result<(), ChalkError> {
-        let solver_builder = || solver_choice.into_solver();
-        let solver: wf::WfSolver<ChalkIr> = wf::WfSolver::new(db.upcast(), &solver_builder);
-        for &id in program.adt_data.keys() {
-            solver.verify_adt_decl(id)?;
-        }
+    let _guard = tls::set_current_program(program);
+    let solver_builder = || solver_choice.into_solver();
+    let mut errors: Vec<ChalkError> = vec![];
+    macro_rules! check {
+        ($result:expr) => {
+            if let Err(error) = $result {
+                errors.push(error.into());
+                if stop_at_first {
+                    return errors;
+                }
+            }
+        };
+    }
 
-        for &opaque_ty_id in program.opaque_ty_data.keys() {
-            solver.verify_opaque_ty_decl(opaque_ty_id)?;
-        }
+    for &trait_id in program.trait_data.keys() {
+        let solver = CoherenceSolver::new(db.upcast(), &solver_builder, trait_id);
+        check!(solver.specialization_priorities());
+    }
 
-        for &impl_id in program.impl_data.keys() {
-            solver.verify_trait_impl(impl_id)?;
-        }
+    for impl_id in program.local_impl_ids() {
+        let mut solver = solver_builder();
+        check!(orphan::perform_orphan_check(
+            db.upcast(),
+            &mut *solver,
+            impl_id
+        ));
+    }
+
+    let solver: wf::WfSolver<ChalkIr> = wf::WfSolver::new(db.upcast(), &solver_builder);
+    for &id in program.adt_data.keys() {
+        check!(solver.verify_adt_decl(id));
+    }
 
-        Ok(())
-    })?;
+    for &opaque_ty_id in program.opaque_ty_data.keys() {
+        check!(solver.verify_opaque_ty_decl(opaque_ty_id));
+    }
+
+    for &impl_id in program.impl_data.keys() {
+        check!(solver.verify_trait_impl(impl_id));
+    }
 
-    Ok(program)
+    errors
+}
+
+fn dead_impls(db: &dyn LoweringDatabase) -> Result<Vec<ImplId<ChalkIr>>, ChalkError> {
+    let program = db.checked_program()?;
+    let solver_choice = db.solver_choice();
+    let _guard = tls::set_current_program(&program);
+    let solver_builder = || solver_choice.into_solver();
+    let solver: wf::WfSolver<ChalkIr> = wf::WfSolver::new(db.upcast(), &solver_builder);
+    let dead_impls = program
+        .impl_data
+        .keys()
+        .copied()
+        .filter(|&impl_id| solver.is_dead_impl(impl_id))
+        .collect();
+
+    Ok(dead_impls)
+}
+
+fn unsatisfiable_where_clauses(
+    db: &dyn LoweringDatabase,
+) -> Result<Vec<wf::UnsatisfiableWhereClause<ChalkIr>>, ChalkError> {
+    let program = db.checked_program()?;
+    let solver_choice = db.solver_choice();
+    let _guard = tls::set_current_program(&program);
+    let solver_builder = || solver_choice.into_solver();
+    let solver: wf::WfSolver<ChalkIr> = wf::WfSolver::new(db.upcast(), &solver_builder);
+    let where_clauses = program
+        .trait_data
+        .keys()
+        .flat_map(|&trait_id| solver.unsatisfiable_trait_where_clauses(trait_id))
+        .chain(
+            program
+                .adt_data
+                .keys()
+                .flat_map(|&adt_id| solver.unsatisfiable_adt_where_clauses(adt_id)),
+        )
+        .collect();
+
+    Ok(where_clauses)
 }
 
 fn environment(db: &dyn LoweringDatabase) -> Result<Arc<ProgramEnvironment>, ChalkError> {
@@ -198,7 +284,7 @@ fn environment(db: &dyn LoweringDatabase) -> Result<Arc<ProgramEnvironment>, Cha
     // of the data above that always has the form:
     //
     //       forall P0...Pn. Something :- Conditions
-    let mut program_clauses = program.custom_clauses.clone();
+    let mut program_clauses = program.custom_clauses();
 
     let builder = &mut ClauseBuilder::new(db.upcast(), &mut program_clauses);
 
@@ -162,15 +162,23 @@ pub fn with_current_program<R>(op: impl FnOnce(Option<&Arc<dyn DebugContext>>) -
     })
 }
 
-pub fn set_current_program<OP, R>(p: &Arc<impl DebugContext + 'static>, op: OP) -> R
-where
-    OP: FnOnce() -> R,
-{
+/// Makes `p` the current program until the returned guard is dropped,
+/// at which point whichever program was current before is restored.
+pub fn set_current_program(p: &Arc<impl DebugContext + 'static>) -> ProgramGuard {
     let p: Arc<dyn DebugContext> = p.clone();
-    PROGRAM.with(|prog_cell| {
-        *prog_cell.borrow_mut() = Some(p);
-        let r = op();
-        *prog_cell.borrow_mut() = None;
-        r
-    })
+    let previous = PROGRAM.with(|prog_cell| prog_cell.borrow_mut().replace(p));
+    ProgramGuard { previous }
+}
+
+/// Returned by `set_current_program`; restores the previous program on drop.
+#[must_use = "the program is only current until the guard is dropped"]
+pub struct ProgramGuard {
+    previous: Option<Arc<dyn DebugContext>>,
+}
+
+impl Drop for ProgramGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        PROGRAM.with(|prog_cell| *prog_cell.borrow_mut() = previous);
+    }
 }
@@ -217,6 +217,7 @@ where
             conditions: Goals::empty(interner),
             constraints: Constraints::empty(interner),
             priority: ClausePriority::High,
+            impl_id: None,
         };
 
         ProgramClauseData(Binders::empty(interner, implication.shifted_in(interner)))
@@ -235,6 +236,7 @@ where
             conditions: Goals::empty(interner),
             constraints: Constraints::empty(interner),
             priority: ClausePriority::High,
+            impl_id: None,
         }))
         .intern(interner)
     }
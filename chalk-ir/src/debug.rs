@@ -818,6 +818,7 @@ impl<I: Interner> Debug for DomainGoal<I> {
             DomainGoal::DownstreamType(n) => write!(fmt, "DownstreamType({:?})", n),
             DomainGoal::Reveal => write!(fmt, "Reveal"),
             DomainGoal::ObjectSafe(n) => write!(fmt, "ObjectSafe({:?})", n),
+            DomainGoal::NotHolds(n) => write!(fmt, "NotHolds({:?})", n),
         }
     }
 }
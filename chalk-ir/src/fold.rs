@@ -6,9 +6,11 @@ use std::fmt::Debug;
 mod binder_impls;
 mod boring_impls;
 mod in_place;
+mod placeholders;
 pub mod shift;
 mod subst;
 
+pub(crate) use self::placeholders::ClosePlaceholders;
 pub use self::shift::Shift;
 pub use self::subst::Subst;
 
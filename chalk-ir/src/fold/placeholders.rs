@@ -0,0 +1,71 @@
+use super::*;
+
+/// Folder that turns the placeholders of one universe back into bound
+/// variables; the inverse of instantiating a binder with placeholders.
+pub(crate) struct ClosePlaceholders<'i, I: Interner> {
+    universe: UniverseIndex,
+    interner: &'i I,
+}
+
+impl<I: Interner> ClosePlaceholders<'_, I> {
+    /// Replaces each placeholder `!U.i` in `value`, where `U` is
+    /// `universe`, with the `i`th variable of a binder just outside of
+    /// `value`. Free bound variables in `value` are left untouched, so
+    /// the caller must shift them in first.
+    pub(crate) fn apply<T: Fold<I>>(interner: &I, universe: UniverseIndex, value: T) -> T::Result {
+        value
+            .fold_with(
+                &mut ClosePlaceholders { universe, interner },
+                DebruijnIndex::INNERMOST,
+            )
+            .unwrap()
+    }
+}
+
+impl<'i, I: Interner> Folder<'i, I> for ClosePlaceholders<'i, I> {
+    fn as_dyn(&mut self) -> &mut dyn Folder<'i, I> {
+        self
+    }
+
+    fn fold_free_placeholder_ty(
+        &mut self,
+        universe: PlaceholderIndex,
+        outer_binder: DebruijnIndex,
+    ) -> Fallible<Ty<I>> {
+        if universe.ui == self.universe {
+            Ok(BoundVar::new(outer_binder, universe.idx).to_ty(self.interner()))
+        } else {
+            Ok(universe.to_ty(self.interner()))
+        }
+    }
+
+    fn fold_free_placeholder_lifetime(
+        &mut self,
+        universe: PlaceholderIndex,
+        outer_binder: DebruijnIndex,
+    ) -> Fallible<Lifetime<I>> {
+        if universe.ui == self.universe {
+            Ok(BoundVar::new(outer_binder, universe.idx).to_lifetime(self.interner()))
+        } else {
+            Ok(universe.to_lifetime(self.interner()))
+        }
+    }
+
+    fn fold_free_placeholder_const(
+        &mut self,
+        ty: Ty<I>,
+        universe: PlaceholderIndex,
+        outer_binder: DebruijnIndex,
+    ) -> Fallible<Const<I>> {
+        let ty = ty.fold_with(self.as_dyn(), outer_binder)?;
+        if universe.ui == self.universe {
+            Ok(BoundVar::new(outer_binder, universe.idx).to_const(self.interner(), ty))
+        } else {
+            Ok(universe.to_const(self.interner(), ty))
+        }
+    }
+
+    fn interner(&self) -> &'i I {
+        self.interner
+    }
+}
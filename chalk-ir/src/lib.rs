@@ -8,9 +8,10 @@ extern crate self as chalk_ir;
 
 use crate::cast::{Cast, CastTo, Caster};
 use crate::fold::shift::Shift;
-use crate::fold::{Fold, Folder, Subst, SuperFold};
+use crate::fold::{ClosePlaceholders, Fold, Folder, Subst, SuperFold};
 use crate::visit::{ControlFlow, SuperVisit, Visit, VisitExt, Visitor};
 use chalk_derive::{Fold, HasInterner, SuperVisit, Visit, Zip};
+use rustc_hash::FxHashSet;
 use std::marker::PhantomData;
 
 pub use crate::debug::SeparatorTraitRef;
@@ -164,17 +165,58 @@ impl<I: Interner> Environment<I> {
         }
     }
 
-    /// Adds (an iterator of) clauses to the environment.
+    /// Adds (an iterator of) clauses to the environment. Clauses that
+    /// are already in the environment are skipped, so adding the same
+    /// clause twice leaves the environment unchanged; the remaining
+    /// clauses are kept in the order they were added.
     pub fn add_clauses<II>(&self, interner: &I, clauses: II) -> Self
     where
         II: IntoIterator<Item = ProgramClause<I>>,
     {
+        let mut all_clauses = self.clauses.as_slice(interner).to_vec();
+        let mut seen: FxHashSet<_> = all_clauses.iter().cloned().collect();
+        all_clauses.extend(
+            clauses
+                .into_iter()
+                .filter(|clause| seen.insert(clause.clone())),
+        );
+
         let mut env = self.clone();
-        env.clauses =
-            ProgramClauses::from_iter(interner, env.clauses.iter(interner).cloned().chain(clauses));
+        env.clauses = ProgramClauses::from_iter(interner, all_clauses);
         env
     }
 
+    /// True if the environment assumes that `goal` does not hold, i.e. if
+    /// `goal` is a where clause `WC` and the environment contains the fact
+    /// `NotHolds(WC)`.
+    pub fn assumes_not_holds(&self, interner: &I, goal: &Goal<I>) -> bool {
+        let where_clause = match goal.data(interner) {
+            GoalData::DomainGoal(DomainGoal::Holds(wc)) => wc,
+            _ => return false,
+        };
+
+        self.clauses.iter(interner).any(|clause| {
+            let ProgramClauseData(implication) = clause.data(interner);
+            let ProgramClauseImplication {
+                consequence,
+                conditions,
+                constraints,
+                ..
+            } = implication.skip_binders();
+            match consequence {
+                DomainGoal::NotHolds(wc)
+                    if conditions.is_empty(interner) && constraints.is_empty(interner) =>
+                {
+                    // The fact is under the clause's binders, so shift it out
+                    // to compare it with the goal. This fails (and hence the
+                    // fact doesn't apply) if it refers to those binders.
+                    wc.clone().shifted_out(interner).as_ref() == Ok(where_clause)
+                }
+                _ => false,
+            }
+        })
+    }
+
     /// True if any of the clauses in the environment have a consequence of `Compatible`.
     /// Panics if the conditions or constraints of that clause are not empty.
     pub fn has_compatible_clause(&self, interner: &I) -> bool {
@@ -228,6 +270,18 @@ impl<G: HasInterner> InEnvironment<G> {
             goal: op(self.goal),
         }
     }
+
+    /// Maps the environment without touching the goal, e.g. to add
+    /// clauses to it.
+    pub fn map_env<OP>(self, op: OP) -> Self
+    where
+        OP: FnOnce(Environment<G::Interner>) -> Environment<G::Interner>,
+    {
+        InEnvironment {
+            environment: op(self.environment),
+            goal: self.goal,
+        }
+    }
 }
 
 impl<G: HasInterner> HasInterner for InEnvironment<G> {
@@ -420,6 +474,66 @@ impl<I: Interner> Ty<I> {
         }
     }
 
+    /// Creates an ADT type, e.g. `Vec<T>`.
+    ///
+    /// ```
+    /// # use chalk_integration::interner::{ChalkIr, RawId};
+    /// # use chalk_ir::*;
+    /// let interner = &ChalkIr;
+    /// let id = AdtId(RawId { index: 0 });
+    /// let ty = Ty::adt(interner, id, Substitution::empty(interner));
+    /// assert!(matches!(ty.kind(interner), TyKind::Adt(adt_id, _) if *adt_id == id));
+    /// ```
+    pub fn adt(interner: &I, id: AdtId<I>, substitution: Substitution<I>) -> Self {
+        TyKind::Adt(id, substitution).intern(interner)
+    }
+
+    /// Creates a projection type, e.g. `<T as Iterator>::Item`.
+    ///
+    /// ```
+    /// # use chalk_integration::interner::{ChalkIr, RawId};
+    /// # use chalk_ir::*;
+    /// let interner = &ChalkIr;
+    /// let projection = ProjectionTy {
+    ///     associated_ty_id: AssocTypeId(RawId { index: 0 }),
+    ///     substitution: Substitution::from1(interner, TyKind::Str.intern(interner)),
+    /// };
+    /// let ty = Ty::projection(interner, projection.clone());
+    /// assert_eq!(
+    ///     ty.kind(interner),
+    ///     &TyKind::Alias(AliasTy::Projection(projection))
+    /// );
+    /// ```
+    pub fn projection(interner: &I, projection: ProjectionTy<I>) -> Self {
+        TyKind::Alias(AliasTy::Projection(projection)).intern(interner)
+    }
+
+    /// Creates a function pointer type, e.g. `for<'a> fn(&'a u32)`.
+    ///
+    /// ```
+    /// # use chalk_integration::interner::{ChalkFnAbi, ChalkIr};
+    /// # use chalk_ir::*;
+    /// let interner = &ChalkIr;
+    /// // `fn() -> ()`
+    /// let function = FnPointer {
+    ///     num_binders: 0,
+    ///     sig: FnSig {
+    ///         abi: ChalkFnAbi::Rust,
+    ///         safety: Safety::Safe,
+    ///         variadic: false,
+    ///     },
+    ///     substitution: FnSubst(Substitution::from1(
+    ///         interner,
+    ///         TyKind::Tuple(0, Substitution::empty(interner)).intern(interner),
+    ///     )),
+    /// };
+    /// let ty = Ty::function(interner, function.clone());
+    /// assert_eq!(ty.kind(interner), &TyKind::Function(function));
+    /// ```
+    pub fn function(interner: &I, function: FnPointer<I>) -> Self {
+        TyKind::Function(function).intern(interner)
+    }
+
     /// Gets the interned type.
     pub fn interned(&self) -> &I::InternedType {
         &self.interned
@@ -445,6 +559,13 @@ impl<I: Interner> Ty<I> {
         WellFormed::Ty(self.clone())
     }
 
+    /// The number of type nodes in this type; `Vec<Vec<u32>>` has size 3.
+    pub fn size(&self, interner: &I) -> usize {
+        let mut visitor = visit::visitors::TySizeVisitor::new(interner, |_| None);
+        self.visit_with(&mut visitor, DebruijnIndex::INNERMOST);
+        visitor.max_size()
+    }
+
     /// Creates a domain goal `FromEnv(T)` where `T` is this type.
     pub fn into_from_env_goal(self, interner: &I) -> DomainGoal<I> {
         self.from_env().cast(interner)
@@ -516,8 +637,8 @@ impl<I: Interner> Ty<I> {
     }
 
     /// True if this type contains "bound" types/lifetimes, and hence
-    /// needs to be shifted across binders. This is a very inefficient
-    /// check, intended only for debug assertions, because I am lazy.
+    /// needs to be shifted across binders. This walks the entire type,
+    /// but that is still cheaper than folding it to do the shift.
     pub fn needs_shift(&self, interner: &I) -> bool {
         self.has_free_vars(interner)
     }
@@ -1462,6 +1583,31 @@ impl<I: Interner> GenericArg<I> {
         self.constant(interner).unwrap()
     }
 
+    /// Checks that this argument has the same kind (type, lifetime or
+    /// constant) as `other`, failing with `NoSolution` if not. Use this
+    /// before operations that rely on the kinds matching, rather than
+    /// the panicking `assert_*_ref` methods.
+    pub fn assert_same_kind(&self, interner: &I, other: &GenericArg<I>) -> Fallible<()> {
+        let kind = match other.data(interner) {
+            GenericArgData::Ty(_) => VariableKind::Ty(TyVariableKind::General),
+            GenericArgData::Lifetime(_) => VariableKind::Lifetime,
+            GenericArgData::Const(c) => VariableKind::Const(c.data(interner).ty.clone()),
+        };
+        self.check_kind(interner, &kind)
+    }
+
+    /// Checks that this argument can be substituted for a variable of
+    /// kind `kind`, failing with `NoSolution` if not. Only the sort of the
+    /// kind (type, lifetime or constant) is compared.
+    pub fn check_kind(&self, interner: &I, kind: &VariableKind<I>) -> Fallible<()> {
+        match (kind, self.data(interner)) {
+            (VariableKind::Ty(_), GenericArgData::Ty(_))
+            | (VariableKind::Lifetime, GenericArgData::Lifetime(_))
+            | (VariableKind::Const(_), GenericArgData::Const(_)) => Ok(()),
+            _ => Err(NoSolution),
+        }
+    }
+
     /// Checks whether the generic argument is a type.
     pub fn is_ty(&self, interner: &I) -> bool {
         match self.data(interner) {
@@ -1906,6 +2052,12 @@ pub enum DomainGoal<I: Interner> {
 
     /// Used to indicate that a trait is object safe.
     ObjectSafe(TraitId<I>),
+
+    /// Assumes that the where clause does *not* hold. This is only
+    /// ever introduced as a hypothesis (e.g. `if (NotHolds(u32: Foo)) { .. }`);
+    /// a negative goal `not { WC }` is then proven by the assumption,
+    /// without searching for impls of `WC`.
+    NotHolds(WhereClause<I>),
 }
 
 impl<I: Interner> Copy for DomainGoal<I>
@@ -2198,6 +2350,35 @@ impl<T: HasInterner> Binders<T> {
     pub fn len(&self, interner: &T::Interner) -> usize {
         self.binders.len(interner)
     }
+
+    /// Returns the kinds of the binders, in order.
+    pub fn kinds<'a>(
+        &'a self,
+        interner: &'a T::Interner,
+    ) -> impl Iterator<Item = &'a VariableKind<T::Interner>> + 'a {
+        self.binders.iter(interner)
+    }
+
+    /// Returns the number of type binders.
+    pub fn num_ty_binders(&self, interner: &T::Interner) -> usize {
+        self.kinds(interner)
+            .filter(|kind| matches!(kind, VariableKind::Ty(_)))
+            .count()
+    }
+
+    /// Returns the number of lifetime binders.
+    pub fn num_lifetime_binders(&self, interner: &T::Interner) -> usize {
+        self.kinds(interner)
+            .filter(|kind| matches!(kind, VariableKind::Lifetime))
+            .count()
+    }
+
+    /// Returns the number of const binders.
+    pub fn num_const_binders(&self, interner: &T::Interner) -> usize {
+        self.kinds(interner)
+            .filter(|kind| matches!(kind, VariableKind::Const(_)))
+            .count()
+    }
 }
 
 impl<T, I> Binders<Binders<T>>
@@ -2236,6 +2417,30 @@ impl<T: HasInterner> From<Binders<T>> for (VariableKinds<T::Interner>, T) {
     }
 }
 
+impl<T, I> Binders<T>
+where
+    T: HasInterner<Interner = I>,
+    I: Interner,
+{
+    /// Checks that `parameters` match these binders in number and kinds,
+    /// failing with `NoSolution` if not. Substituting parameters that
+    /// don't match panics.
+    pub fn check_parameters(
+        &self,
+        interner: &I,
+        parameters: &(impl AsParameters<I> + ?Sized),
+    ) -> Fallible<()> {
+        let parameters = parameters.as_parameters(interner);
+        if self.binders.len(interner) != parameters.len() {
+            return Err(NoSolution);
+        }
+        for (kind, parameter) in self.binders.iter(interner).zip(parameters) {
+            parameter.check_kind(interner, kind)?;
+        }
+        Ok(())
+    }
+}
+
 impl<T, I> Binders<T>
 where
     T: Fold<I> + HasInterner<Interner = I>,
@@ -2254,6 +2459,43 @@ where
         assert_eq!(self.binders.len(interner), parameters.len());
         Subst::apply(interner, parameters, self.value)
     }
+
+    /// Like `substitute`, but fails with `NoSolution` instead of
+    /// panicking if the number or the kinds of `parameters` do not match
+    /// the binders.
+    pub fn try_substitute(
+        self,
+        interner: &I,
+        parameters: &(impl AsParameters<I> + ?Sized),
+    ) -> Fallible<T::Result> {
+        self.check_parameters(interner, parameters)?;
+        Ok(Subst::apply(
+            interner,
+            parameters.as_parameters(interner),
+            self.value,
+        ))
+    }
+}
+
+impl<T, I> Binders<T>
+where
+    T: Fold<I, Result = T> + HasInterner<Interner = I>,
+    I: Interner,
+{
+    /// The inverse of instantiating `binders` with placeholders in
+    /// `universe`: each placeholder `!U.i` in `value`, where `U` is
+    /// `universe`, becomes the `i`th variable of the returned binders.
+    /// Placeholders from other universes are left as they are, and
+    /// variables already bound outside of `value` are shifted in.
+    pub fn from_placeholders(
+        interner: &I,
+        binders: VariableKinds<I>,
+        universe: UniverseIndex,
+        value: T,
+    ) -> Self {
+        let value = ClosePlaceholders::apply(interner, universe, value.shifted_in(interner));
+        Binders::new(binders, value)
+    }
 }
 
 /// Allows iterating over a Binders<Vec<T>>, for instance.
@@ -2309,6 +2551,10 @@ pub struct ProgramClauseImplication<I: Interner> {
 
     /// The relative priority of the implication.
     pub priority: ClausePriority,
+
+    /// The impl this clause was lowered from, if any. Solvers use this
+    /// to report which impl proved a goal.
+    pub impl_id: Option<ImplId<I>>,
 }
 
 /// Specifies how important an implication is.
@@ -2336,6 +2582,28 @@ impl std::ops::BitAnd for ClausePriority {
 pub struct ProgramClauseData<I: Interner>(pub Binders<ProgramClauseImplication<I>>);
 
 impl<I: Interner> ProgramClauseImplication<I> {
+    /// Creates the implication `consequence :- conditions`, with no
+    /// constraints and high priority. `conditions` are given in source
+    /// order, i.e. the order in which they should be proven.
+    ///
+    /// The SLG solver pops conditions from the end of the list, so to
+    /// prove them from left to right, they are stored in reverse.
+    pub fn with_conditions(
+        interner: &I,
+        consequence: DomainGoal<I>,
+        conditions: impl IntoIterator<Item = Goal<I>>,
+    ) -> Self {
+        let mut conditions: Vec<_> = conditions.into_iter().collect();
+        conditions.reverse();
+        ProgramClauseImplication {
+            consequence,
+            conditions: Goals::from_iter(interner, conditions),
+            constraints: Constraints::empty(interner),
+            priority: ClausePriority::High,
+            impl_id: None,
+        }
+    }
+
     /// Change the implication into an application holding a `FromEnv` goal.
     pub fn into_from_env_clause(self, interner: &I) -> ProgramClauseImplication<I> {
         if self.conditions.is_empty(interner) {
@@ -2344,6 +2612,7 @@ impl<I: Interner> ProgramClauseImplication<I> {
                 conditions: self.conditions.clone(),
                 constraints: self.constraints.clone(),
                 priority: self.priority,
+                impl_id: self.impl_id,
             }
         } else {
             self
@@ -2512,6 +2781,15 @@ impl<I: Interner> Goal<I> {
         GoalData::Quantified(kind, Binders::new(binders, self)).intern(interner)
     }
 
+    /// The size of the largest type appearing in this goal, as measured
+    /// by [`Ty::size`]. This is the value that truncation compares
+    /// against the solver's maximum size.
+    pub fn size(&self, interner: &I) -> usize {
+        let mut visitor = visit::visitors::TySizeVisitor::new(interner, |_| None);
+        self.visit_with(&mut visitor, DebruijnIndex::INNERMOST);
+        visitor.max_size()
+    }
+
     /// Takes a goal `G` and turns it into `not { G }`.
     pub fn negate(self, interner: &I) -> Self {
         GoalData::Not(self).intern(interner)
@@ -2537,11 +2815,57 @@ impl<I: Interner> Goal<I> {
         .intern(interner)
     }
 
+    /// Takes a goal `G` and turns it into `reveal { G }`.
+    ///
+    /// This desugars into `if (Reveal) { G }`. Since the hypotheses of
+    /// nested `if` goals accumulate in the environment, this composes with
+    /// `compatible`: `compatible { reveal { G } }` solves `G` with both the
+    /// `Compatible` and `Reveal` modalities active.
+    pub fn reveal(self, interner: &I) -> Self {
+        GoalData::Implies(
+            ProgramClauses::from_iter(interner, vec![DomainGoal::Reveal]),
+            self,
+        )
+        .intern(interner)
+    }
+
+    /// Takes a leaf goal `G` and turns it into the goal that `G` is well
+    /// formed, as with `WhereClause::into_well_formed_goal`. For example,
+    /// `Implemented(T: Trait)` becomes `WellFormed(T: Trait)`. Returns
+    /// `None` if `G` is not a `Holds` domain goal.
+    pub fn well_formed(self, interner: &I) -> Option<Self> {
+        match self.data(interner) {
+            GoalData::DomainGoal(DomainGoal::Holds(wc)) => {
+                Some(wc.clone().into_well_formed_goal(interner).cast(interner))
+            }
+            _ => None,
+        }
+    }
+
     /// Create an implication goal that holds if the predicates are true.
     pub fn implied_by(self, interner: &I, predicates: ProgramClauses<I>) -> Goal<I> {
         GoalData::Implies(predicates, self).intern(interner)
     }
 
+    /// Create an implication goal that holds if the where clauses are
+    /// true. Like the lowering of `if (WC) { G }`, the where clauses are
+    /// assumed as `FromEnv` facts, so e.g. `T: Clone` becomes
+    /// `FromEnv(T: Clone)`.
+    pub fn assuming(
+        self,
+        interner: &I,
+        where_clauses: impl IntoIterator<Item = WhereClause<I>>,
+    ) -> Goal<I> {
+        let clauses = ProgramClauses::from_iter(
+            interner,
+            where_clauses.into_iter().map(|wc| {
+                let clause: ProgramClause<I> = wc.cast(interner);
+                clause.into_from_env_clause(interner)
+            }),
+        );
+        self.implied_by(interner, clauses)
+    }
+
     /// True if this goal is "trivially true" -- i.e., no work is
     /// required to prove it.
     pub fn is_trivially_true(&self, interner: &I) -> bool {
@@ -2550,6 +2874,36 @@ impl<I: Interner> Goal<I> {
             _ => false,
         }
     }
+
+    /// Simplifies the goal:
+    ///
+    /// * A conjunction containing a `CannotProve` becomes `CannotProve`.
+    /// * `not { not { G } }` becomes `G`, provided `G` has no free or
+    ///   inference variables; otherwise solving `G` could bind variables
+    ///   that the negated goal cannot. This also trades the ambiguity of a
+    ///   negated goal with region constraints for `G`'s constrained
+    ///   solution.
+    pub fn simplify(self, interner: &I) -> Goal<I> {
+        if self.is_cannot_prove(interner) {
+            return GoalData::CannotProve.intern(interner);
+        }
+        if let GoalData::Not(negated) = self.data(interner) {
+            if let GoalData::Not(goal) = negated.data(interner) {
+                if !goal.has_free_vars(interner) && !goal.has_inference_vars(interner) {
+                    return goal.clone().simplify(interner);
+                }
+            }
+        }
+        self
+    }
+
+    fn is_cannot_prove(&self, interner: &I) -> bool {
+        match self.data(interner) {
+            GoalData::CannotProve => true,
+            GoalData::All(goals) => goals.iter(interner).any(|g| g.is_cannot_prove(interner)),
+            _ => false,
+        }
+    }
 }
 
 impl<I> Goal<I>
@@ -2715,6 +3069,43 @@ impl<I: Interner> Substitution<I> {
         })
     }
 
+    /// Creates a substitution for a list of binders, such as the binders
+    /// of a `Canonical`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of parameters differs from the number of
+    /// binders. In debug builds, also panics if a parameter is not of the
+    /// kind of its binder (e.g., a lifetime given for a type binder).
+    pub fn from_binders<'b>(
+        interner: &I,
+        binders: impl IntoIterator<Item = &'b VariableKind<I>>,
+        parameters: impl IntoIterator<Item = impl CastTo<GenericArg<I>>>,
+    ) -> Self
+    where
+        I: 'b,
+    {
+        let subst = Self::from_iter(interner, parameters);
+        let mut binders = binders.into_iter();
+        for (index, parameter) in subst.iter(interner).enumerate() {
+            let binder = binders
+                .next()
+                .expect("substitution has more parameters than binders");
+            debug_assert!(
+                parameter.check_kind(interner, binder).is_ok(),
+                "parameter {} ({:?}) does not match binder of kind {:?}",
+                index,
+                parameter,
+                binder
+            );
+        }
+        assert!(
+            binders.next().is_none(),
+            "substitution has fewer parameters than binders"
+        );
+        subst
+    }
+
     /// Apply the substitution to a value.
     pub fn apply<T>(&self, value: T, interner: &I) -> T::Result
     where
@@ -2723,6 +3114,17 @@ impl<I: Interner> Substitution<I> {
         Substitute::apply(self, value, interner)
     }
 
+    /// Apply the substitution to a value, returning an error rather than
+    /// panicking if the substitution does not fit the bound variables of
+    /// the value (e.g., a type parameter is substituted with a lifetime,
+    /// or there are too few parameters).
+    pub fn try_apply<T>(&self, value: T, interner: &I) -> Fallible<T::Result>
+    where
+        T: Fold<I>,
+    {
+        Substitute::try_apply(self, value, interner)
+    }
+
     /// Gets an iterator of all type parameters.
     pub fn type_parameters<'a>(&'a self, interner: &'a I) -> impl Iterator<Item = Ty<I>> + 'a {
         self.iter(interner)
@@ -2746,10 +3148,17 @@ struct SubstFolder<'i, I: Interner, A: AsParameters<I>> {
 }
 
 impl<I: Interner, A: AsParameters<I>> SubstFolder<'_, I, A> {
-    /// Index into the list of parameters.
-    pub fn at(&self, index: usize) -> &GenericArg<I> {
+    /// Index into the list of parameters, checking that the parameter can
+    /// replace a variable of kind `kind`.
+    pub fn at(&self, index: usize, kind: &VariableKind<I>) -> Fallible<&GenericArg<I>> {
         let interner = self.interner;
-        &self.subst.as_parameters(interner)[index]
+        let parameter = self
+            .subst
+            .as_parameters(interner)
+            .get(index)
+            .ok_or(NoSolution)?;
+        parameter.check_kind(interner, kind)?;
+        Ok(parameter)
     }
 }
 
@@ -2797,23 +3206,34 @@ where
 /// that it can applied as a substituion to a value
 pub trait Substitute<I: Interner>: AsParameters<I> {
     /// Apply the substitution to a value.
-    fn apply<T: Fold<I>>(&self, value: T, interner: &I) -> T::Result;
+    ///
+    /// # Panics
+    ///
+    /// Panics if the substitution does not fit the bound variables of
+    /// the value; see `try_apply`.
+    fn apply<T: Fold<I>>(&self, value: T, interner: &I) -> T::Result {
+        self.try_apply(value, interner)
+            .expect("substitution does not match the bound variables of the value")
+    }
+
+    /// Apply the substitution to a value, returning `Err(NoSolution)` if a
+    /// bound variable has no corresponding parameter or the parameter is
+    /// of a different kind than the variable.
+    fn try_apply<T: Fold<I>>(&self, value: T, interner: &I) -> Fallible<T::Result>;
 }
 
 impl<I: Interner, A: AsParameters<I>> Substitute<I> for A {
-    fn apply<T>(&self, value: T, interner: &I) -> T::Result
+    fn try_apply<T>(&self, value: T, interner: &I) -> Fallible<T::Result>
     where
         T: Fold<I>,
     {
-        value
-            .fold_with(
-                &mut &SubstFolder {
-                    interner,
-                    subst: self,
-                },
-                DebruijnIndex::INNERMOST,
-            )
-            .unwrap()
+        value.fold_with(
+            &mut &SubstFolder {
+                interner,
+                subst: self,
+            },
+            DebruijnIndex::INNERMOST,
+        )
     }
 }
 
@@ -2840,6 +3260,10 @@ impl<'a, I: Interner> ToGenericArg<I> for (usize, &'a VariableKind<I>) {
     }
 }
 
+// The substituted values only need to be shifted (i.e., re-folded) if we
+// are underneath some binders *and* they refer to bound variables
+// themselves; otherwise, we can hand back (a cheap clone of) the value
+// we were given.
 impl<'i, I: Interner, A: AsParameters<I>> Folder<'i, I> for &SubstFolder<'i, I, A> {
     fn as_dyn(&mut self) -> &mut dyn Folder<'i, I> {
         self
@@ -2851,8 +3275,11 @@ impl<'i, I: Interner, A: AsParameters<I>> Folder<'i, I> for &SubstFolder<'i, I,
         outer_binder: DebruijnIndex,
     ) -> Fallible<Ty<I>> {
         assert_eq!(bound_var.debruijn, DebruijnIndex::INNERMOST);
-        let ty = self.at(bound_var.index);
+        let ty = self.at(bound_var.index, &VariableKind::Ty(TyVariableKind::General))?;
         let ty = ty.assert_ty_ref(self.interner());
+        if outer_binder == DebruijnIndex::INNERMOST || !ty.needs_shift(self.interner()) {
+            return Ok(ty.clone());
+        }
         Ok(ty.clone().shifted_in_from(self.interner(), outer_binder))
     }
 
@@ -2862,20 +3289,26 @@ impl<'i, I: Interner, A: AsParameters<I>> Folder<'i, I> for &SubstFolder<'i, I,
         outer_binder: DebruijnIndex,
     ) -> Fallible<Lifetime<I>> {
         assert_eq!(bound_var.debruijn, DebruijnIndex::INNERMOST);
-        let l = self.at(bound_var.index);
+        let l = self.at(bound_var.index, &VariableKind::Lifetime)?;
         let l = l.assert_lifetime_ref(self.interner());
+        if outer_binder == DebruijnIndex::INNERMOST || !l.needs_shift(self.interner()) {
+            return Ok(l.clone());
+        }
         Ok(l.clone().shifted_in_from(self.interner(), outer_binder))
     }
 
     fn fold_free_var_const(
         &mut self,
-        _ty: Ty<I>,
+        ty: Ty<I>,
         bound_var: BoundVar,
         outer_binder: DebruijnIndex,
     ) -> Fallible<Const<I>> {
         assert_eq!(bound_var.debruijn, DebruijnIndex::INNERMOST);
-        let c = self.at(bound_var.index);
+        let c = self.at(bound_var.index, &VariableKind::Const(ty))?;
         let c = c.assert_const_ref(self.interner());
+        if outer_binder == DebruijnIndex::INNERMOST || !c.needs_shift(self.interner()) {
+            return Ok(c.clone());
+        }
         Ok(c.clone().shifted_in_from(self.interner(), outer_binder))
     }
 
@@ -1,6 +1,9 @@
 //! Visitor helpers
 
-use crate::{BoundVar, ControlFlow, DebruijnIndex, Interner, Visit, Visitor};
+use crate::{
+    BoundVar, ControlFlow, DebruijnIndex, InferenceVar, Interner, SuperVisit, Ty, Visit, Visitor,
+};
+use std::cmp::max;
 
 /// Visitor extensions.
 pub trait VisitExt<I: Interner>: Visit<I> {
@@ -12,6 +15,15 @@ pub trait VisitExt<I: Interner>: Visit<I> {
         )
         .is_break()
     }
+
+    /// Check whether there are inference variables.
+    fn has_inference_vars(&self, interner: &I) -> bool {
+        self.visit_with(
+            &mut FindInferenceVarsVisitor { interner },
+            DebruijnIndex::INNERMOST,
+        )
+        .is_break()
+    }
 }
 
 impl<T, I: Interner> VisitExt<I> for T where T: Visit<I> {}
@@ -39,3 +51,100 @@ impl<'i, I: Interner> Visitor<'i, I> for FindFreeVarsVisitor<'i, I> {
         ControlFlow::BREAK
     }
 }
+
+struct FindInferenceVarsVisitor<'i, I: Interner> {
+    interner: &'i I,
+}
+
+impl<'i, I: Interner> Visitor<'i, I> for FindInferenceVarsVisitor<'i, I> {
+    type BreakTy = ();
+
+    fn as_dyn(&mut self) -> &mut dyn Visitor<'i, I, BreakTy = Self::BreakTy> {
+        self
+    }
+
+    fn interner(&self) -> &'i I {
+        self.interner
+    }
+
+    fn visit_inference_var(
+        &mut self,
+        _var: InferenceVar,
+        _outer_binder: DebruijnIndex,
+    ) -> ControlFlow<()> {
+        ControlFlow::BREAK
+    }
+}
+
+/// Measures the size of types, counting one for each type node, so
+/// `Vec<Vec<u32>>` has size 3. Each outermost type is measured
+/// separately, and `max_size` is the size of the largest one. This is
+/// the measure that truncation compares against the solver's `max_size`.
+///
+/// Before a type is counted, it is passed to `normalize`; if that returns
+/// a type, the returned type is measured instead. This lets callers
+/// resolve inference variables.
+pub struct TySizeVisitor<'i, I: Interner, F> {
+    interner: &'i I,
+    normalize: F,
+    size: usize,
+    depth: usize,
+    max_size: usize,
+}
+
+impl<'i, I: Interner, F> TySizeVisitor<'i, I, F>
+where
+    F: FnMut(&Ty<I>) -> Option<Ty<I>>,
+{
+    /// Creates a visitor that normalizes types with `normalize`.
+    pub fn new(interner: &'i I, normalize: F) -> Self {
+        Self {
+            interner,
+            normalize,
+            size: 0,
+            depth: 0,
+            max_size: 0,
+        }
+    }
+
+    /// The size of the largest type visited so far.
+    pub fn max_size(&self) -> usize {
+        self.max_size
+    }
+}
+
+impl<'i, I: Interner, F> Visitor<'i, I> for TySizeVisitor<'i, I, F>
+where
+    F: FnMut(&Ty<I>) -> Option<Ty<I>>,
+{
+    type BreakTy = ();
+
+    fn as_dyn(&mut self) -> &mut dyn Visitor<'i, I, BreakTy = Self::BreakTy> {
+        self
+    }
+
+    fn visit_ty(&mut self, ty: &Ty<I>, outer_binder: DebruijnIndex) -> ControlFlow<()> {
+        if let Some(normalized_ty) = (self.normalize)(ty) {
+            normalized_ty.visit_with(self, outer_binder);
+            return ControlFlow::CONTINUE;
+        }
+
+        self.size += 1;
+        self.max_size = max(self.size, self.max_size);
+
+        self.depth += 1;
+        ty.super_visit_with(self, outer_binder);
+        self.depth -= 1;
+
+        // When we get back to the first invocation, clear the counters.
+        // We process each outermost type independently.
+        if self.depth == 0 {
+            self.size = 0;
+        }
+        ControlFlow::CONTINUE
+    }
+
+    fn interner(&self) -> &'i I {
+        self.interner
+    }
+}
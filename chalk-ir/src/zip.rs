@@ -46,7 +46,8 @@ pub trait Zipper<'i, I: Interner + 'i> {
     where
         T: Clone + HasInterner<Interner = I> + Zip<I> + Fold<I, Result = T>;
 
-    /// Zips two substs
+    /// Zips two substs, pairing up their parameters one by one. Parameters
+    /// of different kinds, or substs of different lengths, are an error.
     fn zip_substs(
         &mut self,
         ambient: Variance,
@@ -57,6 +58,9 @@ pub trait Zipper<'i, I: Interner + 'i> {
     where
         Self: Sized,
     {
+        if a.len() != b.len() {
+            return Err(NoSolution);
+        }
         for (i, (a, b)) in a.iter().zip(b.iter()).enumerate() {
             let variance = variances
                 .as_ref()
@@ -221,6 +225,24 @@ impl<T: Zip<I>, I: Interner> Zip<I> for Box<T> {
     }
 }
 
+impl<T: Zip<I>, I: Interner> Zip<I> for Option<T> {
+    fn zip_with<'i, Z: Zipper<'i, I>>(
+        zipper: &mut Z,
+        variance: Variance,
+        a: &Self,
+        b: &Self,
+    ) -> Fallible<()>
+    where
+        I: 'i,
+    {
+        match (a, b) {
+            (Some(a), Some(b)) => Zip::zip_with(zipper, variance, a, b),
+            (None, None) => Ok(()),
+            _ => Err(NoSolution),
+        }
+    }
+}
+
 impl<T: Zip<I>, U: Zip<I>, I: Interner> Zip<I> for (T, U) {
     fn zip_with<'i, Z: Zipper<'i, I>>(
         zipper: &mut Z,
@@ -325,6 +347,7 @@ eq_zip!(I => OpaqueTyId<I>);
 eq_zip!(I => GeneratorId<I>);
 eq_zip!(I => ForeignDefId<I>);
 eq_zip!(I => FnDefId<I>);
+eq_zip!(I => ImplId<I>);
 eq_zip!(I => ClosureId<I>);
 eq_zip!(I => QuantifierKind);
 eq_zip!(I => PhantomData<I>);
@@ -492,6 +515,21 @@ impl<I: Interner> Zip<I> for GenericArg<I> {
     }
 }
 
+impl<I: Interner> Zip<I> for Substitution<I> {
+    fn zip_with<'i, Z: Zipper<'i, I>>(
+        zipper: &mut Z,
+        variance: Variance,
+        a: &Self,
+        b: &Self,
+    ) -> Fallible<()>
+    where
+        I: 'i,
+    {
+        let interner = zipper.interner();
+        zipper.zip_substs(variance, None, a.as_slice(interner), b.as_slice(interner))
+    }
+}
+
 impl<I: Interner> Zip<I> for ProgramClause<I> {
     fn zip_with<'i, Z: Zipper<'i, I>>(
         zipper: &mut Z,
@@ -0,0 +1,31 @@
+use chalk_integration::interner::ChalkIr;
+use chalk_ir::*;
+
+#[test]
+fn binder_kind_counts() {
+    let interner = &ChalkIr;
+    let binders = Binders::new(
+        VariableKinds::from_iter(
+            interner,
+            vec![
+                VariableKind::Ty(TyVariableKind::General),
+                VariableKind::Lifetime,
+                VariableKind::Ty(TyVariableKind::Integer),
+            ],
+        ),
+        TyKind::Str.intern(interner),
+    );
+
+    assert_eq!(
+        binders.kinds(interner).cloned().collect::<Vec<_>>(),
+        [
+            VariableKind::Ty(TyVariableKind::General),
+            VariableKind::Lifetime,
+            VariableKind::Ty(TyVariableKind::Integer),
+        ]
+    );
+    assert_eq!(binders.len(interner), 3);
+    assert_eq!(binders.num_ty_binders(interner), 2);
+    assert_eq!(binders.num_lifetime_binders(interner), 1);
+    assert_eq!(binders.num_const_binders(interner), 0);
+}
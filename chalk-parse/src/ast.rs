@@ -1,7 +1,7 @@
 use std::fmt;
 use string_cache::DefaultAtom as Atom;
 
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub struct Span {
     pub lo: usize,
     pub hi: usize,
@@ -24,6 +24,7 @@ pub enum Item {
     FnDefn(FnDefn),
     ClosureDefn(ClosureDefn),
     TraitDefn(TraitDefn),
+    TraitAliasDefn(TraitAliasDefn),
     OpaqueTyDefn(OpaqueTyDefn),
     GeneratorDefn(GeneratorDefn),
     Impl(Impl),
@@ -127,11 +128,14 @@ pub struct ClosureDefn {
 }
 
 #[derive(Clone, Eq, PartialEq, Debug)]
-pub struct FnAbi(pub Atom);
+pub struct FnAbi(pub Identifier);
 
 impl Default for FnAbi {
     fn default() -> Self {
-        FnAbi(Atom::from("Rust"))
+        FnAbi(Identifier {
+            str: Atom::from("Rust"),
+            span: Span::new(0, 0),
+        })
     }
 }
 
@@ -141,10 +145,21 @@ pub struct TraitDefn {
     pub variable_kinds: Vec<VariableKind>,
     pub where_clauses: Vec<QuantifiedWhereClause>,
     pub assoc_ty_defns: Vec<AssocTyDefn>,
+    /// The `clause C;` items, which hold for any type implementing the trait.
+    pub provided_clauses: Vec<Clause>,
     pub flags: TraitFlags,
     pub well_known: Option<WellKnownTrait>,
 }
 
+/// A trait alias, e.g. `trait Alias<T> = Foo<T> + Bar;`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TraitAliasDefn {
+    pub name: Identifier,
+    pub variable_kinds: Vec<VariableKind>,
+    pub bounds: Vec<QuantifiedInlineBound>,
+    pub where_clauses: Vec<QuantifiedWhereClause>,
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum WellKnownTrait {
     Sized,
@@ -191,6 +206,9 @@ pub struct OpaqueTyDefn {
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum VariableKind {
     Ty(Identifier),
+    /// A type parameter declared `T: ?Sized`, which does not get the
+    /// implicit `T: Sized` bound.
+    MaybeSizedTy(Identifier),
     IntegerTy(Identifier),
     FloatTy(Identifier),
     Lifetime(Identifier),
@@ -280,6 +298,7 @@ pub struct AssocTyValue {
     pub name: Identifier,
     pub variable_kinds: Vec<VariableKind>,
     pub value: Ty,
+    pub where_clauses: Vec<QuantifiedWhereClause>,
     pub default: bool,
 }
 
@@ -391,6 +410,22 @@ pub enum Lifetime {
     Empty,
 }
 
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum AssocTypeBinding {
+    /// `Item<..> = U`
+    Eq {
+        name: Identifier,
+        args: Vec<GenericArg>,
+        ty: Ty,
+    },
+    /// `Item<..>: Bar + Baz`
+    Bounds {
+        name: Identifier,
+        args: Vec<GenericArg>,
+        bounds: Vec<TraitBound>,
+    },
+}
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct ProjectionTy {
     pub trait_ref: TraitRef,
@@ -437,10 +472,24 @@ impl fmt::Display for Identifier {
 
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum WhereClause {
-    Implemented { trait_ref: TraitRef },
-    ProjectionEq { projection: ProjectionTy, ty: Ty },
-    LifetimeOutlives { a: Lifetime, b: Lifetime },
-    TypeOutlives { ty: Ty, lifetime: Lifetime },
+    Implemented {
+        trait_ref: TraitRef,
+    },
+    /// `T: Foo<A, Item = U, Other: Bar>`: a trait reference that binds some
+    /// of the trait's associated types. `trait_ref` holds only the generic
+    /// arguments (`T: Foo<A>`).
+    AssocTypeBindings {
+        trait_ref: TraitRef,
+        bindings: Vec<AssocTypeBinding>,
+    },
+    LifetimeOutlives {
+        a: Lifetime,
+        b: Lifetime,
+    },
+    TypeOutlives {
+        ty: Ty,
+        lifetime: Lifetime,
+    },
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -459,6 +508,7 @@ pub enum DomainGoal {
     DownstreamType { ty: Ty },
     Reveal,
     ObjectSafe { id: Identifier },
+    NotHolds { trait_ref: TraitRef },
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -500,6 +550,9 @@ pub enum Goal {
     /// The `compatible { G }` syntax
     Compatible(Box<Goal>),
 
+    /// The `reveal { G }` syntax
+    Reveal(Box<Goal>),
+
     // Additional kinds of goals:
     Leaf(LeafGoal),
 }
@@ -2,19 +2,22 @@ use chalk_solve::Solution;
 use tracing::debug;
 
 use chalk_ir::interner::Interner;
-use chalk_ir::{ClausePriority, DomainGoal, GenericArg};
+use chalk_ir::{ClausePriority, DomainGoal, GenericArg, ImplId};
+
+/// A solution found by applying one or more clauses, along with the
+/// priority of those clauses and, if the solution comes from the clauses
+/// of a single impl, that impl.
+pub(super) type ClauseSolution<I> = (Solution<I>, ClausePriority, Option<ImplId<I>>);
 
 pub(super) fn with_priorities<I: Interner>(
     interner: &I,
     domain_goal: &DomainGoal<I>,
-    a: Solution<I>,
-    prio_a: ClausePriority,
-    b: Solution<I>,
-    prio_b: ClausePriority,
-) -> (Solution<I>, ClausePriority) {
-    match (prio_a, prio_b, a, b) {
-        (ClausePriority::High, ClausePriority::Low, higher, lower)
-        | (ClausePriority::Low, ClausePriority::High, lower, higher) => {
+    a: ClauseSolution<I>,
+    b: ClauseSolution<I>,
+) -> ClauseSolution<I> {
+    match (a, b) {
+        ((higher, ClausePriority::High, impl_id), (lower, ClausePriority::Low, _))
+        | ((lower, ClausePriority::Low, _), (higher, ClausePriority::High, impl_id)) => {
             // if we have a high-priority solution and a low-priority solution,
             // the high-priority solution overrides *if* they are both for the
             // same inputs -- we don't want a more specific high-priority
@@ -28,12 +31,15 @@ pub(super) fn with_priorities<I: Interner>(
                     "preferring solution: {:?} over {:?} because of higher prio",
                     higher, lower
                 );
-                (higher, ClausePriority::High)
+                (higher, ClausePriority::High, impl_id)
             } else {
-                (higher.combine(lower, interner), ClausePriority::High)
+                (higher.combine(lower, interner), ClausePriority::High, None)
             }
         }
-        (_, _, a, b) => (a.combine(b, interner), prio_a),
+        ((a, prio_a, impl_a), (b, _, impl_b)) => {
+            let impl_id = if impl_a == impl_b { impl_a } else { None };
+            (a.combine(b, interner), prio_a, impl_id)
+        }
     }
 }
 
@@ -7,10 +7,16 @@ mod cache;
 mod search_graph;
 mod stack;
 
+use crate::fulfill::CannotProveReason;
 pub use cache::Cache;
 use search_graph::{DepthFirstNumber, SearchGraph};
 use stack::{Stack, StackDepth};
 
+/// The default for `RecursiveContext::max_fulfill_rounds`. Each round
+/// that counts towards the limit has to make progress on inference, so
+/// this is only reached if solving oscillates.
+const DEFAULT_MAX_FULFILL_ROUNDS: usize = 1000;
+
 pub(super) struct RecursiveContext<K, V>
 where
     K: Hash + Eq + Debug + Clone,
@@ -29,6 +35,14 @@ where
 
     /// The maximum size for goals.
     max_size: usize,
+
+    /// The maximum number of rounds `Fulfill` may spend trying to make
+    /// progress on its obligations.
+    max_fulfill_rounds: usize,
+
+    /// Why goals solved since the last root goal could neither be proven
+    /// nor refuted, without duplicates.
+    cannot_prove: Vec<CannotProveReason>,
 }
 
 pub(super) trait SolverStuff<K, V>: Copy
@@ -78,6 +92,8 @@ where
             search_graph: SearchGraph::new(),
             cache,
             max_size,
+            max_fulfill_rounds: DEFAULT_MAX_FULFILL_ROUNDS,
+            cannot_prove: Vec::new(),
         }
     }
 
@@ -85,6 +101,26 @@ where
         self.max_size
     }
 
+    pub fn max_fulfill_rounds(&self) -> usize {
+        self.max_fulfill_rounds
+    }
+
+    pub fn set_max_fulfill_rounds(&mut self, max_fulfill_rounds: usize) {
+        self.max_fulfill_rounds = max_fulfill_rounds;
+    }
+
+    /// Returns the distinct reasons recorded with `record_cannot_prove`
+    /// while solving the last root goal, in the order they were recorded.
+    pub fn cannot_prove(&self) -> &[CannotProveReason] {
+        &self.cannot_prove
+    }
+
+    pub fn record_cannot_prove(&mut self, reason: CannotProveReason) {
+        if !self.cannot_prove.contains(&reason) {
+            self.cannot_prove.push(reason);
+        }
+    }
+
     /// Solves a canonical goal. The substitution returned in the
     /// solution will be for the fully decomposed goal. For example, given the
     /// program
@@ -107,6 +143,7 @@ where
     ) -> V {
         debug!("solve_root_goal(canonical_goal={:?})", canonical_goal);
         assert!(self.stack.is_empty());
+        self.cannot_prove.clear();
         let minimums = &mut Minimums::new();
         self.solve_goal(canonical_goal, minimums, solver_stuff)
     }
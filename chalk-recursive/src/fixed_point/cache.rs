@@ -20,6 +20,7 @@ where
     V: Debug + Clone,
 {
     cache: FxHashMap<K, V>,
+    hits: usize,
 }
 
 impl<K, V> Cache<K, V>
@@ -38,17 +39,23 @@ where
         data.cache.insert(goal, result);
     }
 
-    /// Record a cache result.
+    /// Lookup a cached result for `goal`.
     pub fn get(&self, goal: &K) -> Option<V> {
-        let data = self.data.lock().unwrap();
-        if let Some(result) = data.cache.get(&goal) {
+        let mut data = self.data.lock().unwrap();
+        if let Some(result) = data.cache.get(&goal).cloned() {
             debug!(?goal, ?result, "Cache hit");
-            Some(result.clone())
+            data.hits += 1;
+            Some(result)
         } else {
             debug!(?goal, "Cache miss");
             None
         }
     }
+
+    /// The number of lookups that have been answered from the cache so far.
+    pub fn hits(&self) -> usize {
+        self.data.lock().unwrap().hits
+    }
 }
 
 impl<K, V> Clone for Cache<K, V>
@@ -83,6 +90,7 @@ where
     fn default() -> Self {
         Self {
             cache: Default::default(),
+            hits: 0,
         }
     }
 }
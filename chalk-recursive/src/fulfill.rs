@@ -6,14 +6,14 @@ use chalk_ir::interner::{HasInterner, Interner};
 use chalk_ir::visit::Visit;
 use chalk_ir::zip::Zip;
 use chalk_ir::{
-    Binders, Canonical, ConstrainedSubst, Constraint, Constraints, DomainGoal, Environment, EqGoal,
-    Fallible, GenericArg, Goal, GoalData, InEnvironment, NoSolution, ProgramClauseImplication,
-    QuantifierKind, Substitution, SubtypeGoal, TyKind, TyVariableKind, UCanonical,
-    UnificationDatabase, UniverseMap, Variance,
+    AliasEq, Binders, Canonical, ConstrainedSubst, Constraint, Constraints, DomainGoal,
+    Environment, EqGoal, Fallible, GenericArg, Goal, GoalData, InEnvironment, NoSolution,
+    ProgramClauseImplication, QuantifierKind, Substitution, SubtypeGoal, TyKind, TyVariableKind,
+    UCanonical, UnificationDatabase, UniverseMap, Variance, WhereClause,
 };
 use chalk_solve::debug_span;
 use chalk_solve::infer::{InferenceTable, ParameterEnaVariableExt};
-use chalk_solve::solve::truncate;
+use chalk_solve::solve::truncate::{self, Truncated};
 use chalk_solve::{Guidance, Solution};
 use rustc_hash::FxHashSet;
 use std::fmt::Debug;
@@ -134,7 +134,18 @@ pub(super) struct Fulfill<'s, I: Interner, Solver: SolveDatabase<I>> {
     /// Record that a goal has been processed that can neither be proved nor
     /// refuted. In such a case the solution will be either `CannotProve`, or `Err`
     /// in the case where some other goal leads to an error.
-    cannot_prove: bool,
+    cannot_prove: Vec<CannotProveReason>,
+}
+
+/// Why a goal could neither be proved nor refuted; see
+/// `RecursiveSolver::solve_with_reasons`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CannotProveReason {
+    /// The goal was inherently ambiguous, e.g. a `CannotProve` goal or
+    /// subtyping between two unresolved inference variables.
+    Ambiguous,
+    /// The goal was too large and had to be truncated.
+    Truncated(Truncated),
 }
 
 impl<'s, I: Interner, Solver: SolveDatabase<I>> Fulfill<'s, I, Solver> {
@@ -152,14 +163,14 @@ impl<'s, I: Interner, Solver: SolveDatabase<I>> Fulfill<'s, I, Solver> {
             subst,
             obligations: vec![],
             constraints: FxHashSet::default(),
-            cannot_prove: false,
+            cannot_prove: vec![],
         };
 
         let ProgramClauseImplication {
             consequence,
             conditions,
             constraints,
-            priority: _,
+            ..
         } = fulfill
             .infer
             .instantiate_binders_existentially(fulfill.solver.interner(), clause.clone());
@@ -202,7 +213,7 @@ impl<'s, I: Interner, Solver: SolveDatabase<I>> Fulfill<'s, I, Solver> {
             subst,
             obligations: vec![],
             constraints: FxHashSet::default(),
-            cannot_prove: false,
+            cannot_prove: vec![],
         };
 
         if let Err(e) = fulfill.push_goal(&canonical_goal.environment, canonical_goal.goal.clone())
@@ -215,32 +226,20 @@ impl<'s, I: Interner, Solver: SolveDatabase<I>> Fulfill<'s, I, Solver> {
 
     fn push_obligation(&mut self, obligation: Obligation<I>) {
         // truncate to avoid overflows
-        match &obligation {
-            Obligation::Prove(goal) => {
-                if truncate::needs_truncation(
-                    self.solver.interner(),
-                    &mut self.infer,
-                    self.solver.max_size(),
-                    goal,
-                ) {
-                    // the goal is too big. Record that we should return Ambiguous
-                    self.cannot_prove = true;
-                    return;
-                }
-            }
-            Obligation::Refute(goal) => {
-                if truncate::needs_truncation(
-                    self.solver.interner(),
-                    &mut self.infer,
-                    self.solver.max_size(),
-                    goal,
-                ) {
-                    // the goal is too big. Record that we should return Ambiguous
-                    self.cannot_prove = true;
-                    return;
-                }
-            }
+        let goal = match &obligation {
+            Obligation::Prove(goal) | Obligation::Refute(goal) => goal,
         };
+        if let Err(truncated) = truncate::check_truncation(
+            self.solver.interner(),
+            &mut self.infer,
+            self.solver.max_size(),
+            goal,
+        ) {
+            // the goal is too big. Record that we should return Ambiguous
+            self.cannot_prove
+                .push(CannotProveReason::Truncated(truncated));
+            return;
+        }
         self.obligations.push(obligation);
     }
 
@@ -285,6 +284,7 @@ impl<'s, I: Interner, Solver: SolveDatabase<I>> Fulfill<'s, I, Solver> {
         goal: Goal<I>,
     ) -> Fallible<()> {
         let interner = self.solver.interner();
+        let goal = goal.simplify(interner);
         match goal.data(interner) {
             GoalData::Quantified(QuantifierKind::ForAll, subgoal) => {
                 let subgoal = self
@@ -332,14 +332,14 @@ impl<'s, I: Interner, Solver: SolveDatabase<I>> Fulfill<'s, I, Solver> {
                     b.kind(interner),
                     TyKind::InferenceVar(_, TyVariableKind::General)
                 ) {
-                    self.cannot_prove = true;
+                    self.cannot_prove.push(CannotProveReason::Ambiguous);
                 } else {
                     self.unify(&environment, Variance::Covariant, &a, &b)?;
                 }
             }
             GoalData::CannotProve => {
-                debug!("Pushed a CannotProve goal, setting cannot_prove = true");
-                self.cannot_prove = true;
+                debug!("Pushed a CannotProve goal, setting cannot_prove");
+                self.cannot_prove.push(CannotProveReason::Ambiguous);
             }
         }
         Ok(())
@@ -363,6 +363,11 @@ impl<'s, I: Interner, Solver: SolveDatabase<I>> Fulfill<'s, I, Solver> {
     }
 
     fn refute(&mut self, goal: InEnvironment<Goal<I>>) -> Fallible<NegativeSolution> {
+        // Any enclosing `forall` binders were instantiated with placeholders
+        // by `push_goal` before this obligation was created. Inverting turns
+        // those placeholders back into existentials, so for
+        // `forall<T> { not { T: Debug } }` we ask whether *any* type
+        // implements `Debug`.
         let canonicalized = match self
             .infer
             .invert_then_canonicalize(self.solver.interner(), goal)
@@ -375,6 +380,13 @@ impl<'s, I: Interner, Solver: SolveDatabase<I>> Fulfill<'s, I, Solver> {
             }
         };
 
+        // An assumption that the goal does not hold trumps searching for
+        // a proof of it.
+        let InEnvironment { environment, goal } = &canonicalized.value;
+        if environment.assumes_not_holds(self.solver.interner(), goal) {
+            return Ok(NegativeSolution::Refuted);
+        }
+
         // Negate the result
         let (quantified, _) =
             u_canonicalize(&mut self.infer, self.solver.interner(), &canonicalized);
@@ -434,6 +446,36 @@ impl<'s, I: Interner, Solver: SolveDatabase<I>> Fulfill<'s, I, Solver> {
         }
     }
 
+    /// If `wc` is an `AliasEq` goal that has just been proven (and applied),
+    /// records the normalization in the inference table, so that the same
+    /// alias does not need to be solved for again.
+    fn record_normalization(&mut self, wc: &InEnvironment<Goal<I>>) {
+        let interner = self.solver.interner();
+        if let Some(AliasEq { alias, ty }) = as_alias_eq(interner, &wc.goal) {
+            self.infer
+                .record_normalization(interner, &wc.environment, alias, ty);
+        }
+    }
+
+    /// If `wc` is an `AliasEq` goal whose alias has a recorded
+    /// normalization, relates the normalized type with the goal's type
+    /// instead of solving the goal again, and returns true.
+    fn apply_recorded_normalization(&mut self, wc: &InEnvironment<Goal<I>>) -> Fallible<bool> {
+        let interner = self.solver.interner();
+        let AliasEq { alias, ty } = match as_alias_eq(interner, &wc.goal) {
+            Some(alias_eq) => alias_eq,
+            None => return Ok(false),
+        };
+        match self.infer.normalization(interner, &wc.environment, alias) {
+            Some(normalized) => {
+                debug!(?normalized, "using recorded normalization for {:?}", alias);
+                self.unify(&wc.environment, Variance::Invariant, &normalized, ty)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
     fn fulfill(&mut self, minimums: &mut Minimums) -> Fallible<Outcome> {
         debug_span!("fulfill", obligations=?self.obligations);
 
@@ -444,8 +486,21 @@ impl<'s, I: Interner, Solver: SolveDatabase<I>> Fulfill<'s, I, Solver> {
         // learning new things about our inference state.
         let mut obligations = Vec::with_capacity(self.obligations.len());
         let mut progress = true;
+        let max_rounds = self.solver.max_fulfill_rounds();
+        let mut rounds = 0;
 
         while progress {
+            if rounds == max_rounds {
+                // Give up rather than risk oscillating forever; whatever
+                // obligations are left make the outcome incomplete, and
+                // hence the solution ambiguous.
+                debug!(
+                    "giving up after {} rounds, obligations left: {:?}",
+                    rounds, self.obligations
+                );
+                break;
+            }
+            rounds += 1;
             progress = false;
             debug!("start of round, {} obligations", self.obligations.len());
 
@@ -458,6 +513,10 @@ impl<'s, I: Interner, Solver: SolveDatabase<I>> Fulfill<'s, I, Solver> {
             assert!(obligations.is_empty());
             while let Some(obligation) = self.obligations.pop() {
                 let ambiguous = match &obligation {
+                    Obligation::Prove(wc) if self.apply_recorded_normalization(wc)? => {
+                        progress = true;
+                        false
+                    }
                     Obligation::Prove(wc) => {
                         let PositiveSolution {
                             free_vars,
@@ -482,6 +541,16 @@ impl<'s, I: Interner, Solver: SolveDatabase<I>> Fulfill<'s, I, Solver> {
                             }
                         }
 
+                        if let Solution::Unique(constrained_subst) = &solution {
+                            if constrained_subst
+                                .value
+                                .constraints
+                                .is_empty(self.interner())
+                            {
+                                self.record_normalization(wc);
+                            }
+                        }
+
                         solution.is_ambig()
                     }
                     Obligation::Refute(goal) => {
@@ -521,8 +590,11 @@ impl<'s, I: Interner, Solver: SolveDatabase<I>> Fulfill<'s, I, Solver> {
             Err(e) => return Err(e),
         };
 
-        if self.cannot_prove {
-            debug!("Goal cannot be proven (cannot_prove = true), returning ambiguous");
+        if !self.cannot_prove.is_empty() {
+            debug!(reasons = ?self.cannot_prove, "Goal cannot be proven, returning ambiguous");
+            for reason in self.cannot_prove {
+                self.solver.record_cannot_prove(reason);
+            }
             return Ok(Solution::Ambig(Guidance::Unknown));
         }
 
@@ -608,3 +680,10 @@ impl<'s, I: Interner, Solver: SolveDatabase<I>> Fulfill<'s, I, Solver> {
         self.solver.interner()
     }
 }
+
+fn as_alias_eq<'g, I: Interner>(interner: &I, goal: &'g Goal<I>) -> Option<&'g AliasEq<I>> {
+    match goal.data(interner) {
+        GoalData::DomainGoal(DomainGoal::Holds(WhereClause::AliasEq(alias_eq))) => Some(alias_eq),
+        _ => None,
+    }
+}
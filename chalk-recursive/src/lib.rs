@@ -9,4 +9,5 @@ mod recursive;
 pub mod solve;
 
 pub use fixed_point::Cache;
+pub use fulfill::CannotProveReason;
 pub use recursive::RecursiveSolver;
@@ -1,8 +1,12 @@
 use crate::fixed_point::{Cache, Minimums, RecursiveContext, SolverStuff};
-use crate::solve::{SolveDatabase, SolveIteration};
+use crate::fulfill::CannotProveReason;
+use crate::solve::{SolveDatabase, SolveIteration, SolveIterationHelpers};
 use crate::UCanonicalGoal;
 use chalk_ir::{interner::Interner, NoSolution};
-use chalk_ir::{Canonical, ConstrainedSubst, Goal, InEnvironment, UCanonical};
+use chalk_ir::{
+    Canonical, ConstrainedSubst, DomainGoal, Goal, GoalData, ImplId, InEnvironment, UCanonical,
+    WhereClause,
+};
 use chalk_ir::{Constraints, Fallible};
 use chalk_solve::{coinductive_goal::IsCoinductive, RustIrDatabase, Solution};
 use std::fmt;
@@ -31,6 +35,33 @@ impl<I: Interner> RecursiveSolver<I> {
             ctx: Box::new(RecursiveContext::new(overflow_depth, max_size, cache)),
         }
     }
+
+    /// Limits the number of rounds spent trying to make progress on the
+    /// obligations of a single goal. A goal that reaches the limit is
+    /// reported as ambiguous, with whatever was inferred so far as guidance.
+    ///
+    /// Results that are already cached are not affected.
+    pub fn set_max_fulfill_rounds(&mut self, max_fulfill_rounds: usize) {
+        self.ctx.set_max_fulfill_rounds(max_fulfill_rounds);
+    }
+
+    /// Like `solve`, but if the goal can neither be proven nor refuted,
+    /// also returns why: for example, because subgoals got too large and
+    /// were truncated. Each distinct reason met while solving the goal is
+    /// reported once. Subgoals whose results were cached by an earlier
+    /// solve don't report a reason.
+    pub fn solve_with_reasons(
+        &mut self,
+        program: &dyn RustIrDatabase<I>,
+        goal: &UCanonical<InEnvironment<Goal<I>>>,
+    ) -> (Option<Solution<I>>, Vec<CannotProveReason>) {
+        let solution = self.ctx.solve_root_goal(goal, program).ok();
+        let reasons = match solution {
+            Some(Solution::Ambig(_)) => self.ctx.cannot_prove().to_vec(),
+            _ => vec![],
+        };
+        (solution, reasons)
+    }
 }
 
 impl<I: Interner> fmt::Debug for RecursiveSolver<I> {
@@ -123,6 +154,14 @@ impl<'me, I: Interner> SolveDatabase<I> for Solver<'me, I> {
     fn max_size(&self) -> usize {
         self.context.max_size()
     }
+
+    fn max_fulfill_rounds(&self) -> usize {
+        self.context.max_fulfill_rounds()
+    }
+
+    fn record_cannot_prove(&mut self, reason: CannotProveReason) {
+        self.context.record_cannot_prove(reason);
+    }
 }
 
 impl<I: Interner> chalk_solve::Solver<I> for RecursiveSolver<I> {
@@ -155,4 +194,40 @@ impl<I: Interner> chalk_solve::Solver<I> for RecursiveSolver<I> {
     ) -> bool {
         unimplemented!("Recursive solver doesn't support multiple answers")
     }
+
+    fn selected_impl(
+        &mut self,
+        program: &dyn RustIrDatabase<I>,
+        goal: &UCanonical<InEnvironment<Goal<I>>>,
+    ) -> Option<ImplId<I>> {
+        let interner = program.interner();
+        let domain_goal = match goal.canonical.value.goal.data(interner) {
+            GoalData::DomainGoal(domain_goal @ DomainGoal::Holds(WhereClause::Implemented(_))) => {
+                domain_goal.clone()
+            }
+            _ => return None,
+        };
+        if !self.solve(program, goal)?.is_unique() {
+            return None;
+        }
+
+        // Solved goals only keep their solution, so go over the goal's
+        // clauses once more to find out which of them it came from. The
+        // subgoals are answered from the cache where possible.
+        let goal = UCanonical {
+            canonical: Canonical {
+                binders: goal.canonical.binders.clone(),
+                value: InEnvironment::new(&goal.canonical.value.environment, domain_goal),
+            },
+            universes: goal.universes,
+        };
+        let (solution, impl_id) = Solver::new(&mut self.ctx, program)
+            .solve_from_clauses(&goal, &mut Minimums::new())
+            .ok()?;
+        if solution.is_unique() {
+            impl_id
+        } else {
+            None
+        }
+    }
 }
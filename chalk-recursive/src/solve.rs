@@ -1,13 +1,14 @@
 use super::combine;
-use super::fulfill::Fulfill;
+use super::fulfill::{CannotProveReason, Fulfill};
 use crate::fixed_point::Minimums;
 use crate::UCanonicalGoal;
 use chalk_ir::could_match::CouldMatch;
 use chalk_ir::fold::Fold;
 use chalk_ir::interner::{HasInterner, Interner};
 use chalk_ir::{
-    Canonical, ClausePriority, DomainGoal, Fallible, Floundered, Goal, GoalData, InEnvironment,
-    NoSolution, ProgramClause, ProgramClauseData, Substitution, UCanonical,
+    Canonical, ClausePriority, DomainGoal, Fallible, Floundered, Goal, GoalData, ImplId,
+    InEnvironment, NoSolution, ProgramClause, ProgramClauseData, ProgramClauseImplication,
+    Substitution, UCanonical,
 };
 use chalk_solve::clauses::program_clauses_that_could_match;
 use chalk_solve::debug_span;
@@ -24,6 +25,11 @@ pub(super) trait SolveDatabase<I: Interner>: Sized {
 
     fn max_size(&self) -> usize;
 
+    fn max_fulfill_rounds(&self) -> usize;
+
+    /// Records why some goal could neither be proven nor refuted.
+    fn record_cannot_prove(&mut self, reason: CannotProveReason);
+
     fn interner(&self) -> &I;
 
     fn db(&self) -> &dyn RustIrDatabase<I>;
@@ -73,6 +79,7 @@ pub(super) trait SolveIteration<I: Interner>: SolveDatabase<I> {
                     debug_span!("prog_clauses");
 
                     self.solve_from_clauses(&canonical_goal, minimums)
+                        .map(|(solution, _)| solution)
                 };
                 debug!(?prog_solution);
 
@@ -101,8 +108,8 @@ where
 {
 }
 
-/// Helper methods for `solve_iteration`, private to this module.
-trait SolveIterationHelpers<I: Interner>: SolveDatabase<I> {
+/// Helper methods for `solve_iteration`.
+pub(super) trait SolveIterationHelpers<I: Interner>: SolveDatabase<I> {
     #[instrument(level = "debug", skip(self, minimums))]
     fn solve_via_simplification(
         &mut self,
@@ -118,12 +125,13 @@ trait SolveIterationHelpers<I: Interner>: SolveDatabase<I> {
 
     /// See whether we can solve a goal by implication on any of the given
     /// clauses. If multiple such solutions are possible, we attempt to combine
-    /// them.
+    /// them. Also returns the impl that the solution came from, if it came
+    /// from the clauses of a single impl.
     fn solve_from_clauses(
         &mut self,
         canonical_goal: &UCanonical<InEnvironment<DomainGoal<I>>>,
         minimums: &mut Minimums,
-    ) -> Fallible<Solution<I>> {
+    ) -> Fallible<(Solution<I>, Option<ImplId<I>>)> {
         let mut clauses = vec![];
 
         let db = self.db();
@@ -134,14 +142,15 @@ trait SolveIterationHelpers<I: Interner>: SolveDatabase<I> {
                 &canonical_goal.canonical.value.goal,
             )
         };
-        clauses.extend(db.custom_clauses().into_iter().filter(could_match));
-        match program_clauses_that_could_match(db, canonical_goal) {
-            Ok(goal_clauses) => clauses.extend(goal_clauses.into_iter().filter(could_match)),
+        let program_clauses = match program_clauses_that_could_match(db, canonical_goal) {
+            Ok(goal_clauses) => goal_clauses,
             Err(Floundered) => {
-                return Ok(Solution::Ambig(Guidance::Unknown));
+                return Ok((Solution::Ambig(Guidance::Unknown), None));
             }
-        }
+        };
 
+        // As in the SLG solver, the clauses from the environment come first,
+        // then those from the program, then the custom clauses.
         let (infer, subst, goal) = self.new_inference_table(&canonical_goal);
         clauses.extend(
             db.program_clauses_for_env(&goal.environment)
@@ -149,36 +158,55 @@ trait SolveIterationHelpers<I: Interner>: SolveDatabase<I> {
                 .cloned()
                 .filter(could_match),
         );
+        clauses.extend(program_clauses.into_iter().filter(could_match));
+        clauses.extend(db.custom_clauses().into_iter().filter(could_match));
 
         let mut cur_solution = None;
         for program_clause in clauses {
             debug_span!("solve_from_clauses", clause = ?program_clause);
 
             // If we have a completely ambiguous answer, it's not going to get better, so stop
-            if cur_solution == Some((Solution::Ambig(Guidance::Unknown), ClausePriority::High)) {
-                return Ok(Solution::Ambig(Guidance::Unknown));
+            if let Some((Solution::Ambig(Guidance::Unknown), ClausePriority::High, _)) =
+                &cur_solution
+            {
+                return Ok((Solution::Ambig(Guidance::Unknown), None));
+            }
+
+            // If we have a trivial answer -- one that holds for all values of
+            // the goal's variables without any region constraints -- other
+            // clauses cannot tell the caller anything more, so stop. This is
+            // the "green cut" that the SLG solver applies in `pursue_answer`.
+            if let Some((Solution::Unique(constrained), ClausePriority::High, impl_id)) =
+                &cur_solution
+            {
+                if constrained.value.subst.is_identity_subst(self.interner())
+                    && constrained.value.constraints.is_empty(self.interner())
+                {
+                    return Ok((Solution::Unique(constrained.clone()), *impl_id));
+                }
             }
 
             let ProgramClauseData(implication) = program_clause.data(self.interner());
             let infer = infer.clone();
             let subst = subst.clone();
             let goal = goal.clone();
+            let ProgramClauseImplication {
+                priority, impl_id, ..
+            } = *implication.skip_binders();
             let res = match Fulfill::new_with_clause(self, infer, subst, goal, &implication) {
-                Ok(fulfill) => (fulfill.solve(minimums), implication.skip_binders().priority),
+                Ok(fulfill) => (fulfill.solve(minimums), priority),
                 Err(e) => (Err(e), ClausePriority::High),
             };
 
             if let (Ok(solution), priority) = res {
                 debug!(?solution, ?priority, "Ok");
                 cur_solution = Some(match cur_solution {
-                    None => (solution, priority),
-                    Some((cur, cur_priority)) => combine::with_priorities(
+                    None => (solution, priority, impl_id),
+                    Some(cur) => combine::with_priorities(
                         self.interner(),
                         &canonical_goal.canonical.value.goal,
                         cur,
-                        cur_priority,
-                        solution,
-                        priority,
+                        (solution, priority, impl_id),
                     ),
                 });
             } else {
@@ -186,8 +214,8 @@ trait SolveIterationHelpers<I: Interner>: SolveDatabase<I> {
             }
         }
 
-        if let Some((s, _)) = cur_solution {
-            Ok(s)
+        if let Some((s, _, impl_id)) = cur_solution {
+            Ok((s, impl_id))
         } else {
             Err(NoSolution)
         }
@@ -0,0 +1,246 @@
+//! Provides a wrapper over `RustIrDatabase` which memoizes the most
+//! frequently requested datums.
+use std::{
+    borrow::Borrow,
+    collections::HashMap,
+    fmt::Debug,
+    hash::Hash,
+    marker::PhantomData,
+    sync::{Arc, Mutex},
+};
+
+use crate::rust_ir::*;
+use crate::RustIrDatabase;
+use chalk_ir::{interner::Interner, *};
+
+/// Wraps another `RustIrDatabase` (`DB`) and caches the results of
+/// `trait_datum`, `adt_datum`, `impl_datum` and `associated_ty_data`, so
+/// that each of them is fetched from the underlying database only once per
+/// id. All other queries are forwarded unchanged.
+///
+/// The cache is never invalidated, so a `CachingRustIrDatabase` should
+/// only live as long as the underlying database does not change -- for
+/// example, for the duration of a single solve.
+///
+/// Uses a separate type, `P`, for the database stored inside to account for
+/// `Arc` or wrapping other storage mediums.
+#[derive(Debug)]
+pub struct CachingRustIrDatabase<I, DB, P = DB>
+where
+    DB: RustIrDatabase<I>,
+    P: Borrow<DB>,
+    I: Interner,
+{
+    db: P,
+    trait_data: Mutex<HashMap<TraitId<I>, Arc<TraitDatum<I>>>>,
+    adt_data: Mutex<HashMap<AdtId<I>, Arc<AdtDatum<I>>>>,
+    impl_data: Mutex<HashMap<ImplId<I>, Arc<ImplDatum<I>>>>,
+    associated_ty_data: Mutex<HashMap<AssocTypeId<I>, Arc<AssociatedTyDatum<I>>>>,
+    _phantom: PhantomData<DB>,
+}
+
+impl<I, DB, P> CachingRustIrDatabase<I, DB, P>
+where
+    DB: RustIrDatabase<I>,
+    P: Borrow<DB>,
+    I: Interner,
+{
+    pub fn new(db: P) -> Self {
+        CachingRustIrDatabase {
+            db,
+            trait_data: Default::default(),
+            adt_data: Default::default(),
+            impl_data: Default::default(),
+            associated_ty_data: Default::default(),
+            _phantom: PhantomData,
+        }
+    }
+
+    fn db(&self) -> &DB {
+        self.db.borrow()
+    }
+}
+
+/// Returns the cached value for `id`, computing and storing it with `fetch`
+/// if it is not present yet. The lock is not held while `fetch` runs, so
+/// the underlying database may itself call back into the wrapper.
+fn cached<K, V>(cache: &Mutex<HashMap<K, Arc<V>>>, id: K, fetch: impl FnOnce() -> Arc<V>) -> Arc<V>
+where
+    K: Eq + Hash,
+{
+    if let Some(value) = cache.lock().unwrap().get(&id) {
+        return value.clone();
+    }
+    let value = fetch();
+    cache.lock().unwrap().entry(id).or_insert(value).clone()
+}
+
+impl<I, DB, P> UnificationDatabase<I> for CachingRustIrDatabase<I, DB, P>
+where
+    DB: RustIrDatabase<I>,
+    P: Borrow<DB> + Debug,
+    I: Interner,
+{
+    fn fn_def_variance(&self, fn_def_id: FnDefId<I>) -> Variances<I> {
+        self.db().unification_database().fn_def_variance(fn_def_id)
+    }
+
+    fn adt_variance(&self, adt_id: AdtId<I>) -> Variances<I> {
+        self.db().unification_database().adt_variance(adt_id)
+    }
+}
+
+impl<I, DB, P> RustIrDatabase<I> for CachingRustIrDatabase<I, DB, P>
+where
+    DB: RustIrDatabase<I>,
+    P: Borrow<DB> + Debug,
+    I: Interner,
+{
+    fn custom_clauses(&self) -> Vec<ProgramClause<I>> {
+        self.db().custom_clauses()
+    }
+
+    fn associated_ty_data(&self, ty: AssocTypeId<I>) -> Arc<AssociatedTyDatum<I>> {
+        cached(&self.associated_ty_data, ty, || {
+            self.db().associated_ty_data(ty)
+        })
+    }
+
+    fn trait_datum(&self, trait_id: TraitId<I>) -> Arc<TraitDatum<I>> {
+        cached(&self.trait_data, trait_id, || {
+            self.db().trait_datum(trait_id)
+        })
+    }
+
+    fn adt_datum(&self, adt_id: AdtId<I>) -> Arc<AdtDatum<I>> {
+        cached(&self.adt_data, adt_id, || self.db().adt_datum(adt_id))
+    }
+
+    fn generator_datum(&self, generator_id: GeneratorId<I>) -> Arc<GeneratorDatum<I>> {
+        self.db().generator_datum(generator_id)
+    }
+
+    fn generator_witness_datum(
+        &self,
+        generator_id: GeneratorId<I>,
+    ) -> Arc<GeneratorWitnessDatum<I>> {
+        self.db().generator_witness_datum(generator_id)
+    }
+
+    fn adt_repr(&self, id: AdtId<I>) -> Arc<AdtRepr<I>> {
+        self.db().adt_repr(id)
+    }
+
+    fn fn_def_datum(&self, fn_def_id: FnDefId<I>) -> Arc<FnDefDatum<I>> {
+        self.db().fn_def_datum(fn_def_id)
+    }
+
+    fn impl_datum(&self, impl_id: ImplId<I>) -> Arc<ImplDatum<I>> {
+        cached(&self.impl_data, impl_id, || self.db().impl_datum(impl_id))
+    }
+
+    fn associated_ty_value(&self, id: AssociatedTyValueId<I>) -> Arc<AssociatedTyValue<I>> {
+        self.db().associated_ty_value(id)
+    }
+
+    fn opaque_ty_data(&self, id: OpaqueTyId<I>) -> Arc<OpaqueTyDatum<I>> {
+        self.db().opaque_ty_data(id)
+    }
+
+    fn hidden_opaque_type(&self, id: OpaqueTyId<I>) -> Ty<I> {
+        self.db().hidden_opaque_type(id)
+    }
+
+    fn impls_for_trait(
+        &self,
+        trait_id: TraitId<I>,
+        parameters: &[GenericArg<I>],
+        binders: &CanonicalVarKinds<I>,
+    ) -> Vec<ImplId<I>> {
+        self.db().impls_for_trait(trait_id, parameters, binders)
+    }
+
+    fn local_impls_to_coherence_check(&self, trait_id: TraitId<I>) -> Vec<ImplId<I>> {
+        self.db().local_impls_to_coherence_check(trait_id)
+    }
+
+    fn impl_provided_for(&self, auto_trait_id: TraitId<I>, ty: &TyKind<I>) -> bool {
+        self.db().impl_provided_for(auto_trait_id, ty)
+    }
+
+    fn well_known_trait_id(&self, well_known_trait: WellKnownTrait) -> Option<TraitId<I>> {
+        self.db().well_known_trait_id(well_known_trait)
+    }
+
+    fn program_clauses_for_env(&self, environment: &Environment<I>) -> ProgramClauses<I> {
+        self.db().program_clauses_for_env(environment)
+    }
+
+    fn interner(&self) -> &I {
+        self.db().interner()
+    }
+
+    fn is_object_safe(&self, trait_id: TraitId<I>) -> bool {
+        self.db().is_object_safe(trait_id)
+    }
+
+    fn closure_kind(&self, closure_id: ClosureId<I>, substs: &Substitution<I>) -> ClosureKind {
+        self.db().closure_kind(closure_id, substs)
+    }
+
+    fn closure_inputs_and_output(
+        &self,
+        closure_id: ClosureId<I>,
+        substs: &Substitution<I>,
+    ) -> Binders<FnDefInputsAndOutputDatum<I>> {
+        self.db().closure_inputs_and_output(closure_id, substs)
+    }
+
+    fn closure_upvars(&self, closure_id: ClosureId<I>, substs: &Substitution<I>) -> Binders<Ty<I>> {
+        self.db().closure_upvars(closure_id, substs)
+    }
+
+    fn closure_fn_substitution(
+        &self,
+        closure_id: ClosureId<I>,
+        substs: &Substitution<I>,
+    ) -> Substitution<I> {
+        self.db().closure_fn_substitution(closure_id, substs)
+    }
+
+    fn unification_database(&self) -> &dyn UnificationDatabase<I> {
+        self
+    }
+
+    fn is_copy_fast_path(&self, ty: &Ty<I>) -> Option<bool> {
+        self.db().is_copy_fast_path(ty)
+    }
+
+    fn is_whole_program(&self) -> bool {
+        self.db().is_whole_program()
+    }
+
+    fn trait_name(&self, trait_id: TraitId<I>) -> String {
+        self.db().trait_name(trait_id)
+    }
+
+    fn adt_name(&self, adt_id: AdtId<I>) -> String {
+        self.db().adt_name(adt_id)
+    }
+
+    fn assoc_type_name(&self, assoc_ty_id: AssocTypeId<I>) -> String {
+        self.db().assoc_type_name(assoc_ty_id)
+    }
+
+    fn opaque_type_name(&self, opaque_ty_id: OpaqueTyId<I>) -> String {
+        self.db().opaque_type_name(opaque_ty_id)
+    }
+
+    fn fn_def_name(&self, fn_def_id: FnDefId<I>) -> String {
+        self.db().fn_def_name(fn_def_id)
+    }
+
+    fn discriminant_type(&self, ty: Ty<I>) -> Ty<I> {
+        self.db().discriminant_type(ty)
+    }
+}
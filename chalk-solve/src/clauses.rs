@@ -2,15 +2,17 @@ use self::builder::ClauseBuilder;
 use self::env_elaborator::elaborate_env_clauses;
 use self::program_clauses::ToProgramClauses;
 use crate::goal_builder::GoalBuilder;
-use crate::rust_ir::{Movability, WellKnownTrait};
+use crate::rust_ir::{ImplDatum, Movability, TraitDatum, WellKnownTrait};
 use crate::split::Split;
 use crate::RustIrDatabase;
 use chalk_ir::cast::{Cast, Caster};
 use chalk_ir::could_match::CouldMatch;
+use chalk_ir::fold::shift::Shift;
 use chalk_ir::interner::Interner;
 use chalk_ir::*;
-use rustc_hash::FxHashSet;
+use rustc_hash::{FxHashMap, FxHashSet};
 use std::iter;
+use std::sync::Arc;
 use tracing::{debug, instrument};
 
 pub mod builder;
@@ -364,13 +366,16 @@ pub fn program_clauses_for_goal<'db, I: Interner>(
     let clauses_that_could_match =
         program_clauses_that_could_match(db, goal).map(|cl| cl.into_iter())?;
 
-    let clauses: Vec<ProgramClause<I>> = custom_clauses
+    // The clauses from the environment (e.g. the hypotheses of an `if`
+    // goal) come first, then those from the program, then the custom
+    // clauses. The solvers try clauses in the same order, which decides
+    // which answer the SLG solver finds first.
+    let clauses: Vec<ProgramClause<I>> = db
+        .program_clauses_for_env(&goal.canonical.value.environment)
+        .iter(interner)
+        .cloned()
         .chain(clauses_that_could_match)
-        .chain(
-            db.program_clauses_for_env(&goal.canonical.value.environment)
-                .iter(interner)
-                .cloned(),
-        )
+        .chain(custom_clauses)
         .filter(|c| {
             c.could_match(
                 interner,
@@ -385,6 +390,190 @@ pub fn program_clauses_for_goal<'db, I: Interner>(
     Ok(clauses)
 }
 
+/// Checks that the parameters of `trait_ref`, as well as those of its self
+/// type if that is an ADT, have the kinds declared by the trait and the ADT.
+fn check_parameter_kinds<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    trait_datum: &TraitDatum<I>,
+    trait_ref: &TraitRef<I>,
+) -> Fallible<()> {
+    let interner = db.interner();
+    trait_datum
+        .binders
+        .check_parameters(interner, &trait_ref.substitution)?;
+    if let TyKind::Adt(adt_id, substitution) =
+        trait_ref.self_type_parameter(interner).kind(interner)
+    {
+        db.adt_datum(*adt_id)
+            .binders
+            .check_parameters(interner, substitution)?;
+    }
+    Ok(())
+}
+
+/// For a `T: Copy` goal that the database can answer through
+/// `is_copy_fast_path`, returns the clauses to use instead of the program
+/// clauses: a single fact if `T` is known to be `Copy`, and none at all if
+/// it is known not to be.
+fn copy_fast_path_clauses<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    trait_ref: &TraitRef<I>,
+) -> Option<Vec<ProgramClause<I>>> {
+    let interner = db.interner();
+
+    // The fact we return must not mention the goal's canonical variables.
+    let self_ty = trait_ref.self_type_parameter(interner);
+    if self_ty.needs_shift(interner) {
+        return None;
+    }
+
+    if db.is_copy_fast_path(&self_ty)? {
+        Some(vec![trait_ref.clone().cast(interner)])
+    } else {
+        Some(vec![])
+    }
+}
+
+/// Whether `Implemented(T: Trait)` for an ADT or scalar `T` can only be
+/// proven through an impl of `Trait`. This is not the case for auto traits
+/// and well-known traits, which have impls that are not listed by
+/// `impls_for_trait`, for trait aliases, which are implemented whenever
+/// their bounds are, nor for traits with provided clauses, which may
+/// imply the trait for other types.
+fn implemented_only_by_impls<I: Interner>(trait_datum: &TraitDatum<I>) -> bool {
+    !trait_datum.is_auto_trait()
+        && !trait_datum.is_trait_alias()
+        && trait_datum.well_known.is_none()
+        && trait_datum.provided_clauses.is_empty()
+}
+
+/// The positive impls of each trait looked at by `conditions_could_hold`,
+/// or `None` for traits that can be implemented through other clauses
+/// than impls (see `implemented_only_by_impls`). Each trait is looked up
+/// in the database once.
+type TraitImpls<I> = FxHashMap<TraitId<I>, Option<Vec<Arc<ImplDatum<I>>>>>;
+
+fn trait_impls<'t, I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    trait_impls: &'t mut TraitImpls<I>,
+    trait_id: TraitId<I>,
+) -> Option<&'t [Arc<ImplDatum<I>>]> {
+    trait_impls
+        .entry(trait_id)
+        .or_insert_with(|| {
+            let interner = db.interner();
+            let trait_datum = db.trait_datum(trait_id);
+            if !implemented_only_by_impls(&trait_datum) {
+                return None;
+            }
+
+            // Ask for the impls that could match any parameters.
+            let kinds = &trait_datum.binders.binders;
+            let binders = CanonicalVarKinds::from_iter(
+                interner,
+                kinds
+                    .iter(interner)
+                    .map(|kind| CanonicalVarKind::new(kind.clone(), UniverseIndex::root())),
+            );
+            let parameters: Vec<_> = kinds
+                .iter(interner)
+                .enumerate()
+                .map(|pk| pk.to_generic_arg(interner))
+                .collect();
+            Some(
+                db.impls_for_trait(trait_id, &parameters, &binders)
+                    .into_iter()
+                    .map(|impl_id| db.impl_datum(impl_id))
+                    .filter(|impl_datum| impl_datum.is_positive())
+                    .collect(),
+            )
+        })
+        .as_deref()
+}
+
+/// A cheap check that none of the conditions of `clause` are trivially
+/// false. A condition `Implemented(T: Trait)` is trivially false if `T` is
+/// a rigid type (an ADT or scalar) and no impl of `Trait` could match it.
+/// Traits that can be implemented through other clauses than impls are
+/// skipped (see `implemented_only_by_impls`).
+///
+/// This assumes the goal's environment is empty, as otherwise the
+/// environment could provide the condition.
+fn conditions_could_hold<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    impls: &mut TraitImpls<I>,
+    clause: &ProgramClause<I>,
+) -> bool {
+    let interner = db.interner();
+    let clause = &clause.data(interner).0;
+
+    clause
+        .skip_binders()
+        .conditions
+        .iter(interner)
+        .all(
+            |condition| match peel_empty_binders(interner, condition).data(interner) {
+                GoalData::DomainGoal(DomainGoal::Holds(WhereClause::Implemented(trait_ref))) => {
+                    let self_ty = match trait_ref.type_parameters(interner).next() {
+                        Some(self_ty) => self_ty,
+                        None => return true,
+                    };
+                    match self_ty.kind(interner) {
+                        TyKind::Adt(..) | TyKind::Scalar(_) => {}
+                        _ => return true,
+                    }
+                    let impls = match trait_impls(db, impls, trait_ref.trait_id) {
+                        Some(impls) => impls,
+                        None => return true,
+                    };
+                    impls.iter().any(|impl_datum| {
+                        let impl_trait_ref = &impl_datum.binders.skip_binders().trait_ref;
+                        self_ty_heads_could_match(
+                            impl_trait_ref.self_type_parameter(interner).kind(interner),
+                            self_ty.kind(interner),
+                        ) && impl_trait_ref.could_match(
+                            interner,
+                            db.unification_database(),
+                            trait_ref,
+                        )
+                    })
+                }
+                _ => true,
+            },
+        )
+}
+
+/// Checks whether an impl for `impl_self_ty` could apply to the ADT or
+/// scalar type `self_ty`, looking only at the outermost type. (`could_match`
+/// is not enough here, as it assumes types of different kinds could match.)
+fn self_ty_heads_could_match<I: Interner>(impl_self_ty: &TyKind<I>, self_ty: &TyKind<I>) -> bool {
+    match impl_self_ty {
+        TyKind::BoundVar(_)
+        | TyKind::Alias(_)
+        | TyKind::Placeholder(_)
+        | TyKind::Dyn(_)
+        | TyKind::InferenceVar(..)
+        | TyKind::Error => true,
+        TyKind::Adt(id, _) => matches!(self_ty, TyKind::Adt(other, _) if id == other),
+        TyKind::Scalar(scalar) => matches!(self_ty, TyKind::Scalar(other) if scalar == other),
+        _ => false,
+    }
+}
+
+/// Strips `forall<> { .. }` wrappers (such as those around the where
+/// clauses of an impl) from `goal`.
+fn peel_empty_binders<I: Interner>(interner: &I, goal: &Goal<I>) -> Goal<I> {
+    match goal.data(interner) {
+        GoalData::Quantified(_, subgoal) if subgoal.binders.is_empty(interner) => {
+            match subgoal.skip_binders().clone().shifted_out(interner) {
+                Ok(subgoal) => peel_empty_binders(interner, &subgoal),
+                Err(_) => goal.clone(),
+            }
+        }
+        _ => goal.clone(),
+    }
+}
+
 /// Returns a set of program clauses that could possibly match
 /// `goal`. This can be any superset of the correct set, but the
 /// more precise you can make it, the more efficient solving will
@@ -409,11 +598,27 @@ pub fn program_clauses_that_could_match<I: Interner>(
 
     match goal {
         DomainGoal::Holds(WhereClause::Implemented(trait_ref)) => {
-            let self_ty = trait_ref.self_type_parameter(interner);
-
             let trait_id = trait_ref.trait_id;
             let trait_datum = db.trait_datum(trait_id);
 
+            // The clauses below are built by substituting the goal's
+            // parameters into the binders of the trait and of the self
+            // type, which panics if their kinds don't match. A goal like
+            // that can't hold.
+            if check_parameter_kinds(db, &trait_datum, trait_ref).is_err() {
+                debug!("mismatched parameter kinds");
+                return Ok(vec![]);
+            }
+
+            let self_ty = trait_ref.self_type_parameter(interner);
+
+            if trait_datum.well_known == Some(WellKnownTrait::Copy) {
+                if let Some(clauses) = copy_fast_path_clauses(db, trait_ref) {
+                    debug!(?clauses, "copy fast path");
+                    return Ok(clauses);
+                }
+            }
+
             match self_ty.kind(interner) {
                 TyKind::InferenceVar(_, _) => {
                     panic!("Inference vars not allowed when getting program clauses")
@@ -473,8 +678,10 @@ pub fn program_clauses_that_could_match<I: Interner>(
                 trait_ref.substitution.as_slice(interner),
                 binders,
             ) {
-                db.impl_datum(impl_id)
-                    .to_program_clauses(builder, environment);
+                builder.with_impl(impl_id, |builder| {
+                    db.impl_datum(impl_id)
+                        .to_program_clauses(builder, environment)
+                });
             }
 
             // If this is a `Foo: Send` (or any auto-trait), then add
@@ -644,9 +851,17 @@ pub fn program_clauses_that_could_match<I: Interner>(
             }
             AliasTy::Opaque(_) => (),
         },
-        DomainGoal::Compatible | DomainGoal::Reveal => (),
+        DomainGoal::Compatible | DomainGoal::Reveal | DomainGoal::NotHolds(_) => (),
     };
 
+    // Without custom or environment clauses, an `Implemented` goal on a
+    // rigid type can only be proven by an impl, which lets us discard
+    // clauses whose conditions no impl could satisfy.
+    if db.custom_clauses().is_empty() && environment.clauses.is_empty(interner) {
+        let mut impls = TraitImpls::default();
+        clauses.retain(|clause| conditions_could_hold(db, &mut impls, clause));
+    }
+
     Ok(clauses)
 }
 
@@ -939,8 +1154,10 @@ fn match_ty<I: Interner>(
         }
         TyKind::BoundVar(_) => return Err(Floundered),
         TyKind::Dyn(dyn_ty) => {
+            // A `dyn` type is well-formed if its traits are well-formed
+            // and object safe.
+            //
             // FIXME(#203)
-            // - Object safety? (not needed with RFC 2027)
             // - Implied bounds
             // - Bounds on the associated types
             // - Checking that all associated types are specified, including
@@ -958,9 +1175,10 @@ fn match_ty<I: Interner>(
             wf_goals.extend(bounds.iter(interner).flat_map(|bound| {
                 bound.map_ref(|bound| -> Vec<_> {
                     match bound {
-                        WhereClause::Implemented(trait_ref) => {
-                            vec![DomainGoal::WellFormed(WellFormed::Trait(trait_ref.clone()))]
-                        }
+                        WhereClause::Implemented(trait_ref) => vec![
+                            DomainGoal::WellFormed(WellFormed::Trait(trait_ref.clone())),
+                            DomainGoal::ObjectSafe(trait_ref.trait_id),
+                        ],
                         WhereClause::AliasEq(_)
                         | WhereClause::LifetimeOutlives(_)
                         | WhereClause::TypeOutlives(_) => vec![],
@@ -992,27 +1210,27 @@ pub fn program_clauses_for_env<'db, I: Interner>(
     db: &'db dyn RustIrDatabase<I>,
     environment: &Environment<I>,
 ) -> ProgramClauses<I> {
-    let mut last_round = environment
+    // The clauses of the environment itself come first, in their original
+    // order, followed by the clauses elaborated from them, in the order
+    // they were found.
+    let mut closure = FxHashSet::default();
+    let mut clauses: Vec<_> = environment
         .clauses
         .as_slice(db.interner())
         .iter()
+        .filter(|&clause| closure.insert(clause.clone()))
         .cloned()
-        .collect::<FxHashSet<_>>();
-    let mut closure = last_round.clone();
-    let mut next_round = FxHashSet::default();
+        .collect();
+    let mut last_round = clauses.clone();
+    let mut next_round = vec![];
     while !last_round.is_empty() {
-        elaborate_env_clauses(
-            db,
-            &last_round.drain().collect::<Vec<_>>(),
-            &mut next_round,
-            environment,
-        );
-        last_round.extend(
-            next_round
-                .drain()
-                .filter(|clause| closure.insert(clause.clone())),
-        );
+        elaborate_env_clauses(db, &last_round, &mut next_round, environment);
+        last_round = next_round
+            .drain(..)
+            .filter(|clause| closure.insert(clause.clone()))
+            .collect();
+        clauses.extend(last_round.iter().cloned());
     }
 
-    ProgramClauses::from_iter(db.interner(), closure)
+    ProgramClauses::from_iter(db.interner(), clauses)
 }
@@ -16,6 +16,7 @@ pub struct ClauseBuilder<'me, I: Interner> {
     clauses: &'me mut Vec<ProgramClause<I>>,
     binders: Vec<VariableKind<I>>,
     parameters: Vec<GenericArg<I>>,
+    impl_id: Option<ImplId<I>>,
 }
 
 impl<'me, I: Interner> ClauseBuilder<'me, I> {
@@ -25,6 +26,7 @@ impl<'me, I: Interner> ClauseBuilder<'me, I> {
             clauses,
             binders: vec![],
             parameters: vec![],
+            impl_id: None,
         }
     }
 
@@ -87,6 +89,7 @@ impl<'me, I: Interner> ClauseBuilder<'me, I> {
             conditions: Goals::from_iter(interner, conditions),
             constraints: Constraints::from_iter(interner, constraints),
             priority,
+            impl_id: self.impl_id,
         };
 
         let clause = if self.binders.is_empty() {
@@ -107,6 +110,23 @@ impl<'me, I: Interner> ClauseBuilder<'me, I> {
         debug!("pushed clause {:?}", self.clauses.last());
     }
 
+    /// Pushes a clause that the database has already built, such as one
+    /// of `TraitDatum::provided_clauses`. The clause must be closed, so
+    /// the binders pushed so far don't apply to it.
+    pub fn push_program_clause(&mut self, clause: ProgramClause<I>) {
+        self.clauses.push(clause);
+        debug!("pushed clause {:?}", self.clauses.last());
+    }
+
+    /// Executes `op`, marking the clauses that it pushes as coming from
+    /// `impl_id`.
+    pub fn with_impl<R>(&mut self, impl_id: ImplId<I>, op: impl FnOnce(&mut Self) -> R) -> R {
+        let old_impl_id = self.impl_id.replace(impl_id);
+        let result = op(self);
+        self.impl_id = old_impl_id;
+        result
+    }
+
     /// Accesses the placeholders for the current list of parameters in scope.
     pub fn placeholders_in_scope(&self) -> &[GenericArg<I>] {
         &self.parameters
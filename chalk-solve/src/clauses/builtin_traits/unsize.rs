@@ -1,6 +1,7 @@
 use std::collections::HashSet;
 use std::iter;
 
+use crate::clauses::super_traits::super_traits;
 use crate::clauses::ClauseBuilder;
 use crate::rust_ir::AdtKind;
 use crate::{Interner, RustIrDatabase, TraitRef, WellKnownTrait};
@@ -205,12 +206,53 @@ pub fn add_unsize_program_clauses<I: Interner>(
             let auto_trait_ids_a: Vec<_> = auto_trait_ids(db, bounds_a).collect();
             let auto_trait_ids_b: Vec<_> = auto_trait_ids(db, bounds_b).collect();
 
-            let may_apply = principal_a == principal_b
-                && auto_trait_ids_b
+            let auto_traits_compatible = auto_trait_ids_b
+                .iter()
+                .all(|id_b| auto_trait_ids_a.iter().any(|id_a| id_a == id_b));
+
+            if !auto_traits_compatible {
+                return;
+            }
+
+            // dyn Sub + 'a -> dyn Super + 'b, where `Super` is a supertrait of `Sub`
+            if principal_a != principal_b {
+                let (principal_a, principal_b) = match (principal_a, principal_b) {
+                    (Some(a), Some(b)) => (a, b),
+                    _ => return,
+                };
+                let is_supertrait = super_traits(db, principal_a)
+                    .skip_binders()
                     .iter()
-                    .all(|id_b| auto_trait_ids_a.iter().any(|id_a| id_a == id_b));
+                    .any(|trait_ref| trait_ref.skip_binders().trait_id == principal_b);
+                if !is_supertrait {
+                    return;
+                }
 
-            if !may_apply {
+                // The source type must implement every bound of the target
+                // type, which it does through the supertrait clauses of
+                // `dyn Sub` if the generic parameters line up.
+                let target_bounds = bounds_b
+                    .clone()
+                    .substitute(interner, &Substitution::from1(interner, source_ty.clone()));
+
+                // Check that source lifetime outlives target lifetime
+                let lifetime_outlives_goal: Goal<I> =
+                    WhereClause::LifetimeOutlives(LifetimeOutlives {
+                        a: lifetime_a.clone(),
+                        b: lifetime_b.clone(),
+                    })
+                    .cast(interner);
+
+                let object_safe_goal: Goal<I> = DomainGoal::ObjectSafe(principal_b).cast(interner);
+
+                builder.push_clause(
+                    trait_ref,
+                    target_bounds
+                        .iter(interner)
+                        .map(|bound| bound.clone().cast::<Goal<I>>(interner))
+                        .chain(iter::once(object_safe_goal))
+                        .chain(iter::once(lifetime_outlives_goal)),
+                );
                 return;
             }
 
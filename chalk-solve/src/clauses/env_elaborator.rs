@@ -10,7 +10,6 @@ use crate::{debug_span, TyKind};
 use chalk_ir::interner::Interner;
 use chalk_ir::visit::{ControlFlow, Visit, Visitor};
 use chalk_ir::{DebruijnIndex, Environment};
-use rustc_hash::FxHashSet;
 use tracing::instrument;
 
 /// When proving a `FromEnv` goal, we elaborate all `FromEnv` goals
@@ -22,7 +21,7 @@ use tracing::instrument;
 pub(super) fn elaborate_env_clauses<I: Interner>(
     db: &dyn RustIrDatabase<I>,
     in_clauses: &[ProgramClause<I>],
-    out: &mut FxHashSet<ProgramClause<I>>,
+    out: &mut Vec<ProgramClause<I>>,
     environment: &Environment<I>,
 ) {
     let mut this_round = vec![];
@@ -61,7 +61,7 @@ impl<I: Interner> ToProgramClauses<I> for AssociatedTyValue<I> {
     /// Then for the following impl:
     /// ```notrust
     /// impl<T> Iterable for Vec<T> where T: Clone {
-    ///     type IntoIter<'a> = Iter<'a, T>;
+    ///     type IntoIter<'a> = Iter<'a, T> where T: Debug;
     /// }
     /// ```
     ///
@@ -72,7 +72,8 @@ impl<I: Interner> ToProgramClauses<I> for AssociatedTyValue<I> {
     /// forall<'a, T> {
     ///     Normalize(<Vec<T> as Iterable>::IntoIter<'a> -> Iter<'a, T>>) :-
     ///         Implemented(T: Clone),  // (1)
-    ///         Implemented(Iter<'a, T>: 'a).   // (2)
+    ///         Implemented(Iter<'a, T>: 'a),   // (2)
+    ///         Implemented(T: Debug).   // (3)
     /// }
     /// ```
     fn to_program_clauses(
@@ -113,6 +114,9 @@ impl<I: Interner> ToProgramClauses<I> for AssociatedTyValue<I> {
                 .into_iter()
                 .map(|wc| wc.cloned().substitute(interner, &projection.substitution));
 
+            // 3. any where-clauses on the `type` value in the impl itself
+            let value_where_clauses = assoc_ty_value.where_clauses;
+
             // Create the final program clause:
             //
             // ```notrust
@@ -120,7 +124,8 @@ impl<I: Interner> ToProgramClauses<I> for AssociatedTyValue<I> {
             // forall<'a, T> {
             //     Normalize(<Vec<T> as Iterable>::IntoIter<'a> -> Iter<'a, T>>) :-
             //         Implemented(T: Clone),  // (1)
-            //         Implemented(Iter<'a, T>: 'a).   // (2)
+            //         Implemented(Iter<'a, T>: 'a),   // (2)
+            //         Implemented(T: Debug).   // (3)
             // }
             // ```
             builder.push_clause(
@@ -128,7 +133,9 @@ impl<I: Interner> ToProgramClauses<I> for AssociatedTyValue<I> {
                     alias: AliasTy::Projection(projection.clone()),
                     ty: assoc_ty_value.ty,
                 },
-                impl_where_clauses.chain(assoc_ty_where_clauses),
+                impl_where_clauses
+                    .chain(assoc_ty_where_clauses)
+                    .chain(value_where_clauses),
             );
         });
     }
@@ -373,6 +380,11 @@ impl<I: Interner> ToProgramClauses<I> for AdtDatum<I> {
     /// forall<T, U> { DownstreamType(Box<T, U>) :- DownstreamType(U). }
     /// ```
     ///
+    /// A fundamental type without type parameters (a zero-field marker)
+    /// therefore gets no `IsLocal` or `DownstreamType` rules from its
+    /// parameters, and behaves like any other type with the same
+    /// `#[upstream]` flag.
+    ///
     #[instrument(level = "debug", skip(builder))]
     fn to_program_clauses(
         &self,
@@ -423,14 +435,6 @@ impl<I: Interner> ToProgramClauses<I> for AdtDatum<I> {
             }
 
             if self.flags.fundamental {
-                assert!(
-                    builder
-                        .substitution_in_scope()
-                        .type_parameters(interner)
-                        .count()
-                        >= 1,
-                    "Only fundamental types with type parameters are supported"
-                );
                 for type_param in builder.substitution_in_scope().type_parameters(interner) {
                     builder.push_clause(
                         DomainGoal::DownstreamType(self_ty.clone()),
@@ -621,7 +625,21 @@ impl<I: Interner> ToProgramClauses<I> for TraitDatum<I> {
             // conditions.
             let type_parameters: Vec<_> = trait_ref.type_parameters(interner).collect();
 
-            if environment.has_compatible_clause(interner) {
+            // A trait alias `trait Alias = Foo + Bar` has no impls of its
+            // own; it is implemented exactly when its bounds are:
+            //
+            // ```
+            // Implemented(Self: Alias) :- Implemented(Self: Foo), Implemented(Self: Bar)
+            // ```
+            //
+            // Downstream crates can't implement it either, so the
+            // compatible-mode rules below don't apply.
+            if self.is_trait_alias() {
+                builder.push_clause(
+                    trait_ref.clone(),
+                    where_clauses.iter().cloned().casted::<Goal<_>>(interner),
+                );
+            } else if environment.has_compatible_clause(interner) {
                 // Note: even though we do check for a `Compatible` clause here,
                 // we also keep it as a condition for the clauses below, purely
                 // for logical consistency. But really, it's not needed and could be
@@ -676,8 +694,9 @@ impl<I: Interner> ToProgramClauses<I> for TraitDatum<I> {
             }
 
             // Orphan rules:
-            if !self.flags.upstream {
-                // Impls for traits declared locally always pass the impl rules
+            if !self.flags.upstream || builder.db.is_whole_program() {
+                // Impls for traits declared locally always pass the impl rules,
+                // as do all impls when every crate is treated as local
                 builder.push_fact(DomainGoal::LocalImplAllowed(trait_ref.clone()));
             } else {
                 // Impls for remote traits must have a local type in the right place
@@ -719,6 +738,11 @@ impl<I: Interner> ToProgramClauses<I> for TraitDatum<I> {
             // ```
             builder.push_clause(trait_ref.clone(), Some(trait_ref.from_env()));
         });
+
+        // The clauses the trait provides already require `Self: Foo`.
+        for clause in &self.provided_clauses {
+            builder.push_program_clause(clause.clone());
+        }
     }
 }
 
@@ -38,6 +38,20 @@ impl<I: Interner> fmt::Display for CoherenceError<I> {
 
 impl<I: Interner> std::error::Error for CoherenceError<I> {}
 
+/// Checks a single impl, e.g. one that was just added, against the
+/// existing impls of its trait. Rather than recomputing every pair like
+/// [`CoherenceSolver::specialization_priorities`], this only compares
+/// `impl_id` with the impls whose trait ref could unify with its own.
+/// Overlap is allowed only if one of the two impls specializes the other.
+pub fn check_impl_against<I: Interner>(
+    db: &dyn RustIrDatabase<I>,
+    solver_builder: &dyn Fn() -> Box<dyn Solver<I>>,
+    impl_id: ImplId<I>,
+) -> Result<(), CoherenceError<I>> {
+    let trait_id = db.impl_datum(impl_id).trait_id();
+    CoherenceSolver::new(db, solver_builder, trait_id).check_impl_against_others(impl_id)
+}
+
 /// Stores the specialization priorities for a set of impls.
 /// This basically encodes which impls specialize one another.
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
@@ -21,27 +21,72 @@ impl<I: Interner> CoherenceSolver<'_, I> {
             return Ok(());
         }
 
-        // Iterate over every pair of impls for the same trait.
-        let impls = self.db.local_impls_to_coherence_check(self.trait_id);
-        for (l_id, r_id) in impls.into_iter().tuple_combinations() {
-            let lhs = &self.db.impl_datum(l_id);
-            let rhs = &self.db.impl_datum(r_id);
+        // Iterate over every pair of local impls for the same trait, as well
+        // as every local impl paired with each upstream impl that it could
+        // overlap with. Upstream impls are never checked against each other;
+        // that was the job of the crate that defined them.
+        let local_impls = self.db.local_impls_to_coherence_check(self.trait_id);
+        let mut impl_pairs: Vec<_> = local_impls.iter().copied().tuple_combinations().collect();
+        for &local_id in &local_impls {
+            impl_pairs.extend(
+                self.impls_that_could_overlap(local_id)
+                    .into_iter()
+                    .filter(|&id| self.db.impl_datum(id).impl_type == ImplType::External)
+                    .map(|upstream_id| (local_id, upstream_id)),
+            );
+        }
+
+        for (l_id, r_id) in impl_pairs {
+            self.check_impl_pair(l_id, r_id, &mut record_specialization)?;
+        }
+
+        Ok(())
+    }
+
+    /// Checks the impl `impl_id` against the other impls of our trait
+    /// that it could overlap with, local or upstream. The remaining impls
+    /// are neither compared with each other nor fetched at all.
+    pub(super) fn check_impl_against_others(
+        &self,
+        impl_id: ImplId<I>,
+    ) -> Result<(), CoherenceError<I>> {
+        let trait_datum = self.db.trait_datum(self.trait_id);
+        if trait_datum.flags.marker {
+            return Ok(());
+        }
 
-            // Two negative impls never overlap.
-            if !lhs.is_positive() && !rhs.is_positive() {
-                continue;
+        for other_id in self.impls_that_could_overlap(impl_id) {
+            if other_id != impl_id {
+                self.check_impl_pair(impl_id, other_id, &mut |_, _| {})?;
             }
+        }
 
-            // Check if the impls overlap, then if they do, check if one specializes
-            // the other. Note that specialization can only run one way - if both
-            // specialization checks return *either* true or false, that's an error.
-            if !self.disjoint(lhs, rhs) {
-                match (self.specializes(l_id, r_id), self.specializes(r_id, l_id)) {
-                    (true, false) => record_specialization(l_id, r_id),
-                    (false, true) => record_specialization(r_id, l_id),
-                    (_, _) => {
-                        return Err(CoherenceError::OverlappingImpls(self.trait_id));
-                    }
+        Ok(())
+    }
+
+    fn check_impl_pair(
+        &self,
+        l_id: ImplId<I>,
+        r_id: ImplId<I>,
+        record_specialization: &mut impl FnMut(ImplId<I>, ImplId<I>),
+    ) -> Result<(), CoherenceError<I>> {
+        let lhs = &self.db.impl_datum(l_id);
+        let rhs = &self.db.impl_datum(r_id);
+
+        // Two negative impls never overlap.
+        if !lhs.is_positive() && !rhs.is_positive() {
+            return Ok(());
+        }
+
+        // Check if the impls overlap, then if they do, check if one specializes
+        // the other. Note that specialization can only run one way - if both
+        // specialization checks return *either* true or false, that's an error.
+        if !self.disjoint(lhs, rhs) {
+            match (self.specializes(l_id, r_id), self.specializes(r_id, l_id)) {
+                (true, false) => record_specialization(l_id, r_id),
+                (false, true) => record_specialization(r_id, l_id),
+                (_, _) => {
+                    return Err(CoherenceError::OverlappingImpls(self.trait_id));
                 }
             }
         }
@@ -49,6 +94,27 @@ impl<I: Interner> CoherenceSolver<'_, I> {
         Ok(())
     }
 
+    /// Returns the impls of our trait whose trait ref could unify with
+    /// the one of the impl `impl_id` (including `impl_id` itself).
+    fn impls_that_could_overlap(&self, impl_id: ImplId<I>) -> Vec<ImplId<I>> {
+        let interner = self.db.interner();
+        let impl_datum = self.db.impl_datum(impl_id);
+        let binders = CanonicalVarKinds::from_iter(
+            interner,
+            impl_datum
+                .binders
+                .binders
+                .iter(interner)
+                .map(|kind| CanonicalVarKind::new(kind.clone(), UniverseIndex::root())),
+        );
+        let trait_ref = &impl_datum.binders.skip_binders().trait_ref;
+        self.db.impls_for_trait(
+            self.trait_id,
+            trait_ref.substitution.as_slice(interner),
+            &binders,
+        )
+    }
+
     // Test if the set of types that these two impls apply to overlap. If the test succeeds, these
     // two impls are disjoint.
     //
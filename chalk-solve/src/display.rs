@@ -14,6 +14,7 @@ use crate::{logging_db::RecordedItemId, split::Split, RustIrDatabase};
 mod utils;
 
 mod bounds;
+mod goals;
 mod identifiers;
 mod items;
 mod render_trait;
@@ -102,6 +103,29 @@ where
     Ok(())
 }
 
+/// Writes a where clause declared on the trait or ADT `item`, naming the
+/// item's parameters as `write_items` does when writing the item.
+pub fn write_item_where_clause<F, I, DB, P>(
+    f: &mut F,
+    ws: &WriterState<I, DB, P>,
+    item: RecordedItemId<I>,
+    where_clause: &Binders<QuantifiedWhereClause<I>>,
+) -> Result
+where
+    F: std::fmt::Write + ?Sized,
+    I: Interner,
+    DB: RustIrDatabase<I>,
+    P: Borrow<DB>,
+{
+    // The first parameter of a trait is `Self`.
+    let self_binding = match item {
+        RecordedItemId::Trait(_) => Some(0),
+        _ => None,
+    };
+    let s = &InternalWriterState::new(ws).add_debrujin_index(self_binding);
+    write!(f, "{}", where_clause.skip_binders().display(s))
+}
+
 /// Displays a set of bounds, all targeting `Self`, as just the trait names,
 /// separated by `+`.
 ///
@@ -0,0 +1,144 @@
+//! Writer logic for goals and program clauses.
+//!
+//! These only show up in the provided clauses of traits, so this writes them
+//! the way they are written there: `C :- G1, G2`, `forall<T> { C :- G }` and
+//! so on.
+use std::fmt::{Formatter, Result};
+
+use chalk_ir::{interner::Interner, *};
+use itertools::Itertools;
+
+use super::{render_trait::RenderAsRust, state::InternalWriterState};
+
+impl<I: Interner> RenderAsRust<I> for DomainGoal<I> {
+    fn fmt(&self, s: &InternalWriterState<'_, I>, f: &'_ mut Formatter<'_>) -> Result {
+        match self {
+            DomainGoal::Holds(where_clause) => where_clause.fmt(s, f),
+            DomainGoal::WellFormed(WellFormed::Ty(ty)) => {
+                write!(f, "WellFormed({})", ty.display(s))
+            }
+            DomainGoal::WellFormed(WellFormed::Trait(trait_ref)) => {
+                write!(f, "WellFormed({})", trait_ref.display(s))
+            }
+            DomainGoal::FromEnv(FromEnv::Ty(ty)) => write!(f, "FromEnv({})", ty.display(s)),
+            DomainGoal::FromEnv(FromEnv::Trait(trait_ref)) => {
+                write!(f, "FromEnv({})", trait_ref.display(s))
+            }
+            DomainGoal::Normalize(normalize) => write!(
+                f,
+                "Normalize({} -> {})",
+                normalize.alias.display(s),
+                normalize.ty.display(s)
+            ),
+            DomainGoal::IsLocal(ty) => write!(f, "IsLocal({})", ty.display(s)),
+            DomainGoal::IsUpstream(ty) => write!(f, "IsUpstream({})", ty.display(s)),
+            DomainGoal::IsFullyVisible(ty) => write!(f, "IsFullyVisible({})", ty.display(s)),
+            DomainGoal::LocalImplAllowed(trait_ref) => {
+                write!(f, "LocalImplAllowed({})", trait_ref.display(s))
+            }
+            DomainGoal::Compatible => write!(f, "Compatible"),
+            DomainGoal::DownstreamType(ty) => write!(f, "DownstreamType({})", ty.display(s)),
+            DomainGoal::Reveal => write!(f, "Reveal"),
+            DomainGoal::ObjectSafe(trait_id) => write!(f, "ObjectSafe({})", trait_id.display(s)),
+            DomainGoal::NotHolds(where_clause) => {
+                write!(f, "NotHolds({})", where_clause.display(s))
+            }
+        }
+    }
+}
+
+impl<I: Interner> RenderAsRust<I> for Goal<I> {
+    fn fmt(&self, s: &InternalWriterState<'_, I>, f: &'_ mut Formatter<'_>) -> Result {
+        let interner = s.db().interner();
+        match self.data(interner) {
+            GoalData::Quantified(kind, subgoal) => {
+                let quantifier = match kind {
+                    QuantifierKind::ForAll => "forall",
+                    QuantifierKind::Exists => "exists",
+                };
+                let s = &s.add_debrujin_index(None);
+                let binders = s.binder_var_display(&subgoal.binders).join(", ");
+                write!(
+                    f,
+                    "{}<{}> {{ {} }}",
+                    quantifier,
+                    binders,
+                    subgoal.skip_binders().display(s)
+                )
+            }
+            GoalData::Implies(hypotheses, subgoal) => write!(
+                f,
+                "if ({}) {{ {} }}",
+                hypotheses
+                    .iter(interner)
+                    .map(|clause| clause.display(s))
+                    .format("; "),
+                subgoal.display(s)
+            ),
+            // Within a list of conditions, a conjunction has to be
+            // parenthesized.
+            GoalData::All(goals) => write!(
+                f,
+                "({})",
+                goals
+                    .iter(interner)
+                    .map(|goal| goal.display(s))
+                    .format(", ")
+            ),
+            GoalData::Not(subgoal) => write!(f, "not {{ {} }}", subgoal.display(s)),
+            GoalData::EqGoal(eq_goal) => {
+                write!(f, "{} = {}", eq_goal.a.display(s), eq_goal.b.display(s))
+            }
+            GoalData::SubtypeGoal(subtype_goal) => write!(
+                f,
+                "Subtype({}, {})",
+                subtype_goal.a.display(s),
+                subtype_goal.b.display(s)
+            ),
+            GoalData::DomainGoal(domain_goal) => domain_goal.fmt(s, f),
+            GoalData::CannotProve => write!(f, "CannotProve"),
+        }
+    }
+}
+
+/// Writes `C :- G1, G2`. The conditions are stored in reverse (see
+/// `ProgramClauseImplication::with_conditions`), so this writes them back in
+/// source order.
+impl<I: Interner> RenderAsRust<I> for ProgramClauseImplication<I> {
+    fn fmt(&self, s: &InternalWriterState<'_, I>, f: &'_ mut Formatter<'_>) -> Result {
+        let interner = s.db().interner();
+        write!(f, "{}", self.consequence.display(s))?;
+        let conditions = self.conditions.as_slice(interner);
+        if !conditions.is_empty() {
+            write!(
+                f,
+                " :- {}",
+                conditions
+                    .iter()
+                    .rev()
+                    .map(|goal| goal.display(s))
+                    .format(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl<I: Interner> RenderAsRust<I> for ProgramClause<I> {
+    fn fmt(&self, s: &InternalWriterState<'_, I>, f: &'_ mut Formatter<'_>) -> Result {
+        let interner = s.db().interner();
+        let implication = &self.data(interner).0;
+        let s = &s.add_debrujin_index(None);
+        if implication.binders.is_empty(interner) {
+            implication.skip_binders().fmt(s, f)
+        } else {
+            let binders = s.binder_var_display(&implication.binders).join(", ");
+            write!(
+                f,
+                "forall<{}> {{ {} }}",
+                binders,
+                implication.skip_binders().display(s)
+            )
+        }
+    }
+}
@@ -8,6 +8,10 @@ use std::fmt::{Formatter, Result};
 use crate::rust_ir::*;
 use crate::split::Split;
 use chalk_ir::interner::Interner;
+use chalk_ir::{
+    AliasEq, AliasTy, BoundVar, DebruijnIndex, Goals, ProgramClause, QuantifiedWhereClause, TyKind,
+    TyVariableKind, VariableKind, VariableKinds, WhereClause,
+};
 use itertools::Itertools;
 
 use super::{
@@ -63,6 +67,74 @@ macro_rules! write_flags {
     };
 }
 
+/// Displays the parameters of an item, marking those that are `?Sized`.
+fn display_params<'a, I: Interner>(
+    s: &'a InternalWriterState<'a, I>,
+    binders: &'a VariableKinds<I>,
+    maybe_sized: &'a [bool],
+) -> impl Iterator<Item = String> + 'a {
+    s.binder_var_display(binders)
+        .zip(maybe_sized)
+        .map(|(name, &maybe_sized)| {
+            if maybe_sized {
+                format!("{}: ?Sized", name)
+            } else {
+                name
+            }
+        })
+}
+
+/// Splits the `T: Sized` bounds on an item's type parameters off its where
+/// clauses. Lowering gives every type parameter such a bound unless it is
+/// declared `?Sized`, so a parameter without one is displayed as `?Sized`
+/// and the first bound of each other parameter is left out, since parsing
+/// the item adds it back.
+///
+/// Returns whether each parameter is `?Sized`, and the remaining where
+/// clauses. Without a `Sized` trait, no parameter is `?Sized`.
+fn split_sized_bounds<I: Interner>(
+    s: &InternalWriterState<'_, I>,
+    binders: &VariableKinds<I>,
+    where_clauses: &[QuantifiedWhereClause<I>],
+) -> (Vec<bool>, Vec<QuantifiedWhereClause<I>>) {
+    let interner = s.db().interner();
+    let sized_trait_id = match s.db().well_known_trait_id(WellKnownTrait::Sized) {
+        Some(id) => id,
+        None => return (vec![false; binders.len(interner)], where_clauses.to_vec()),
+    };
+
+    // Within the binders of a where clause, the item's parameters are
+    // bound at `^1`.
+    let sized_param = |qwc: &QuantifiedWhereClause<I>| match qwc.skip_binders() {
+        WhereClause::Implemented(trait_ref)
+            if trait_ref.trait_id == sized_trait_id && qwc.binders.is_empty(interner) =>
+        {
+            trait_ref
+                .self_type_parameter(interner)
+                .bound_var(interner)
+                .filter(|bv| bv.debruijn == DebruijnIndex::ONE)
+                .map(|bv| bv.index)
+        }
+        _ => None,
+    };
+    let mut maybe_sized: Vec<_> = binders
+        .iter(interner)
+        .map(|kind| matches!(kind, VariableKind::Ty(TyVariableKind::General)))
+        .collect();
+    let where_clauses = where_clauses
+        .iter()
+        .filter(|qwc| match sized_param(qwc) {
+            Some(idx) if maybe_sized.get(idx) == Some(&true) => {
+                maybe_sized[idx] = false;
+                false
+            }
+            _ => true,
+        })
+        .cloned()
+        .collect();
+    (maybe_sized, where_clauses)
+}
+
 impl<'a, I: Interner> RenderAsRust<I> for (&'a GeneratorDatum<I>, &'a GeneratorWitnessDatum<I>) {
     fn fmt(&self, _s: &InternalWriterState<'_, I>, _f: &'_ mut Formatter<'_>) -> Result {
         unimplemented!()
@@ -107,12 +179,19 @@ impl<I: Interner> RenderAsRust<I> for AdtDatum<I> {
             AdtKind::Enum => write!(f, "enum {}", self.id.display(s),)?,
             AdtKind::Union => write!(f, "union {}", self.id.display(s),)?,
         }
-        write_joined_non_empty_list!(f, "<{}>", s.binder_var_display(&self.binders.binders), ", ")?;
+        let (maybe_sized, where_clauses) =
+            split_sized_bounds(s, &self.binders.binders, &value.where_clauses);
+        write_joined_non_empty_list!(
+            f,
+            "<{}>",
+            display_params(s, &self.binders.binders, &maybe_sized),
+            ", "
+        )?;
 
         // where clauses
-        if !value.where_clauses.is_empty() {
+        if !where_clauses.is_empty() {
             let s = &s.add_indent();
-            write!(f, "\nwhere\n{}\n", value.where_clauses.display(s))?;
+            write!(f, "\nwhere\n{}\n", where_clauses.display(s))?;
         } else {
             write!(f, " ")?;
         }
@@ -170,6 +249,10 @@ impl<I: Interner> RenderAsRust<I> for TraitDatum<I> {
         let s = &s.add_debrujin_index(Some(0));
         let value = self.binders.skip_binders();
 
+        if self.is_trait_alias() {
+            return display_trait_alias(self, s, f);
+        }
+
         // flags
         write_flags!(
             f,
@@ -180,7 +263,8 @@ impl<I: Interner> RenderAsRust<I> for TraitDatum<I> {
                 upstream,
                 fundamental,
                 non_enumerable,
-                coinductive
+                coinductive,
+                alias
             }
         );
 
@@ -208,14 +292,16 @@ impl<I: Interner> RenderAsRust<I> for TraitDatum<I> {
         }
 
         // trait declaration
-        let binders = s.binder_var_display(&self.binders.binders).skip(1);
+        let (maybe_sized, where_clauses) =
+            split_sized_bounds(s, &self.binders.binders, &value.where_clauses);
+        let binders = display_params(s, &self.binders.binders, &maybe_sized).skip(1);
         write!(f, "trait {}", self.id.display(s))?;
         write_joined_non_empty_list!(f, "<{}>", binders, ", ")?;
 
         // where clauses
-        if !value.where_clauses.is_empty() {
+        if !where_clauses.is_empty() {
             let s = &s.add_indent();
-            write!(f, "\nwhere\n{}\n", value.where_clauses.display(s))?;
+            write!(f, "\nwhere\n{}\n", where_clauses.display(s))?;
         } else {
             write!(f, " ")?;
         }
@@ -223,27 +309,146 @@ impl<I: Interner> RenderAsRust<I> for TraitDatum<I> {
         // body
         write!(f, "{{")?;
         let s = &s.add_indent();
-        write_joined_non_empty_list!(
-            f,
-            "\n{}\n",
-            self.associated_ty_ids.iter().map(|assoc_ty_id| {
-                let assoc_ty_data = s.db().associated_ty_data(*assoc_ty_id);
-                format!("{}{}", s.indent(), (*assoc_ty_data).display(s))
-            }),
-            "\n"
-        )?;
+        let assoc_tys = self.associated_ty_ids.iter().map(|assoc_ty_id| {
+            let assoc_ty_data = s.db().associated_ty_data(*assoc_ty_id);
+            format!("{}{}", s.indent(), (*assoc_ty_data).display(s))
+        });
+        let provided_clauses = self
+            .provided_clauses
+            .iter()
+            .map(|clause| format!("{}{}", s.indent(), display_provided_clause(s, self, clause)));
+        write_joined_non_empty_list!(f, "\n{}\n", assoc_tys.chain(provided_clauses), "\n")?;
         write!(f, "}}")?;
         Ok(())
     }
 }
 
+/// Writes a provided clause of a trait as `clause forall<..> { C :- G };`.
+///
+/// Lowering puts the trait's own parameters first in the clause's binders
+/// and adds an `Implemented(Self: Trait<..>)` condition (see
+/// `TraitDatum::provided_clauses`). We map those parameters back to the
+/// trait's names and leave out the added condition, which is stored first
+/// since conditions are stored in reverse.
+fn display_provided_clause<I: Interner>(
+    s: &InternalWriterState<'_, I>,
+    trait_datum: &TraitDatum<I>,
+    clause: &ProgramClause<I>,
+) -> String {
+    let interner = s.db().interner();
+    let implication = &clause.data(interner).0;
+    let trait_params_len = trait_datum.binders.len(interner);
+
+    // see comments for a similar mapping in AssociatedTyDatum's `fmt`
+    let trait_param_names_in_trait_env = s.binder_var_indices(&trait_datum.binders.binders);
+    let s = &s.add_debrujin_index(None);
+    let param_names_in_clause_env = s
+        .binder_var_indices(&implication.binders)
+        .collect::<Vec<_>>();
+    let s = &s.add_parameter_mapping(
+        param_names_in_clause_env[..trait_params_len]
+            .iter()
+            .copied(),
+        trait_param_names_in_trait_env,
+    );
+    let clause_params = s
+        .binder_var_display(&implication.binders)
+        .skip(trait_params_len)
+        .collect::<Vec<_>>();
+
+    let mut implication = implication.skip_binders().clone();
+    implication.conditions = Goals::from_iter(
+        interner,
+        implication.conditions.iter(interner).skip(1).cloned(),
+    );
+    if clause_params.is_empty() {
+        format!("clause {};", implication.display(s))
+    } else {
+        format!(
+            "clause forall<{}> {{ {} }};",
+            clause_params.join(", "),
+            implication.display(s)
+        )
+    }
+}
+
+/// Writes a trait alias, turning its where clauses on `Self` back into
+/// bounds:
+///
+/// ```notrust
+/// trait Alias<T> = Foo<T> + Bar
+/// where
+///     T: Baz;
+/// ```
+fn display_trait_alias<I: Interner>(
+    datum: &TraitDatum<I>,
+    s: &InternalWriterState<'_, I>,
+    f: &'_ mut Formatter<'_>,
+) -> Result {
+    let interner = s.db().interner();
+    let value = datum.binders.skip_binders();
+
+    // Within the binders of a where clause, `Self` is `^1.0`.
+    let self_ty = TyKind::BoundVar(BoundVar::new(DebruijnIndex::ONE, 0)).intern(interner);
+    let (mut bounds, where_clauses): (Vec<_>, Vec<_>) = value
+        .where_clauses
+        .iter()
+        .cloned()
+        .partition(|qwc| match qwc.skip_binders() {
+            WhereClause::Implemented(trait_ref) => {
+                trait_ref.self_type_parameter(interner) == self_ty
+            }
+            WhereClause::AliasEq(AliasEq {
+                alias: AliasTy::Projection(projection_ty),
+                ..
+            }) => {
+                s.db()
+                    .trait_ref_from_projection(projection_ty)
+                    .self_type_parameter(interner)
+                    == self_ty
+            }
+            _ => false,
+        });
+
+    // A bound like `Foo<Item = T>` is lowered into both `Self: Foo` and
+    // `<Self as Foo>::Item = T`; drop the former so that we don't print
+    // the trait twice.
+    let mut i = 1;
+    while i < bounds.len() {
+        match (bounds[i - 1].skip_binders(), bounds[i].skip_binders()) {
+            (
+                WhereClause::Implemented(trait_ref),
+                WhereClause::AliasEq(AliasEq {
+                    alias: AliasTy::Projection(projection_ty),
+                    ..
+                }),
+            ) if *trait_ref == s.db().trait_ref_from_projection(projection_ty) => {
+                bounds.remove(i - 1);
+            }
+            _ => i += 1,
+        }
+    }
+
+    let binders = s.binder_var_display(&datum.binders.binders).skip(1);
+    write!(f, "trait {}", datum.id.display(s))?;
+    write_joined_non_empty_list!(f, "<{}>", binders, ", ")?;
+    write!(f, " = {}", display_self_where_clauses_as_bounds(s, &bounds))?;
+    if !where_clauses.is_empty() {
+        let s = &s.add_indent();
+        write!(f, "\nwhere\n{}", where_clauses.display(s))?;
+    }
+    write!(f, ";")
+}
+
 impl<I: Interner> RenderAsRust<I> for ImplDatum<I> {
     fn fmt(&self, s: &InternalWriterState<'_, I>, f: &'_ mut Formatter<'_>) -> Result {
         let interner = s.db().interner();
 
         let s = &s.add_debrujin_index(None);
-        let binders = s.binder_var_display(&self.binders.binders);
         let value = self.binders.skip_binders();
+        let (maybe_sized, where_clauses) =
+            split_sized_bounds(s, &self.binders.binders, &value.where_clauses);
+        let binders = display_params(s, &self.binders.binders, &maybe_sized);
 
         // annotations
         // #[upstream]
@@ -284,9 +489,9 @@ impl<I: Interner> RenderAsRust<I> for ImplDatum<I> {
         // where clauses
         // impl<T> Foo<T> for Bar<T> where T: Baz { }
         //                           ^^^^^^^^^^^^
-        if !value.where_clauses.is_empty() {
+        if !where_clauses.is_empty() {
             let s = &s.add_indent();
-            write!(f, "\nwhere\n{}\n", value.where_clauses.display(s))?;
+            write!(f, "\nwhere\n{}\n", where_clauses.display(s))?;
         } else {
             write!(f, " ")?;
         }
@@ -442,7 +647,13 @@ impl<I: Interner> RenderAsRust<I> for AssociatedTyValue<I> {
 
         write!(f, "{}type {}", s.indent(), assoc_ty_data.id.display(s))?;
         write_joined_non_empty_list!(f, "<{}>", &assoc_ty_value_display, ", ")?;
-        write!(f, " = {};", value.ty.display(s))?;
+        write!(f, " = {}", value.ty.display(s))?;
+        if !value.where_clauses.is_empty() {
+            let where_s = &s.add_indent();
+            let where_clauses = value.where_clauses.display(where_s);
+            write!(f, "\n{}where\n{}", s.indent(), where_clauses)?;
+        }
+        write!(f, ";")?;
         Ok(())
     }
 }
@@ -460,7 +671,9 @@ impl<I: Interner> RenderAsRust<I> for FnDefDatum<I> {
         // binders
         // fn foo<T>(arg: u32, arg2: T) -> Result<T> where T: Bar
         //       ^^^
-        let binders = s.binder_var_display(&self.binders.binders);
+        let (maybe_sized, where_clauses) =
+            split_sized_bounds(s, &self.binders.binders, &bound_datum.where_clauses);
+        let binders = display_params(s, &self.binders.binders, &maybe_sized);
         write_joined_non_empty_list!(f, "<{}>", binders, ", ")?;
 
         {
@@ -488,9 +701,9 @@ impl<I: Interner> RenderAsRust<I> for FnDefDatum<I> {
         // where clause
         // fn foo<T>(arg: u32, arg2: T) -> Result<T> where T: Bar
         //                                           ^^^^^^^^^^^^
-        if !bound_datum.where_clauses.is_empty() {
+        if !where_clauses.is_empty() {
             let s = &s.add_indent();
-            write!(f, "\nwhere\n{}", bound_datum.where_clauses.display(s))?;
+            write!(f, "\nwhere\n{}", where_clauses.display(s))?;
         }
 
         write!(f, ";")?;
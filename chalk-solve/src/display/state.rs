@@ -10,6 +10,7 @@ use std::{
     sync::{Arc, Mutex},
 };
 
+use super::utils::escape_keyword;
 use crate::RustIrDatabase;
 use chalk_ir::{interner::Interner, *};
 use itertools::Itertools;
@@ -72,7 +73,7 @@ impl<T: Copy + Ord> IdAliasStore<T> {
         // If there are no conflicts, keep the name the same so that we don't
         // need name-agnostic equality in display tests.
         if alias == 0 {
-            name
+            escape_keyword(name)
         } else {
             format!("{}_{}", name, alias)
         }
@@ -67,6 +67,7 @@ impl<I: Interner, DB: RustIrDatabase<I>> RustIrDatabase<I> for StubWrapper<'_, D
                 where_clauses: Vec::new(),
             },
         );
+        v.provided_clauses = Vec::new();
         Arc::new(v)
     }
 
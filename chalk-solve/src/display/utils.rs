@@ -24,6 +24,108 @@ macro_rules! write_joined_non_empty_list {
     }};
 }
 
+/// Words the chalk parser reserves. A name that is one of these has to be
+/// written as a raw identifier (`r#struct`) to parse back.
+const KEYWORDS: &[&str] = &[
+    "Compatible",
+    "Contravariant",
+    "Covariant",
+    "DownstreamType",
+    "FromEnv",
+    "Invariant",
+    "IsFullyVisible",
+    "IsLocal",
+    "IsUpstream",
+    "LocalImplAllowed",
+    "Normalize",
+    "NotHolds",
+    "ObjectSafe",
+    "Reveal",
+    "Subtype",
+    "WellFormed",
+    "as",
+    "auto",
+    "bool",
+    "char",
+    "clause",
+    "clone",
+    "closure",
+    "coerce_unsized",
+    "coinductive",
+    "compatible",
+    "const",
+    "copy",
+    "default",
+    "discriminant_kind",
+    "drop",
+    "dyn",
+    "enum",
+    "exists",
+    "extern",
+    "f32",
+    "f64",
+    "float",
+    "fn",
+    "fn_mut",
+    "fn_once",
+    "for",
+    "forall",
+    "fundamental",
+    "generator",
+    "i128",
+    "i16",
+    "i32",
+    "i64",
+    "i8",
+    "if",
+    "impl",
+    "int",
+    "isize",
+    "lang",
+    "marker",
+    "mut",
+    "non_enumerable",
+    "not",
+    "object_safe",
+    "opaque",
+    "phantom_data",
+    "repr",
+    "resume",
+    "reveal",
+    "self",
+    "sized",
+    "static",
+    "str",
+    "struct",
+    "trait",
+    "type",
+    "u128",
+    "u16",
+    "u32",
+    "u64",
+    "u8",
+    "unpin",
+    "unsafe",
+    "unsize",
+    "upstream",
+    "upvars",
+    "usize",
+    "variance",
+    "where",
+    "witnesses",
+    "yield",
+];
+
+/// Escapes `name` as a raw identifier if the parser would read it as a
+/// keyword.
+pub fn escape_keyword(name: String) -> String {
+    if KEYWORDS.contains(&name.as_str()) {
+        format!("r#{}", name)
+    } else {
+        name
+    }
+}
+
 /// Processes a name given by an [`Interner`][chalk_ir::Interner] debug
 /// method into something usable by the `display` module.
 ///
@@ -1,7 +1,10 @@
 use crate::infer::InferenceTable;
+use crate::RustIrDatabase;
+use chalk_ir::cast::Cast;
 use chalk_ir::fold::Fold;
 use chalk_ir::interner::{HasInterner, Interner};
 use chalk_ir::*;
+use rustc_hash::FxHashSet;
 
 pub trait CanonicalExt<T: HasInterner, I: Interner> {
     fn map<OP, U>(self, interner: &I, op: OP) -> Canonical<U::Result>
@@ -111,3 +114,82 @@ impl<I: Interner> GoalExt<I> for Goal<I> {
         InferenceTable::u_canonicalize(interner, &canonical_goal).quantified
     }
 }
+
+/// How many steps `EnvironmentExt::elaborate` follows implied bounds away
+/// from the facts in the environment.
+const MAX_ELABORATION_DEPTH: usize = 16;
+
+pub trait EnvironmentExt<I: Interner> {
+    fn elaborate(&self, db: &dyn RustIrDatabase<I>) -> Environment<I>;
+}
+
+impl<I: Interner> EnvironmentExt<I> for Environment<I> {
+    /// Returns this environment extended with the bounds implied by its
+    /// `FromEnv(T: Trait)` facts: given `FromEnv(T: Foo)` and
+    /// `trait Foo: Bar where T: Baz`, adds `FromEnv(T: Bar)` and
+    /// `FromEnv(T: Baz)`, and so on transitively.
+    ///
+    /// Solving in the elaborated environment gives the same results, but
+    /// the implied bounds are found directly rather than derived through
+    /// the `FromEnv(T: Bar) :- FromEnv(T: Foo)` rules every time a goal
+    /// is solved in it.
+    fn elaborate(&self, db: &dyn RustIrDatabase<I>) -> Environment<I> {
+        let interner = db.interner();
+
+        let mut clauses: FxHashSet<ProgramClause<I>> =
+            self.clauses.iter(interner).cloned().collect();
+        let mut new_clauses = vec![];
+        let mut worklist: Vec<(TraitRef<I>, usize)> = self
+            .clauses
+            .iter(interner)
+            .filter_map(|clause| from_env_trait_fact(interner, clause))
+            .map(|trait_ref| (trait_ref, 0))
+            .collect();
+
+        while let Some((trait_ref, depth)) = worklist.pop() {
+            let trait_datum = db.trait_datum(trait_ref.trait_id);
+            let where_clauses = trait_datum
+                .binders
+                .map_ref(|bound| bound.where_clauses.clone())
+                .substitute(interner, &trait_ref.substitution);
+            for qwc in where_clauses {
+                let clause: ProgramClause<I> =
+                    qwc.clone().into_from_env_goal(interner).cast(interner);
+                if !clauses.insert(clause.clone()) {
+                    continue;
+                }
+                new_clauses.push(clause);
+                // Where clauses like `trait Foo where Vec<Self>: Foo` imply
+                // infinitely many bounds, so stop following them at some
+                // point; the remaining ones are still derived on demand.
+                if qwc.binders.is_empty(interner) && depth < MAX_ELABORATION_DEPTH {
+                    if let WhereClause::Implemented(trait_ref) = qwc.skip_binders() {
+                        worklist.push((trait_ref.clone(), depth + 1));
+                    }
+                }
+            }
+        }
+
+        self.add_clauses(interner, new_clauses)
+    }
+}
+
+/// If `clause` is a fact `FromEnv(T: Trait)` (with no binders), returns
+/// the trait ref.
+fn from_env_trait_fact<I: Interner>(
+    interner: &I,
+    clause: &ProgramClause<I>,
+) -> Option<TraitRef<I>> {
+    let implication = &clause.data(interner).0;
+    if !implication.binders.is_empty(interner) {
+        return None;
+    }
+    let implication = implication.skip_binders();
+    if !implication.conditions.is_empty(interner) || !implication.constraints.is_empty(interner) {
+        return None;
+    }
+    match &implication.consequence {
+        DomainGoal::FromEnv(FromEnv::Trait(trait_ref)) => Some(trait_ref.clone()),
+        _ => None,
+    }
+}
@@ -1,16 +1,21 @@
 use chalk_ir::interner::{HasInterner, Interner};
+use chalk_ir::visit::{ControlFlow, Visit, Visitor};
 use chalk_ir::*;
 use chalk_ir::{cast::Cast, fold::Fold};
+use rustc_hash::{FxHashMap, FxHashSet};
 use tracing::debug;
 
 mod canonicalize;
 pub(crate) mod instantiate;
 mod invert;
+mod normalize_deep;
+mod solve;
 mod test;
 pub mod ucanonicalize;
 pub mod unify;
 mod var;
 
+pub use self::solve::InPlaceSolution;
 use self::var::*;
 
 #[derive(Clone)]
@@ -18,12 +23,20 @@ pub struct InferenceTable<I: Interner> {
     unify: ena::unify::InPlaceUnificationTable<EnaVariable<I>>,
     vars: Vec<EnaVariable<I>>,
     max_universe: UniverseIndex,
+    /// Aliases (without inference variables) whose normalization has been
+    /// proven, keyed by the environment they were proven in; see
+    /// `record_normalization`.
+    normalizations: FxHashMap<InEnvironment<AliasTy<I>>, Ty<I>>,
+    /// The keys of `normalizations` in insertion order, so that entries
+    /// added after a snapshot can be removed again on rollback.
+    normalization_log: Vec<InEnvironment<AliasTy<I>>>,
 }
 
 pub struct InferenceSnapshot<I: Interner> {
     unify_snapshot: ena::unify::Snapshot<ena::unify::InPlace<EnaVariable<I>>>,
     max_universe: UniverseIndex,
     vars: Vec<EnaVariable<I>>,
+    normalization_log_len: usize,
 }
 
 #[allow(type_alias_bounds)]
@@ -36,6 +49,8 @@ impl<I: Interner> InferenceTable<I> {
             unify: ena::unify::UnificationTable::new(),
             vars: vec![],
             max_universe: UniverseIndex::root(),
+            normalizations: FxHashMap::default(),
+            normalization_log: vec![],
         }
     }
 
@@ -103,6 +118,7 @@ impl<I: Interner> InferenceTable<I> {
             unify_snapshot,
             max_universe,
             vars,
+            normalization_log_len: self.normalization_log.len(),
         }
     }
 
@@ -111,6 +127,12 @@ impl<I: Interner> InferenceTable<I> {
         self.unify.rollback_to(snapshot.unify_snapshot);
         self.vars = snapshot.vars;
         self.max_universe = snapshot.max_universe;
+        for key in self
+            .normalization_log
+            .drain(snapshot.normalization_log_len..)
+        {
+            self.normalizations.remove(&key);
+        }
     }
 
     /// Make permanent the changes made since the snapshot was taken.
@@ -182,6 +204,69 @@ impl<I: Interner> InferenceTable<I> {
         }
     }
 
+    /// Returns the unbound inference variables that appear in `value`,
+    /// looking through variables that have already been bound. Each
+    /// variable is given as the root of its unification set, so
+    /// variables unified with one another are reported once.
+    pub fn vars_in<T>(&mut self, interner: &I, value: &T) -> FxHashSet<InferenceVar>
+    where
+        T: Visit<I>,
+    {
+        let mut collector = VarCollector {
+            table: self,
+            interner,
+            vars: FxHashSet::default(),
+        };
+        value.visit_with(&mut collector, DebruijnIndex::INNERMOST);
+        collector.vars
+    }
+
+    /// Records that `alias` normalizes to `ty` in `environment`, which the
+    /// caller has proven (e.g. by solving `AliasEq(alias = ty)` with a
+    /// unique solution and no region constraints). Later relations of the
+    /// same alias in the same environment then relate `ty` directly rather
+    /// than producing another `AliasEq` goal.
+    ///
+    /// Only aliases that contain no unresolved inference variables are
+    /// recorded. Entries recorded after a snapshot are discarded when
+    /// rolling back to it.
+    pub fn record_normalization(
+        &mut self,
+        interner: &I,
+        environment: &Environment<I>,
+        alias: &AliasTy<I>,
+        ty: &Ty<I>,
+    ) {
+        let alias = self.normalize_deep(interner, alias.clone());
+        if !self.vars_in(interner, &alias).is_empty() {
+            return;
+        }
+        let key = InEnvironment::new(environment, alias);
+        if self.normalizations.contains_key(&key) {
+            return;
+        }
+        let ty = self.normalize_deep(interner, ty.clone());
+        debug!(?key, ?ty, "record_normalization");
+        self.normalization_log.push(key.clone());
+        self.normalizations.insert(key, ty);
+    }
+
+    /// Returns the type that `alias` was recorded to normalize to in
+    /// `environment`, if any; see `record_normalization`.
+    pub fn normalization(
+        &mut self,
+        interner: &I,
+        environment: &Environment<I>,
+        alias: &AliasTy<I>,
+    ) -> Option<Ty<I>> {
+        if self.normalizations.is_empty() {
+            return None;
+        }
+        let alias = self.normalize_deep(interner, alias.clone());
+        let key = InEnvironment::new(environment, alias);
+        self.normalizations.get(&key).cloned()
+    }
+
     /// Given an unbound variable, returns its universe.
     ///
     /// # Panics
@@ -195,6 +280,44 @@ impl<I: Interner> InferenceTable<I> {
     }
 }
 
+/// Collects the free inference variables of a value; see
+/// `InferenceTable::vars_in`.
+struct VarCollector<'t, 'i, I: Interner> {
+    table: &'t mut InferenceTable<I>,
+    interner: &'i I,
+    vars: FxHashSet<InferenceVar>,
+}
+
+impl<'i, I: Interner> Visitor<'i, I> for VarCollector<'_, 'i, I>
+where
+    I: 'i,
+{
+    type BreakTy = ();
+
+    fn as_dyn(&mut self) -> &mut dyn Visitor<'i, I, BreakTy = Self::BreakTy> {
+        self
+    }
+
+    fn visit_inference_var(
+        &mut self,
+        var: InferenceVar,
+        _outer_binder: DebruijnIndex,
+    ) -> ControlFlow<()> {
+        match self.table.probe_var(var) {
+            Some(value) => value.visit_with(self, DebruijnIndex::INNERMOST),
+            None => {
+                let root = self.table.inference_var_root(var);
+                self.vars.insert(root);
+                ControlFlow::CONTINUE
+            }
+        }
+    }
+
+    fn interner(&self) -> &'i I {
+        self.interner
+    }
+}
+
 pub trait ParameterEnaVariableExt<I: Interner> {
     fn to_generic_arg(&self, interner: &I) -> GenericArg<I>;
 }
@@ -16,8 +16,9 @@ impl<I: Interner> InferenceTable<I> {
         interner: &I,
         binders: &[CanonicalVarKind<I>],
     ) -> Substitution<I> {
-        Substitution::from_iter(
+        Substitution::from_binders(
             interner,
+            binders.iter().map(|kind| &kind.kind),
             binders.iter().map(|kind| {
                 let param_infer_var = kind.map_ref(|&ui| self.new_variable(ui));
                 param_infer_var.to_generic_arg(interner)
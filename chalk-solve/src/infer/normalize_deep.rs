@@ -2,14 +2,15 @@ use chalk_ir::fold::shift::Shift;
 use chalk_ir::fold::{Fold, Folder};
 use chalk_ir::interner::Interner;
 use chalk_ir::*;
-use chalk_solve::infer::InferenceTable;
 
-pub(crate) struct DeepNormalizer<'table, 'i, I: Interner> {
+use super::InferenceTable;
+
+struct DeepNormalizer<'table, 'i, I: Interner> {
     table: &'table mut InferenceTable<I>,
     interner: &'i I,
 }
 
-impl<I: Interner> DeepNormalizer<'_, '_, I> {
+impl<I: Interner> InferenceTable<I> {
     /// Given a value `value` with variables in it, replaces those variables
     /// with their instantiated values (if any). Uninstantiated variables are
     /// left as-is.
@@ -21,18 +22,25 @@ impl<I: Interner> DeepNormalizer<'_, '_, I> {
     /// See also `InferenceTable::canonicalize`, which -- during real
     /// processing -- is often used to capture the "current state" of
     /// variables.
-    pub fn normalize_deep<T: Fold<I>>(
-        table: &mut InferenceTable<I>,
-        interner: &I,
-        value: T,
-    ) -> T::Result {
+    pub fn normalize_deep<T: Fold<I>>(&mut self, interner: &I, value: T) -> T::Result {
         value
             .fold_with(
-                &mut DeepNormalizer { interner, table },
+                &mut DeepNormalizer {
+                    interner,
+                    table: self,
+                },
                 DebruijnIndex::INNERMOST,
             )
             .unwrap()
     }
+
+    /// Resolves `ty` against the current bindings of this table, replacing
+    /// every bound inference variable in it, however deeply nested, with
+    /// its value. Variables that are still unbound are left in place (as
+    /// the root of their unification set).
+    pub fn resolve_ty(&mut self, interner: &I, ty: &Ty<I>) -> Ty<I> {
+        self.normalize_deep(interner, ty.clone())
+    }
 }
 
 impl<'i, I: Interner> Folder<'i, I> for DeepNormalizer<'_, 'i, I>
@@ -152,8 +160,8 @@ mod test {
         // _which_ of 'b' and 'c' becomes the root. We need to normalize
         // "b" too, then, to ensure we get a consistent result.
         assert_eq!(
-            DeepNormalizer::normalize_deep(&mut table, interner, a.clone()),
-            ty!(apply (item 0) (expr DeepNormalizer::normalize_deep(&mut table, interner, b.clone()))),
+            table.normalize_deep(interner, a.clone()),
+            ty!(apply (item 0) (expr table.normalize_deep(interner, b.clone()))),
         );
         table
             .relate(
@@ -166,8 +174,36 @@ mod test {
             )
             .unwrap();
         assert_eq!(
-            DeepNormalizer::normalize_deep(&mut table, interner, a),
+            table.normalize_deep(interner, a),
             ty!(apply (item 0) (apply (item 1)))
         );
     }
+
+    #[test]
+    fn resolve_ty_leaves_unbound_vars() {
+        let interner = &ChalkIr;
+        let mut table: InferenceTable<ChalkIr> = InferenceTable::new();
+        let environment0 = Environment::new(interner);
+        let v0 = table.new_variable(U0).to_ty(interner);
+        let v1 = table.new_variable(U0).to_ty(interner);
+
+        // ?0 := Vec<?1>
+        table
+            .relate(
+                interner,
+                &TestDatabase,
+                &environment0,
+                Variance::Invariant,
+                &v0,
+                &ty!(apply (item 0) (expr v1)),
+            )
+            .unwrap();
+
+        let resolved_v1 = table.resolve_ty(interner, &v1);
+        assert!(resolved_v1.inference_var(interner).is_some());
+        assert_eq!(
+            table.resolve_ty(interner, &v0),
+            ty!(apply (item 0) (expr resolved_v1)),
+        );
+    }
 }
@@ -0,0 +1,83 @@
+use chalk_ir::interner::Interner;
+use chalk_ir::*;
+use tracing::debug;
+
+use super::ucanonicalize::UniverseMapExt;
+use super::{InferenceTable, ParameterEnaVariableExt};
+use crate::{Guidance, RustIrDatabase, Solution, Solver};
+
+/// The outcome of `InferenceTable::solve_in_place` for a goal that has a
+/// solution.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InPlaceSolution<I: Interner> {
+    /// The goal has a unique solution, which has been applied to the
+    /// table. These are its region constraints, which may mention the
+    /// table's variables; they are left for the caller to handle.
+    Unique(Constraints<I>),
+
+    /// The goal may have several solutions, and the table is unchanged.
+    /// As in `Solution::Ambig`, the guidance is canonical.
+    Ambig(Guidance<I>),
+}
+
+impl<I: Interner> InPlaceSolution<I> {
+    pub fn is_unique(&self) -> bool {
+        matches!(self, InPlaceSolution::Unique(_))
+    }
+}
+
+impl<I: Interner> InferenceTable<I> {
+    /// Solves `goal`, which may mention inference variables from this
+    /// table, some of which may already be (partially) resolved. The goal
+    /// is canonicalized and handed to `solver`; if the solution is unique,
+    /// its substitution is applied back to the variables of this table.
+    ///
+    /// Returns `None` if the goal has no solution.
+    pub fn solve_in_place(
+        &mut self,
+        interner: &I,
+        db: &dyn RustIrDatabase<I>,
+        solver: &mut dyn Solver<I>,
+        goal: InEnvironment<Goal<I>>,
+    ) -> Option<InPlaceSolution<I>> {
+        let canonicalized = self.canonicalize(interner, goal);
+        let u_canonicalized = Self::u_canonicalize(interner, &canonicalized.quantified);
+        let solution = solver.solve(db, &u_canonicalized.quantified)?;
+        debug!("solve_in_place: solution={:?}", solution);
+
+        let constrained_subst = match solution {
+            Solution::Unique(constrained_subst) => constrained_subst,
+            Solution::Ambig(guidance) => return Some(InPlaceSolution::Ambig(guidance)),
+        };
+        let constrained_subst = u_canonicalized
+            .universes
+            .map_from_canonical(interner, &constrained_subst);
+        let ConstrainedSubst { subst, constraints } =
+            self.instantiate_canonical(interner, constrained_subst);
+
+        // As in the recursive solver's `apply_solution`, this only
+        // binds variables that the solver has proven to be equal to
+        // the given values, so it cannot fail.
+        let empty_env = &Environment::new(interner);
+        for (i, free_var) in canonicalized.free_vars.iter().enumerate() {
+            let free_var = free_var.to_generic_arg(interner);
+            let subst_value = subst.at(interner, i);
+            self.relate(
+                interner,
+                db.unification_database(),
+                empty_env,
+                Variance::Invariant,
+                &free_var,
+                subst_value,
+            )
+            .unwrap_or_else(|err| {
+                panic!(
+                    "solve_in_place failed with free_var={:?}, subst_value={:?}: {:?}",
+                    free_var, subst_value, err
+                );
+            });
+        }
+
+        Some(InPlaceSolution::Unique(constraints))
+    }
+}
@@ -420,3 +420,438 @@ fn lifetime_constraint_indirect() {
         "InEnvironment { environment: Env([]), goal: \'!1_0: \'?2 }",
     );
 }
+
+#[test]
+fn canonical_map_to_subst() {
+    use crate::ext::CanonicalExt;
+
+    let interner = &ChalkIr;
+    let mut table = make_table();
+    let environment0 = Environment::new(interner);
+    let v0 = table.new_variable(U1).to_ty(interner);
+    let v1 = table.new_variable(U0).to_ty(interner);
+    let l0 = table.new_variable(U0).to_lifetime(interner);
+
+    // The lifetime only appears in the constraints, so it should
+    // not appear in the binders once they have been dropped.
+    let constrained_subst = table
+        .canonicalize(
+            interner,
+            ConstrainedSubst {
+                subst: Substitution::from_iter(interner, vec![v0, v1]),
+                constraints: Constraints::from_iter(
+                    interner,
+                    vec![InEnvironment::new(
+                        &environment0,
+                        Constraint::LifetimeOutlives(l0, l0),
+                    )],
+                ),
+            },
+        )
+        .quantified;
+
+    assert_eq!(
+        constrained_subst.map(interner, |cs| cs.subst),
+        Canonical {
+            value: Substitution::from_iter(interner, vec![ty!(bound 0), ty!(bound 1)]),
+            binders: CanonicalVarKinds::from_iter(
+                interner,
+                vec![
+                    CanonicalVarKind::new(VariableKind::Ty(TyVariableKind::General), U1),
+                    CanonicalVarKind::new(VariableKind::Ty(TyVariableKind::General), U0),
+                ]
+            ),
+        }
+    );
+}
+
+#[test]
+fn substitution_apply_matches_naive_fold() {
+    use chalk_ir::fold::Subst;
+
+    let interner = &ChalkIr;
+
+    // The first parameter refers to a bound variable of its own, so it
+    // must be shifted when substituted under the function binder; the
+    // others can be returned as-is.
+    let parameters = vec![
+        arg!((apply (item 1) (bound 0))),
+        arg!((apply (item 2) (placeholder 1))),
+        arg!((lifetime (placeholder 1))),
+    ];
+    let subst = Substitution::from_iter(interner, parameters.clone());
+
+    let value = ty!(apply (item 0)
+        (bound 0)
+        (bound 1)
+        (lifetime (bound 2))
+        (function 1 (bound 1 0) (bound 1 1) (lifetime (bound 1 2)) (bound 0 0)));
+
+    assert_eq!(
+        subst.apply(value.clone(), interner),
+        Subst::apply(interner, &parameters, value),
+    );
+}
+
+#[test]
+fn substitution_try_apply_kind_mismatch() {
+    let interner = &ChalkIr;
+
+    let value = ty!(apply (item 0) (bound 0) (lifetime (bound 1)));
+
+    let subst = Substitution::from_iter(
+        interner,
+        vec![arg!((apply (item 1))), arg!((lifetime (placeholder 1)))],
+    );
+    assert_eq!(
+        subst.try_apply(value.clone(), interner),
+        Ok(ty!(apply (item 0) (apply (item 1)) (lifetime (placeholder 1)))),
+    );
+
+    // A lifetime where a type is expected, and vice versa.
+    let swapped = Substitution::from_iter(
+        interner,
+        vec![arg!((lifetime (placeholder 1))), arg!((apply (item 1)))],
+    );
+    assert_eq!(swapped.try_apply(value.clone(), interner), Err(NoSolution));
+
+    // Too few parameters.
+    let short = Substitution::from_iter(interner, vec![arg!((apply (item 1)))]);
+    assert_eq!(short.try_apply(value, interner), Err(NoSolution));
+}
+
+#[test]
+fn substitution_from_binders() {
+    let interner = &ChalkIr;
+    let binders = [
+        VariableKind::Ty(TyVariableKind::General),
+        VariableKind::Lifetime,
+    ];
+
+    let subst = Substitution::from_binders(
+        interner,
+        &binders,
+        vec![arg!((apply (item 1))), arg!((lifetime (placeholder 1)))],
+    );
+    assert_eq!(subst.len(interner), 2);
+}
+
+#[test]
+#[should_panic(expected = "substitution has fewer parameters than binders")]
+fn substitution_from_binders_too_short() {
+    let interner = &ChalkIr;
+    let binders = [
+        VariableKind::Ty(TyVariableKind::General),
+        VariableKind::Lifetime,
+    ];
+
+    Substitution::from_binders(interner, &binders, vec![arg!((apply (item 1)))]);
+}
+
+#[test]
+#[should_panic(expected = "substitution has more parameters than binders")]
+fn substitution_from_binders_too_long() {
+    let interner = &ChalkIr;
+    let binders = [VariableKind::Ty(TyVariableKind::General)];
+
+    Substitution::from_binders(
+        interner,
+        &binders,
+        vec![arg!((apply (item 1))), arg!((apply (item 2)))],
+    );
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "does not match binder of kind")]
+fn substitution_from_binders_kind_mismatch() {
+    let interner = &ChalkIr;
+    let binders = [
+        VariableKind::Ty(TyVariableKind::General),
+        VariableKind::Lifetime,
+    ];
+
+    Substitution::from_binders(
+        interner,
+        &binders,
+        vec![arg!((lifetime (placeholder 1))), arg!((apply (item 1)))],
+    );
+}
+
+#[test]
+fn relate_substitutions() {
+    let interner = &ChalkIr;
+    let mut table: InferenceTable<ChalkIr> = InferenceTable::new();
+    let environment0 = Environment::new(interner);
+    let a = table.new_variable(U0).to_ty(interner);
+    let b = table.new_variable(U1).to_lifetime(interner);
+
+    // Parameters of each kind are related with their counterparts.
+    let subst_a = Substitution::from_iter(
+        interner,
+        vec![arg!((apply (item 0))), arg!((lifetime (placeholder 1)))],
+    );
+    let subst_b =
+        Substitution::from_iter(interner, vec![a.clone().cast(interner), b.cast(interner)]);
+    table
+        .relate(
+            interner,
+            &TestDatabase,
+            &environment0,
+            Variance::Invariant,
+            &subst_a,
+            &subst_b,
+        )
+        .unwrap();
+    assert_eq!(
+        table.normalize_ty_shallow(interner, &a),
+        Some(ty!(apply (item 0)))
+    );
+    assert_eq!(
+        table.normalize_lifetime_shallow(interner, &b),
+        Some(lifetime!(placeholder 1))
+    );
+
+    // A type and a lifetime never match...
+    let swapped = Substitution::from_iter(
+        interner,
+        vec![arg!((lifetime (placeholder 1))), arg!((apply (item 0)))],
+    );
+    table
+        .relate(
+            interner,
+            &TestDatabase,
+            &environment0,
+            Variance::Invariant,
+            &subst_a,
+            &swapped,
+        )
+        .unwrap_err();
+
+    // ...and neither do substitutions of different lengths.
+    let short = Substitution::from_iter(interner, vec![arg!((apply (item 0)))]);
+    table
+        .relate(
+            interner,
+            &TestDatabase,
+            &environment0,
+            Variance::Invariant,
+            &subst_a,
+            &short,
+        )
+        .unwrap_err();
+}
+
+#[test]
+fn universe_map_round_trip() {
+    use super::ucanonicalize::UniverseMapExt;
+
+    let interner = &ChalkIr;
+    let universes = UniverseMap {
+        universes: vec![
+            U0,
+            UniverseIndex { counter: 2 },
+            UniverseIndex { counter: 5 },
+        ],
+    };
+
+    // `U3` and `placeholder 4` are beyond the end of the map, and so
+    // correspond to universes greater than `U5` in the original space.
+    let canonical = Canonical {
+        value: ty!(apply (item 0) (placeholder 1) (placeholder 2) (placeholder 4) (bound 0)),
+        binders: CanonicalVarKinds::from_iter(
+            interner,
+            vec![
+                CanonicalVarKind::new(VariableKind::Ty(TyVariableKind::General), U1),
+                CanonicalVarKind::new(
+                    VariableKind::Ty(TyVariableKind::General),
+                    UniverseIndex { counter: 3 },
+                ),
+            ],
+        ),
+    };
+
+    let original = universes.map_from_canonical(interner, &canonical);
+    assert_eq!(
+        original,
+        Canonical {
+            value: ty!(apply (item 0) (placeholder 2) (placeholder 5) (placeholder 7) (bound 0)),
+            binders: CanonicalVarKinds::from_iter(
+                interner,
+                vec![
+                    CanonicalVarKind::new(
+                        VariableKind::Ty(TyVariableKind::General),
+                        UniverseIndex { counter: 2 }
+                    ),
+                    CanonicalVarKind::new(
+                        VariableKind::Ty(TyVariableKind::General),
+                        UniverseIndex { counter: 6 }
+                    ),
+                ],
+            ),
+        }
+    );
+    assert_eq!(universes.map_to_canonical(interner, &original), canonical);
+}
+
+#[test]
+fn vars_in_partially_unified() {
+    let interner = &ChalkIr;
+    let mut table: InferenceTable<ChalkIr> = InferenceTable::new();
+    let environment0 = Environment::new(interner);
+    let v0 = table.new_variable(U0);
+    let v1 = table.new_variable(U0);
+    let v2 = table.new_variable(U0);
+
+    // ?1 := Vec<?0>, and ?2 is unified with ?0
+    table
+        .relate(
+            interner,
+            &TestDatabase,
+            &environment0,
+            Variance::Invariant,
+            &v1.to_ty(interner),
+            &ty!(apply (item 0) (expr v0.to_ty(interner))),
+        )
+        .unwrap();
+    table
+        .relate(
+            interner,
+            &TestDatabase,
+            &environment0,
+            Variance::Invariant,
+            &v2.to_ty(interner),
+            &v0.to_ty(interner),
+        )
+        .unwrap();
+
+    let root = table.inference_var_root(v0.into());
+    let expected: rustc_hash::FxHashSet<_> = vec![root].into_iter().collect();
+    assert_eq!(table.vars_in(interner, &v1.to_ty(interner)), expected);
+    assert_eq!(
+        table.vars_in(
+            interner,
+            &ty!(apply (item 0) (expr v1.to_ty(interner)) (expr v2.to_ty(interner)))
+        ),
+        expected
+    );
+    assert!(table.vars_in(interner, &ty!(apply (item 1))).is_empty());
+}
+
+#[test]
+fn recorded_normalization() {
+    let interner = &ChalkIr;
+    let mut table: InferenceTable<ChalkIr> = InferenceTable::new();
+    let environment0 = Environment::new(interner);
+    // The projection `<Item1 as Trait>::Item0`
+    let projection = ty!(projection (item 0) (apply (item 1)));
+    let alias = match projection.kind(interner) {
+        TyKind::Alias(alias) => alias.clone(),
+        _ => unreachable!(),
+    };
+
+    // Without a recorded normalization, relating the alias yields an
+    // `AliasEq` goal.
+    let v0 = table.new_variable(U0).to_ty(interner);
+    let result = table
+        .relate(
+            interner,
+            &TestDatabase,
+            &environment0,
+            Variance::Invariant,
+            &projection,
+            &v0,
+        )
+        .unwrap();
+    assert_eq!(result.goals.len(), 1);
+    assert!(table.normalize_ty_shallow(interner, &v0).is_none());
+
+    // Once the projection is recorded to normalize to `Item3`, relating
+    // it relates `Item3` instead.
+    let snapshot = table.snapshot();
+    table.record_normalization(interner, &environment0, &alias, &ty!(apply (item 3)));
+    let result = table
+        .relate(
+            interner,
+            &TestDatabase,
+            &environment0,
+            Variance::Invariant,
+            &projection,
+            &v0,
+        )
+        .unwrap();
+    assert!(result.goals.is_empty());
+    assert_eq!(table.resolve_ty(interner, &v0), ty!(apply (item 3)));
+
+    // Rolling back forgets the normalization again.
+    table.rollback_to(snapshot);
+    assert_eq!(table.normalization(interner, &environment0, &alias), None);
+    let result = table
+        .relate(
+            interner,
+            &TestDatabase,
+            &environment0,
+            Variance::Invariant,
+            &projection,
+            &v0,
+        )
+        .unwrap();
+    assert_eq!(result.goals.len(), 1);
+}
+
+#[test]
+fn close_placeholders_round_trip() {
+    let interner = &ChalkIr;
+    let mut table = make_table();
+
+    // `for<T, 'a> Foo<T, 'a, ^1.0, for<> fn(T), !0>`: besides our own
+    // variables, this mentions a variable bound further out and a
+    // placeholder from another universe, both of which must survive.
+    let binders = Binders::new(
+        VariableKinds::from_iter(
+            interner,
+            vec![
+                VariableKind::Ty(TyVariableKind::General),
+                VariableKind::Lifetime,
+            ],
+        ),
+        ty!(apply (item 0) (bound 0) (lifetime (bound 1)) (bound 1 0)
+            (function 0 (apply (item 1) (bound 1 0))) (placeholder 0)),
+    );
+
+    // `make_table` already created U1 and U2, so the placeholders are in U3.
+    let instantiated = table.instantiate_binders_universally(interner, binders.clone());
+    assert_ne!(instantiated, binders.skip_binders().clone());
+
+    let u3 = UniverseIndex { counter: 3 };
+    let closed = Binders::from_placeholders(interner, binders.binders.clone(), u3, instantiated);
+    assert_eq!(closed, binders);
+}
+
+#[test]
+fn mismatched_kinds() {
+    let interner = &ChalkIr;
+
+    let ty_arg = arg!((apply (item 1)));
+    let lifetime_arg = arg!((lifetime (placeholder 1)));
+    assert_eq!(ty_arg.assert_same_kind(interner, &ty_arg), Ok(()));
+    assert_eq!(
+        ty_arg.assert_same_kind(interner, &lifetime_arg),
+        Err(NoSolution)
+    );
+
+    // Substituting a lifetime for a type variable fails rather than
+    // panicking.
+    let binders = Binders::new(
+        VariableKinds::from1(interner, VariableKind::Ty(TyVariableKind::General)),
+        ty!(apply (item 0) (bound 0)),
+    );
+    assert_eq!(
+        binders.clone().try_substitute(interner, &[lifetime_arg]),
+        Err(NoSolution)
+    );
+    assert_eq!(
+        binders.try_substitute(interner, &[ty_arg]),
+        Ok(ty!(apply (item 0) (apply (item 1))))
+    );
+}
@@ -39,6 +39,7 @@ impl<I: Interner> InferenceTable<I> {
                 &mut UMapToCanonical {
                     universes: &universes,
                     interner,
+                    allow_implicit: false,
                 },
                 DebruijnIndex::INNERMOST,
             )
@@ -86,6 +87,11 @@ pub trait UniverseMapExt {
         T: Clone + Fold<I> + HasInterner<Interner = I>,
         T::Result: HasInterner<Interner = I>,
         I: Interner;
+    fn map_to_canonical<T, I>(&self, interner: &I, value: &Canonical<T>) -> Canonical<T::Result>
+    where
+        T: Clone + Fold<I> + HasInterner<Interner = I>,
+        T::Result: HasInterner<Interner = I>,
+        I: Interner;
 }
 impl UniverseMapExt for UniverseMap {
     fn add(&mut self, universe: UniverseIndex) {
@@ -190,6 +196,62 @@ impl UniverseMapExt for UniverseMap {
             value,
         }
     }
+
+    /// The inverse of `map_from_canonical`: translates the universes in
+    /// `value` from the original universes back into canonical
+    /// universes, so that `map_to_canonical(map_from_canonical(x)) == x`.
+    ///
+    /// Unlike the folding done by `u_canonicalize`, universes greater
+    /// than every universe in the map are accepted; they are the
+    /// "implicit" universes that `map_from_canonical` produces for
+    /// out-of-bounds canonical universes, and are mapped back to them.
+    fn map_to_canonical<T, I>(&self, interner: &I, value: &Canonical<T>) -> Canonical<T::Result>
+    where
+        T: Clone + Fold<I> + HasInterner<Interner = I>,
+        T::Result: HasInterner<Interner = I>,
+        I: Interner,
+    {
+        debug_span!("map_to_canonical", ?value, universes = ?self.universes);
+
+        let binders = value
+            .binders
+            .iter(interner)
+            .map(|cvk| cvk.map_ref(|&universe| map_universe_to_canonical_implicit(self, universe)));
+
+        let value = value
+            .value
+            .clone()
+            .fold_with(
+                &mut UMapToCanonical {
+                    interner,
+                    universes: self,
+                    allow_implicit: true,
+                },
+                DebruijnIndex::INNERMOST,
+            )
+            .unwrap();
+
+        Canonical {
+            binders: CanonicalVarKinds::from_iter(interner, binders),
+            value,
+        }
+    }
+}
+
+/// Like `map_universe_to_canonical`, but also maps universes beyond the
+/// end of the map, inverting the out-of-bounds case of
+/// `map_universe_from_canonical`.
+fn map_universe_to_canonical_implicit(map: &UniverseMap, universe: UniverseIndex) -> UniverseIndex {
+    let max_counter = map.universes.last().unwrap().counter;
+    let canonical = match map.map_universe_to_canonical(universe) {
+        Some(canonical) => canonical,
+        None if universe.counter > max_counter => UniverseIndex {
+            counter: map.universes.len() + (universe.counter - max_counter - 1),
+        },
+        None => panic!("universe {:?} has no canonical counterpart", universe),
+    };
+    debug_assert_eq!(map.map_universe_from_canonical(canonical), universe);
+    canonical
 }
 
 /// The `UCollector` is a "no-op" in terms of the value, but along the
@@ -230,6 +292,21 @@ where
 struct UMapToCanonical<'q, I> {
     interner: &'q I,
     universes: &'q UniverseMap,
+    /// Whether universes beyond the end of the map are allowed (see
+    /// `map_to_canonical`).
+    allow_implicit: bool,
+}
+
+impl<I> UMapToCanonical<'_, I> {
+    fn map_universe(&self, universe: UniverseIndex) -> UniverseIndex {
+        if self.allow_implicit {
+            map_universe_to_canonical_implicit(self.universes, universe)
+        } else {
+            self.universes
+                .map_universe_to_canonical(universe)
+                .expect("Expected UCollector to encounter this universe")
+        }
+    }
 }
 
 impl<'i, I: Interner> Folder<'i, I> for UMapToCanonical<'i, I>
@@ -249,10 +326,7 @@ where
         universe0: PlaceholderIndex,
         _outer_binder: DebruijnIndex,
     ) -> Fallible<Ty<I>> {
-        let ui = self
-            .universes
-            .map_universe_to_canonical(universe0.ui)
-            .expect("Expected UCollector to encounter this universe");
+        let ui = self.map_universe(universe0.ui);
         Ok(PlaceholderIndex {
             ui,
             idx: universe0.idx,
@@ -265,10 +339,7 @@ where
         universe0: PlaceholderIndex,
         _outer_binder: DebruijnIndex,
     ) -> Fallible<Lifetime<I>> {
-        let universe = self
-            .universes
-            .map_universe_to_canonical(universe0.ui)
-            .expect("Expected UCollector to encounter this universe");
+        let universe = self.map_universe(universe0.ui);
 
         Ok(PlaceholderIndex {
             ui: universe,
@@ -283,10 +354,7 @@ where
         universe0: PlaceholderIndex,
         _outer_binder: DebruijnIndex,
     ) -> Fallible<Const<I>> {
-        let universe = self
-            .universes
-            .map_universe_to_canonical(universe0.ui)
-            .expect("Expected UCollector to encounter this universe");
+        let universe = self.map_universe(universe0.ui);
 
         Ok(PlaceholderIndex {
             ui: universe,
@@ -339,6 +407,20 @@ where
         .to_lifetime(self.interner()))
     }
 
+    fn fold_free_placeholder_const(
+        &mut self,
+        ty: Ty<I>,
+        universe0: PlaceholderIndex,
+        _outer_binder: DebruijnIndex,
+    ) -> Fallible<Const<I>> {
+        let universe = self.universes.map_universe_from_canonical(universe0.ui);
+        Ok(PlaceholderIndex {
+            ui: universe,
+            idx: universe0.idx,
+        }
+        .to_const(self.interner(), ty))
+    }
+
     fn forbid_inference_vars(&self) -> bool {
         true
     }
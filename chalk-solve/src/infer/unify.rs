@@ -451,6 +451,10 @@ impl<'t, I: Interner> Unifier<'t, I> {
     /// AliasEq(Alias = ?X)
     /// ```
     /// and relates `?X` and `ty`.
+    ///
+    /// If the normalization of `alias` has already been recorded in the
+    /// table (see `InferenceTable::record_normalization`), the normalized
+    /// type is related with `ty` directly instead.
     #[instrument(level = "debug", skip(self))]
     fn relate_alias_ty(
         &mut self,
@@ -459,6 +463,10 @@ impl<'t, I: Interner> Unifier<'t, I> {
         ty: &Ty<I>,
     ) -> Fallible<()> {
         let interner = self.interner;
+        if let Some(normalized) = self.table.normalization(interner, self.environment, alias) {
+            debug!(?normalized, "using recorded normalization");
+            return self.relate_ty_ty(variance, &normalized, ty);
+        }
         match variance {
             Variance::Invariant => {
                 self.goals.push(InEnvironment::new(
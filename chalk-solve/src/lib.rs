@@ -8,6 +8,7 @@ use chalk_ir::*;
 use std::fmt::Debug;
 use std::sync::Arc;
 
+pub mod caching_db;
 pub mod clauses;
 pub mod coherence;
 pub mod coinductive_goal;
@@ -163,6 +164,20 @@ pub trait RustIrDatabase<I: Interner>: Debug {
 
     fn unification_database(&self) -> &dyn UnificationDatabase<I>;
 
+    /// Lets the database decide `ty: Copy` without consulting any program
+    /// clauses, for types it already knows about (e.g. scalars). Returns
+    /// `None` to fall back to the normal clauses.
+    fn is_copy_fast_path(&self, _ty: &Ty<I>) -> Option<bool> {
+        None
+    }
+
+    /// If true, all crates are treated as a single crate: the orphan rules
+    /// are not enforced, and `LocalImplAllowed` holds for every trait
+    /// reference. This is meant for modeling whole-program scenarios.
+    fn is_whole_program(&self) -> bool {
+        false
+    }
+
     /// Retrieves a trait's original name. No uniqueness guarantees, but must
     /// a valid Rust identifier.
     fn trait_name(&self, trait_id: TraitId<I>) -> String {
@@ -199,6 +214,7 @@ pub trait RustIrDatabase<I: Interner>: Debug {
 
 pub use clauses::program_clauses_for_env;
 
+pub use solve::FailureReason;
 pub use solve::Guidance;
 pub use solve::Solution;
 pub use solve::Solver;
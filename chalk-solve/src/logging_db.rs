@@ -287,6 +287,14 @@ where
         self.ws.db().discriminant_type(ty)
     }
 
+    fn is_copy_fast_path(&self, ty: &Ty<I>) -> Option<bool> {
+        self.ws.db().is_copy_fast_path(ty)
+    }
+
+    fn is_whole_program(&self) -> bool {
+        self.ws.db().is_whole_program()
+    }
+
     fn unification_database(&self) -> &dyn UnificationDatabase<I> {
         self
     }
@@ -533,6 +541,14 @@ where
     fn discriminant_type(&self, ty: Ty<I>) -> Ty<I> {
         self.db.discriminant_type(ty)
     }
+
+    fn is_copy_fast_path(&self, ty: &Ty<I>) -> Option<bool> {
+        self.db.is_copy_fast_path(ty)
+    }
+
+    fn is_whole_program(&self) -> bool {
+        self.db.is_whole_program()
+    }
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
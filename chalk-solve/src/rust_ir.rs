@@ -10,8 +10,8 @@ use chalk_ir::{
     try_break,
     visit::{ControlFlow, Visit},
     AdtId, AliasEq, AliasTy, AssocTypeId, Binders, DebruijnIndex, FnDefId, GenericArg, ImplId,
-    OpaqueTyId, ProjectionTy, QuantifiedWhereClause, Substitution, ToGenericArg, TraitId, TraitRef,
-    Ty, TyKind, VariableKind, WhereClause, WithKind,
+    OpaqueTyId, ProgramClause, ProjectionTy, QuantifiedWhereClause, Substitution, ToGenericArg,
+    TraitId, TraitRef, Ty, TyKind, VariableKind, WhereClause, WithKind,
 };
 use std::iter;
 
@@ -109,6 +109,9 @@ pub struct AdtVariantDatum<I: Interner> {
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct AdtFlags {
     pub upstream: bool,
+    /// A fundamental type is local or downstream whenever one of its type
+    /// parameters is. It may have any number of type parameters, including
+    /// none, in which case it is only local if it is not `#[upstream]`.
     pub fundamental: bool,
     pub phantom_data: bool,
 }
@@ -242,6 +245,14 @@ pub struct TraitDatum<I: Interner> {
     /// If this is a well-known trait, which one? If `None`, this is a regular,
     /// user-defined trait.
     pub well_known: Option<WellKnownTrait>,
+
+    /// Clauses "provided" by the trait, which hold for any type that
+    /// implements it. These stand in for provided (default) items: a
+    /// provided clause `C :- G` on `trait Foo<T>` is stored as
+    /// `forall<Self, T> { C :- G, Implemented(Self: Foo<T>) }`. They are
+    /// among the trait's program clauses, so they apply to goals about the
+    /// trait and in environments that assume `Self: Foo<T>`.
+    pub provided_clauses: Vec<ProgramClause<I>>,
 }
 
 /// A list of the traits that are "well known" to chalk, which means that
@@ -279,6 +290,10 @@ impl<I: Interner> TraitDatum<I> {
         self.flags.coinductive
     }
 
+    pub fn is_trait_alias(&self) -> bool {
+        self.flags.alias
+    }
+
     /// Gives access to the where clauses of the trait, quantified over the type parameters of the trait:
     ///
     /// ```ignore
@@ -332,6 +347,11 @@ pub struct TraitFlags {
     pub non_enumerable: bool,
 
     pub coinductive: bool,
+
+    /// A trait alias, like `trait Alias = Foo + Bar;`. The bounds of
+    /// the alias are stored as where clauses on `Self`, and the alias is
+    /// implemented exactly when all of them hold.
+    pub alias: bool,
 }
 
 chalk_ir::const_visit!(TraitFlags);
@@ -612,6 +632,10 @@ pub struct AssociatedTyValue<I: Interner> {
 pub struct AssociatedTyValueBound<I: Interner> {
     /// Type that we normalize to. The X in `type Foo<'a> = X`.
     pub ty: Ty<I>,
+
+    /// Where clauses that must hold for the projection to normalize
+    /// to `ty`. The `WC` in `type Foo<'a> = X where WC`.
+    pub where_clauses: Vec<QuantifiedWhereClause<I>>,
 }
 
 /// Represents the bounds for an `impl Trait` type.
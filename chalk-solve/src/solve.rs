@@ -1,10 +1,17 @@
+use crate::clauses::builder::ClauseBuilder;
+use crate::clauses::program_clauses::ToProgramClauses;
+use crate::ext::GoalExt;
+use crate::goal_builder::GoalBuilder;
+use crate::rust_ir::ImplDatum;
 use crate::RustIrDatabase;
 use chalk_derive::HasInterner;
+use chalk_ir::cast::Cast;
 use chalk_ir::interner::Interner;
 use chalk_ir::*;
 use std::fmt;
 use tracing::debug;
 
+pub mod test_support;
 pub mod truncate;
 
 /// A (possible) solution for a proposed goal.
@@ -137,6 +144,22 @@ impl<I: Interner> Solution<I> {
         }
     }
 
+    /// Returns the constrained substitution if this solution is unique.
+    pub fn as_unique(&self) -> Option<&Canonical<ConstrainedSubst<I>>> {
+        match self {
+            Solution::Unique(constrained) => Some(constrained),
+            Solution::Ambig(_) => None,
+        }
+    }
+
+    /// Returns the inference guidance if this solution is ambiguous.
+    pub fn guidance(&self) -> Option<&Guidance<I>> {
+        match self {
+            Solution::Unique(_) => None,
+            Solution::Ambig(guidance) => Some(guidance),
+        }
+    }
+
     pub fn is_unique(&self) -> bool {
         matches!(*self, Solution::Unique(..))
     }
@@ -212,6 +235,15 @@ impl<S: fmt::Display> fmt::Display for SubstitutionResult<S> {
     }
 }
 
+/// A best-effort explanation of why a goal could not be proven; see
+/// `Solver::solve_with_explanation`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FailureReason<I: Interner> {
+    /// A subgoal that had no answers, e.g. `u32: Clone` when trying to
+    /// prove `Vec<u32>: Clone` with an `impl<T: Clone> Clone for Vec<T>`.
+    pub goal: UCanonical<InEnvironment<Goal<I>>>,
+}
+
 /// Finds the solution to "goals", or trait queries -- i.e., figures
 /// out what sets of types implement which traits. Also, between
 /// queries, this struct stores the cached state from previous solver
@@ -314,4 +346,154 @@ where
             None => false,
         }
     }
+
+    /// Returns a printable description of the solver's cached state,
+    /// for debugging. Solvers that keep no inspectable state return
+    /// `None`, which is the default.
+    fn dump_tables(&self) -> Option<String> {
+        None
+    }
+
+    /// Returns the goals the solver has created tables for so far, i.e.
+    /// every goal it has looked up program clauses for. Solvers that do
+    /// not keep tables return `None`, which is the default.
+    fn table_goals(&self) -> Option<Vec<UCanonical<InEnvironment<Goal<I>>>>> {
+        None
+    }
+
+    /// Attempts to solve the given goal as though `impl_datum` were an
+    /// additional impl in the program. This is useful for answering
+    /// "what if" questions, e.g. whether adding an impl would make a goal
+    /// provable. The clauses generated from the impl are added to the
+    /// goal's environment, so the program itself is left untouched.
+    fn solve_with_impl(
+        &mut self,
+        program: &dyn RustIrDatabase<I>,
+        goal: &UCanonical<InEnvironment<Goal<I>>>,
+        impl_datum: &ImplDatum<I>,
+    ) -> Option<Solution<I>> {
+        let interner = program.interner();
+        let environment = &goal.canonical.value.environment;
+        let mut clauses = vec![];
+        impl_datum.to_program_clauses(&mut ClauseBuilder::new(program, &mut clauses), environment);
+
+        let mut goal = goal.clone();
+        goal.canonical.value = goal
+            .canonical
+            .value
+            .map_env(|environment| environment.add_clauses(interner, clauses));
+        self.solve(program, &goal)
+    }
+
+    /// Like `solve`, but if the goal cannot be proven, also tries to
+    /// explain why by naming a subgoal that failed. This is meant for
+    /// reporting to users and is best-effort: solvers that keep no record
+    /// of their subgoals never give a reason, which is the default.
+    fn solve_with_explanation(
+        &mut self,
+        program: &dyn RustIrDatabase<I>,
+        goal: &UCanonical<InEnvironment<Goal<I>>>,
+    ) -> (Option<Solution<I>>, Option<FailureReason<I>>) {
+        (self.solve(program, goal), None)
+    }
+
+    /// If `goal` is a trait goal `T: Trait` with a unique solution, returns
+    /// the impl that proves it, which is what e.g. codegen needs to know.
+    /// Returns `None` if the goal is not a trait goal, has no unique
+    /// solution, or is proven by something other than a single impl (say,
+    /// a where clause in the environment, or a builtin impl). Solvers
+    /// that don't record where their answers come from always return
+    /// `None`, which is the default.
+    fn selected_impl(
+        &mut self,
+        _program: &dyn RustIrDatabase<I>,
+        _goal: &UCanonical<InEnvironment<Goal<I>>>,
+    ) -> Option<ImplId<I>> {
+        None
+    }
+}
+
+/// Returns the traits among `traits` that `ty` is known to implement,
+/// i.e. those for which `exists<P..> { ty: Trait<P..> }` has a unique
+/// solution. Stops after finding `limit` such traits.
+pub fn implemented_traits<I: Interner>(
+    solver: &mut dyn Solver<I>,
+    program: &dyn RustIrDatabase<I>,
+    ty: &Ty<I>,
+    traits: impl IntoIterator<Item = TraitId<I>>,
+    limit: usize,
+) -> Vec<TraitId<I>> {
+    let interner = program.interner();
+    let mut implemented = vec![];
+    for trait_id in traits {
+        if implemented.len() >= limit {
+            break;
+        }
+
+        // exists<Self, P..> { ty: Trait<P..> }
+        let trait_datum = program.trait_datum(trait_id);
+        let mut gb = GoalBuilder::new(program);
+        let goal = gb.exists(
+            &trait_datum.binders,
+            (trait_id, ty.clone()),
+            |gb, substitution, _, (trait_id, ty)| {
+                let interner = gb.interner();
+                let substitution = Substitution::from_iter(
+                    interner,
+                    Some(ty.cast(interner))
+                        .into_iter()
+                        .chain(substitution.iter(interner).skip(1).cloned()),
+                );
+                TraitRef {
+                    trait_id,
+                    substitution,
+                }
+            },
+        );
+
+        if solver.has_unique_solution(program, &goal.into_closed_goal(interner)) {
+            implemented.push(trait_id);
+        }
+    }
+    implemented
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chalk_integration::interner::ChalkIr;
+
+    fn empty_subst() -> Canonical<Substitution<ChalkIr>> {
+        Canonical {
+            value: Substitution::empty(&ChalkIr),
+            binders: CanonicalVarKinds::empty(&ChalkIr),
+        }
+    }
+
+    #[test]
+    fn unique_accessors() {
+        let constrained = Canonical {
+            value: ConstrainedSubst {
+                subst: Substitution::empty(&ChalkIr),
+                constraints: Constraints::empty(&ChalkIr),
+            },
+            binders: CanonicalVarKinds::empty(&ChalkIr),
+        };
+        let solution = Solution::Unique(constrained.clone());
+        assert_eq!(solution.as_unique(), Some(&constrained));
+        assert_eq!(solution.guidance(), None);
+    }
+
+    #[test]
+    fn ambig_accessors() {
+        for guidance in [
+            Guidance::Definite(empty_subst()),
+            Guidance::Suggested(empty_subst()),
+            Guidance::Unknown,
+        ] {
+            let solution = Solution::Ambig(guidance.clone());
+            assert_eq!(solution.as_unique(), None);
+            assert_eq!(solution.guidance(), Some(&guidance));
+        }
+    }
 }
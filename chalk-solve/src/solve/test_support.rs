@@ -0,0 +1,207 @@
+//! Helpers for checking solver results in tests.
+//!
+//! Comparing the `Display` output of a `Solution` against a string works,
+//! but the expected strings tend to be long and a mismatch in some
+//! unimportant detail (say, the order of region constraints) fails the
+//! test. An `ExpectedSolution` instead describes only the parts of the
+//! solution a test cares about.
+//!
+//! `solve_with_all` checks that several solvers agree on a goal, which
+//! catches divergences between the SLG and recursive solvers.
+
+use super::{Guidance, Solution, Solver};
+use crate::RustIrDatabase;
+use chalk_ir::interner::Interner;
+use chalk_ir::{Constraint, Constraints, Goal, InEnvironment, Substitution, UCanonical};
+use std::fmt;
+
+/// The kind of solution a test expects.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ExpectedStatus {
+    /// `Solution::Unique`.
+    Unique,
+    /// Any `Solution::Ambig`, regardless of its guidance.
+    Ambiguous,
+    /// `Solution::Ambig(Guidance::Definite(..))`.
+    Definite,
+    /// `Solution::Ambig(Guidance::Suggested(..))`.
+    Suggested,
+    /// `Solution::Ambig(Guidance::Unknown)`.
+    Unknown,
+}
+
+/// A description of the solution a test expects, checked with
+/// [`Solution::matches`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExpectedSolution<I: Interner> {
+    status: ExpectedStatus,
+    subst: Option<Substitution<I>>,
+    constraints: Option<Vec<InEnvironment<Constraint<I>>>>,
+}
+
+impl<I: Interner> ExpectedSolution<I> {
+    pub fn new(status: ExpectedStatus) -> Self {
+        ExpectedSolution {
+            status,
+            subst: None,
+            constraints: None,
+        }
+    }
+
+    pub fn unique() -> Self {
+        Self::new(ExpectedStatus::Unique)
+    }
+
+    pub fn ambiguous() -> Self {
+        Self::new(ExpectedStatus::Ambiguous)
+    }
+
+    pub fn definite() -> Self {
+        Self::new(ExpectedStatus::Definite)
+    }
+
+    pub fn suggested() -> Self {
+        Self::new(ExpectedStatus::Suggested)
+    }
+
+    pub fn unknown() -> Self {
+        Self::new(ExpectedStatus::Unknown)
+    }
+
+    /// Also requires the solution's substitution (or, for an ambiguous
+    /// solution, the substitution in its guidance) to be `subst`.
+    pub fn with_subst(mut self, subst: Substitution<I>) -> Self {
+        self.subst = Some(subst);
+        self
+    }
+
+    /// Also requires the solution to be unique with exactly the region
+    /// constraints `constraints`, in any order.
+    pub fn with_constraints(
+        mut self,
+        constraints: impl IntoIterator<Item = InEnvironment<Constraint<I>>>,
+    ) -> Self {
+        self.constraints = Some(constraints.into_iter().collect());
+        self
+    }
+
+    pub fn status(&self) -> ExpectedStatus {
+        self.status
+    }
+}
+
+impl<I: Interner> fmt::Display for ExpectedSolution<I> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "{:?}", self.status)?;
+        if let Some(subst) = &self.subst {
+            write!(fmt, " with substitution {:?}", subst)?;
+        }
+        if let Some(constraints) = &self.constraints {
+            write!(fmt, " with lifetime constraints {:?}", constraints)?;
+        }
+        Ok(())
+    }
+}
+
+impl<I: Interner> Solution<I> {
+    /// Checks whether this solution has the status and (if given) the
+    /// substitution and region constraints described by `expected`.
+    pub fn matches(&self, interner: &I, expected: &ExpectedSolution<I>) -> bool {
+        let (status_matches, subst, constraints) = match self {
+            Solution::Unique(constrained) => (
+                expected.status == ExpectedStatus::Unique,
+                Some(&constrained.value.subst),
+                Some(constrained.value.constraints.as_slice(interner)),
+            ),
+            Solution::Ambig(guidance) => {
+                let (status, subst) = match guidance {
+                    Guidance::Definite(subst) => (ExpectedStatus::Definite, Some(&subst.value)),
+                    Guidance::Suggested(subst) => (ExpectedStatus::Suggested, Some(&subst.value)),
+                    Guidance::Unknown => (ExpectedStatus::Unknown, None),
+                };
+                (
+                    expected.status == ExpectedStatus::Ambiguous || expected.status == status,
+                    subst,
+                    None,
+                )
+            }
+        };
+        status_matches
+            && matches_if_expected(subst, expected.subst.as_ref(), |subst, expected| {
+                subst == expected
+            })
+            && matches_if_expected(
+                constraints,
+                expected.constraints.as_deref(),
+                same_constraints,
+            )
+    }
+}
+
+/// Nothing expected always matches; something expected never matches
+/// nothing.
+fn matches_if_expected<T: ?Sized, E: ?Sized>(
+    actual: Option<&T>,
+    expected: Option<&E>,
+    matches: impl FnOnce(&T, &E) -> bool,
+) -> bool {
+    match (actual, expected) {
+        (_, None) => true,
+        (Some(actual), Some(expected)) => matches(actual, expected),
+        (None, Some(_)) => false,
+    }
+}
+
+/// Whether `actual` and `expected` hold the same constraints, in any order.
+fn same_constraints<I: Interner>(
+    actual: &[InEnvironment<Constraint<I>>],
+    expected: &[InEnvironment<Constraint<I>>],
+) -> bool {
+    let mut unmatched = actual.to_vec();
+    actual.len() == expected.len()
+        && expected.iter().all(|constraint| {
+            match unmatched.iter().position(|other| other == constraint) {
+                Some(idx) => {
+                    unmatched.swap_remove(idx);
+                    true
+                }
+                None => false,
+            }
+        })
+}
+
+/// Solves `goal` with each of `solvers` and returns the solution they
+/// agree on. Region constraints are compared without regard to their
+/// order. If the solvers disagree, returns the solution each of them
+/// found instead.
+pub fn solve_with_all<I: Interner>(
+    program: &dyn RustIrDatabase<I>,
+    goal: &UCanonical<InEnvironment<Goal<I>>>,
+    solvers: &mut [&mut dyn Solver<I>],
+) -> Result<Option<Solution<I>>, Vec<Option<Solution<I>>>> {
+    let interner = program.interner();
+    let solutions: Vec<_> = solvers
+        .iter_mut()
+        .map(|solver| {
+            solver
+                .solve(program, goal)
+                .map(|solution| sort_constraints(interner, solution))
+        })
+        .collect();
+    match solutions.split_first() {
+        Some((first, rest)) if rest.iter().any(|solution| solution != first) => Err(solutions),
+        _ => Ok(solutions.into_iter().next().flatten()),
+    }
+}
+
+fn sort_constraints<I: Interner>(interner: &I, solution: Solution<I>) -> Solution<I> {
+    match solution {
+        Solution::Unique(mut constrained) => {
+            let mut constraints = constrained.value.constraints.as_slice(interner).to_vec();
+            constraints.sort_by_key(|c| format!("{:?}", c));
+            constrained.value.constraints = Constraints::from_iter(interner, constraints);
+            Solution::Unique(constrained)
+        }
+        solution => solution,
+    }
+}
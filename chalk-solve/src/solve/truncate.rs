@@ -2,9 +2,10 @@
 
 use crate::infer::InferenceTable;
 use chalk_ir::interner::Interner;
-use chalk_ir::visit::{ControlFlow, SuperVisit, Visit, Visitor};
+use chalk_ir::visit::visitors::TySizeVisitor;
+use chalk_ir::visit::Visit;
 use chalk_ir::*;
-use std::cmp::max;
+use tracing::debug;
 
 /// "Truncation" (called "abstraction" in the papers referenced below)
 /// refers to the act of modifying a goal or answer that has become
@@ -24,62 +25,36 @@ pub fn needs_truncation<I: Interner>(
     max_size: usize,
     value: impl Visit<I>,
 ) -> bool {
-    let mut visitor = TySizeVisitor::new(interner, infer);
-    value.visit_with(&mut visitor, DebruijnIndex::INNERMOST);
-
-    visitor.max_size > max_size
-}
-
-struct TySizeVisitor<'infer, 'i, I: Interner> {
-    interner: &'i I,
-    infer: &'infer mut InferenceTable<I>,
-    size: usize,
-    depth: usize,
-    max_size: usize,
+    check_truncation(interner, infer, max_size, value).is_err()
 }
 
-impl<'infer, 'i, I: Interner> TySizeVisitor<'infer, 'i, I> {
-    fn new(interner: &'i I, infer: &'infer mut InferenceTable<I>) -> Self {
-        Self {
-            interner,
-            infer,
-            size: 0,
-            depth: 0,
-            max_size: 0,
-        }
-    }
+/// Records that a value was too large for the solver, and so had to be
+/// truncated: the size of its largest type exceeded the maximum size.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Truncated {
+    /// The size of the largest type in the value.
+    pub size: usize,
+    /// The solver's maximum size.
+    pub max_size: usize,
 }
 
-impl<'infer, 'i, I: Interner> Visitor<'i, I> for TySizeVisitor<'infer, 'i, I> {
-    type BreakTy = ();
-
-    fn as_dyn(&mut self) -> &mut dyn Visitor<'i, I, BreakTy = Self::BreakTy> {
-        self
-    }
-
-    fn visit_ty(&mut self, ty: &Ty<I>, outer_binder: DebruijnIndex) -> ControlFlow<()> {
-        if let Some(normalized_ty) = self.infer.normalize_ty_shallow(self.interner, ty) {
-            normalized_ty.visit_with(self, outer_binder);
-            return ControlFlow::CONTINUE;
-        }
-
-        self.size += 1;
-        self.max_size = max(self.size, self.max_size);
-
-        self.depth += 1;
-        ty.super_visit_with(self, outer_binder);
-        self.depth -= 1;
-
-        // When we get back to the first invocation, clear the counters.
-        // We process each outermost type independently.
-        if self.depth == 0 {
-            self.size = 0;
-        }
-        ControlFlow::CONTINUE
-    }
+/// Like `needs_truncation`, but reports the measured size and the limit
+/// when the value is too large.
+pub fn check_truncation<I: Interner>(
+    interner: &I,
+    infer: &mut InferenceTable<I>,
+    max_size: usize,
+    value: impl Visit<I>,
+) -> Result<(), Truncated> {
+    let mut visitor = TySizeVisitor::new(interner, |ty| infer.normalize_ty_shallow(interner, ty));
+    value.visit_with(&mut visitor, DebruijnIndex::INNERMOST);
 
-    fn interner(&self) -> &'i I {
-        self.interner
+    let size = visitor.max_size();
+    if size > max_size {
+        debug!(size, max_size, "value needs truncation");
+        Err(Truncated { size, max_size })
+    } else {
+        Ok(())
     }
 }
 
@@ -87,6 +62,7 @@ impl<'infer, 'i, I: Interner> Visitor<'i, I> for TySizeVisitor<'infer, 'i, I> {
 mod tests {
     use super::*;
     use chalk_integration::{arg, ty};
+    use chalk_ir::cast::Cast;
 
     #[test]
     fn one_type() {
@@ -102,9 +78,9 @@ mod tests {
                         (apply (item 0)
                          (placeholder 1)))));
 
-        let mut visitor = TySizeVisitor::new(interner, &mut table);
-        ty0.visit_with(&mut visitor, DebruijnIndex::INNERMOST);
-        assert!(visitor.max_size == 5);
+        assert_eq!(ty0.size(interner), 5);
+        assert!(needs_truncation(interner, &mut table, 4, &ty0));
+        assert!(!needs_truncation(interner, &mut table, 5, &ty0));
     }
 
     #[test]
@@ -126,8 +102,38 @@ mod tests {
                        (apply (item 0)
                         (placeholder 1))));
 
-        let mut visitor = TySizeVisitor::new(interner, &mut table);
-        vec![&ty0, &ty1].visit_with(&mut visitor, DebruijnIndex::INNERMOST);
-        assert!(visitor.max_size == 5);
+        assert!(needs_truncation(interner, &mut table, 4, vec![&ty0, &ty1]));
+        assert!(!needs_truncation(interner, &mut table, 5, vec![&ty0, &ty1]));
+    }
+
+    #[test]
+    fn nested_vec_size() {
+        use chalk_integration::interner::ChalkIr;
+        let interner = &ChalkIr;
+        let mut table = InferenceTable::<chalk_integration::interner::ChalkIr>::new();
+
+        // Vec<Vec<u32>>
+        let ty0 = ty!(apply (item 0)
+                      (apply (item 0)
+                       (expr TyKind::Scalar(Scalar::Uint(UintTy::U32)).intern(interner))));
+
+        assert_eq!(ty0.size(interner), 3);
+        assert!(needs_truncation(interner, &mut table, 2, &ty0));
+        assert!(!needs_truncation(interner, &mut table, 3, &ty0));
+    }
+
+    #[test]
+    fn goal_size_is_largest_type() {
+        use chalk_integration::interner::ChalkIr;
+        let interner = &ChalkIr;
+
+        // Vec<Vec<T>> = Vec<T>
+        let goal: Goal<ChalkIr> = EqGoal {
+            a: arg!((apply (item 0) (apply (item 0) (placeholder 1)))),
+            b: arg!((apply (item 0) (placeholder 1))),
+        }
+        .cast(interner);
+
+        assert_eq!(goal.size(interner), 3);
     }
 }
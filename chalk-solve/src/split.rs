@@ -46,7 +46,7 @@ pub trait Split<I: Interner>: RustIrDatabase<I> {
     }
 
     /// Given a projection `<P0 as Trait<P1..Pn>>::Item<Pn..Pm>`,
-    /// returns the trait parameters `[P0..Pn]` (see
+    /// returns the trait ref `P0: Trait<P1..Pn>` (see
     /// `split_projection`).
     fn trait_ref_from_projection<'p>(&self, projection: &'p ProjectionTy<I>) -> TraitRef<I> {
         let interner = self.interner();
@@ -190,10 +190,114 @@ pub trait Split<I: Interner>: RustIrDatabase<I> {
     ) -> (&'p [P], &'p [P]) {
         let trait_datum = &self.trait_datum(associated_ty_datum.trait_id);
         let trait_num_params = trait_datum.binders.len(self.interner());
-        let split_point = parameters.len() - trait_num_params;
-        let (other_params, trait_params) = parameters.split_at(split_point);
-        (trait_params, other_params)
+        split_out_associated_type_parameters(parameters, trait_num_params)
+    }
+
+    /// Gathers the owning trait, the parameter kinds and the declared
+    /// bounds of an associated type, with the parameter kinds already
+    /// split as in `split_associated_ty_parameters`.
+    fn associated_ty_info(&self, associated_ty_id: AssocTypeId<I>) -> AssociatedTyInfo<I> {
+        let interner = self.interner();
+        let associated_ty_datum = self.associated_ty_data(associated_ty_id);
+        let (trait_parameter_kinds, associated_ty_parameter_kinds) = self
+            .split_associated_ty_parameters(
+                associated_ty_datum.binders.binders.as_slice(interner),
+                &associated_ty_datum,
+            );
+        AssociatedTyInfo {
+            trait_id: associated_ty_datum.trait_id,
+            trait_parameter_kinds: trait_parameter_kinds.to_vec(),
+            associated_ty_parameter_kinds: associated_ty_parameter_kinds.to_vec(),
+            bounds: associated_ty_datum
+                .binders
+                .map_ref(|bound| bound.bounds.clone()),
+        }
     }
 }
 
 impl<DB: RustIrDatabase<I> + ?Sized, I: Interner> Split<I> for DB {}
+
+/// The shape of an associated type declaration, as returned by
+/// `Split::associated_ty_info`.
+///
+/// ```ignore (example)
+/// trait Foo<T> {
+///     type Assoc<'a>: Clone;
+/// }
+/// ```
+///
+/// Here the trait parameter kinds are those of `[Self, T]`, the
+/// associated type parameter kinds are those of `['a]`, and the bounds
+/// are `[Clone]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AssociatedTyInfo<I: Interner> {
+    /// The trait this associated type is defined in.
+    pub trait_id: TraitId<I>,
+
+    /// The kinds of the parameters that come from the trait.
+    pub trait_parameter_kinds: Vec<VariableKind<I>>,
+
+    /// The kinds of the associated type's own parameters.
+    pub associated_ty_parameter_kinds: Vec<VariableKind<I>>,
+
+    /// The bounds declared on the associated type, in the scope of all of
+    /// its parameters (ordered as in `AssociatedTyDatum::binders`).
+    pub bounds: Binders<Vec<QuantifiedInlineBound<I>>>,
+}
+
+/// Splits the parameters of an associated type into those for the *trait*
+/// and those for the *associated type itself*, given the number of trait
+/// parameters. When lowering a projection, the associated type's own
+/// parameters come first and the trait's parameters are appended, so
+/// `<P0 as Trait<P1>>::Assoc<'a, U>` has the parameters `['a, U, P0, P1]`,
+/// which this splits into `([P0, P1], ['a, U])`.
+///
+/// Panics if there are fewer parameters than `num_trait_params`.
+pub fn split_out_associated_type_parameters<P>(
+    parameters: &[P],
+    num_trait_params: usize,
+) -> (&[P], &[P]) {
+    assert!(
+        parameters.len() >= num_trait_params,
+        "expected at least {} parameters, found {}",
+        num_trait_params,
+        parameters.len()
+    );
+
+    // the trait parameters are a suffix
+    //
+    // [ P0..Pn, Pn...Pm ]
+    //           ^^^^^^^ trait parameters
+    let split_point = parameters.len() - num_trait_params;
+    let (other_params, trait_params) = parameters.split_at(split_point);
+    (trait_params, other_params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_associated_type_parameters() {
+        // `<P0 as Trait<P1>>::Assoc`
+        let parameters = ["P0", "P1"];
+        let (trait_params, assoc_params) = split_out_associated_type_parameters(&parameters, 2);
+        assert_eq!(trait_params, ["P0", "P1"]);
+        assert!(assoc_params.is_empty());
+    }
+
+    #[test]
+    fn multiple_associated_type_parameters() {
+        // `<P0 as Trait<P1>>::Assoc<'a, U>`
+        let parameters = ["'a", "U", "P0", "P1"];
+        let (trait_params, assoc_params) = split_out_associated_type_parameters(&parameters, 2);
+        assert_eq!(trait_params, ["P0", "P1"]);
+        assert_eq!(assoc_params, ["'a", "U"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "expected at least 2 parameters, found 1")]
+    fn too_few_parameters() {
+        split_out_associated_type_parameters(&["P0"], 2);
+    }
+}
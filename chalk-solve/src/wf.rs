@@ -1,7 +1,14 @@
 use std::{fmt, iter};
 
 use crate::{
-    ext::*, goal_builder::GoalBuilder, rust_ir::*, solve::Solver, split::Split, RustIrDatabase,
+    display::{write_item_where_clause, WriterState},
+    ext::*,
+    goal_builder::GoalBuilder,
+    logging_db::RecordedItemId,
+    rust_ir::*,
+    solve::Solver,
+    split::Split,
+    RustIrDatabase,
 };
 use chalk_ir::{
     cast::*,
@@ -43,6 +50,57 @@ impl<I: Interner> fmt::Display for WfError<I> {
 
 impl<I: Interner> std::error::Error for WfError<I> {}
 
+/// A where clause declared on a trait or type that can never be
+/// satisfied; see `WfSolver::unsatisfiable_trait_where_clauses`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum UnsatisfiableWhereClause<I: Interner> {
+    Trait(TraitId<I>, Binders<QuantifiedWhereClause<I>>),
+    Adt(AdtId<I>, Binders<QuantifiedWhereClause<I>>),
+}
+
+impl<I: Interner> UnsatisfiableWhereClause<I> {
+    /// Displays an error message for the where clause, which is written as
+    /// Rust, with the parameters of the item named as the display writer
+    /// names them.
+    pub fn display<'a, DB: RustIrDatabase<I>>(&'a self, db: &'a DB) -> impl fmt::Display + 'a {
+        DisplayUnsatisfiableWhereClause {
+            db,
+            where_clause: self,
+        }
+    }
+}
+
+struct DisplayUnsatisfiableWhereClause<'a, I: Interner, DB> {
+    db: &'a DB,
+    where_clause: &'a UnsatisfiableWhereClause<I>,
+}
+
+impl<I: Interner, DB: RustIrDatabase<I>> fmt::Display
+    for DisplayUnsatisfiableWhereClause<'_, I, DB>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (item, kind, name, wc) = match self.where_clause {
+            UnsatisfiableWhereClause::Trait(id, wc) => (
+                RecordedItemId::Trait(*id),
+                "trait",
+                self.db.trait_name(*id),
+                wc,
+            ),
+            UnsatisfiableWhereClause::Adt(id, wc) => {
+                (RecordedItemId::Adt(*id), "type", self.db.adt_name(*id), wc)
+            }
+        };
+        let ws = &WriterState::<I, DB, _>::new(self.db);
+        let mut clause = String::new();
+        write_item_where_clause(&mut clause, ws, item, wc)?;
+        write!(
+            f,
+            "where clause `{}` on {} `{}` can never be satisfied",
+            clause, kind, name
+        )
+    }
+}
+
 pub struct WfSolver<'a, I: Interner> {
     db: &'a dyn RustIrDatabase<I>,
     solver_builder: &'a dyn Fn() -> Box<dyn Solver<I>>,
@@ -325,10 +383,15 @@ where
             ),
         );
 
-        if let Some(well_known) = self.db.trait_datum(trait_id).well_known {
+        let trait_datum = self.db.trait_datum(trait_id);
+        if let Some(well_known) = trait_datum.well_known {
             self.verify_well_known_impl(impl_id, well_known)?
         }
 
+        if trait_datum.is_auto_trait() {
+            self.verify_auto_trait_impl(impl_id)?
+        }
+
         debug!("WF trait goal: {:?}", impl_goal);
 
         let mut fresh_solver = (self.solver_builder)();
@@ -342,6 +405,93 @@ where
         }
     }
 
+    /// Checks whether an impl can never apply because its where clauses
+    /// are unsatisfiable for every choice of its parameters, e.g.
+    /// `impl<T> Foo for T where u32: Unimplemented`. Such impls are not
+    /// ill-formed, but they are almost certainly a mistake, so this is
+    /// useful as a diagnostic.
+    pub fn is_dead_impl(&self, impl_id: ImplId<I>) -> bool {
+        let interner = self.db.interner();
+
+        let impl_datum = self.db.impl_datum(impl_id);
+        if !impl_datum.is_positive() {
+            return false;
+        }
+
+        // exists<P..> { WC }
+        let mut gb = GoalBuilder::new(self.db);
+        let where_clauses = impl_datum.binders.map_ref(|b| &b.where_clauses);
+        let impl_goal = gb.exists(&where_clauses, (), |gb, _, where_clauses, ()| {
+            let interner = gb.interner();
+            gb.all(
+                where_clauses
+                    .iter()
+                    .cloned()
+                    .casted::<Goal<I>>(interner)
+                    .collect::<Vec<_>>(),
+            )
+        });
+
+        debug!("dead impl goal: {:?}", impl_goal);
+
+        let mut fresh_solver = (self.solver_builder)();
+        fresh_solver
+            .solve(self.db, &impl_goal.into_closed_goal(interner))
+            .is_none()
+    }
+
+    /// Returns the where clauses declared on a trait that can never be
+    /// satisfied, whatever the trait's parameters are, e.g. `u32: Unimplemented`
+    /// in `trait Foo where u32: Unimplemented { }`. Such a trait cannot be
+    /// used, which is almost certainly a mistake, so like `is_dead_impl`
+    /// this is useful as a diagnostic.
+    pub fn unsatisfiable_trait_where_clauses(
+        &self,
+        trait_id: TraitId<I>,
+    ) -> Vec<UnsatisfiableWhereClause<I>> {
+        let trait_datum = self.db.trait_datum(trait_id);
+        self.unsatisfiable_where_clauses(trait_datum.binders.map_ref(|b| &b.where_clauses))
+            .map(|wc| UnsatisfiableWhereClause::Trait(trait_id, wc))
+            .collect()
+    }
+
+    /// Like `unsatisfiable_trait_where_clauses`, but for the where clauses
+    /// of an ADT.
+    pub fn unsatisfiable_adt_where_clauses(
+        &self,
+        adt_id: AdtId<I>,
+    ) -> Vec<UnsatisfiableWhereClause<I>> {
+        let adt_datum = self.db.adt_datum(adt_id);
+        self.unsatisfiable_where_clauses(adt_datum.binders.map_ref(|b| &b.where_clauses))
+            .map(|wc| UnsatisfiableWhereClause::Adt(adt_id, wc))
+            .collect()
+    }
+
+    /// Checks each of `where_clauses` on its own, returning those for
+    /// which `exists<P..> { WC }` has no solution.
+    fn unsatisfiable_where_clauses<'w>(
+        &'w self,
+        where_clauses: Binders<&'w Vec<QuantifiedWhereClause<I>>>,
+    ) -> impl Iterator<Item = Binders<QuantifiedWhereClause<I>>> + 'w {
+        let interner = self.db.interner();
+        (0..where_clauses.skip_binders().len())
+            .map(move |index| where_clauses.map_ref(|wcs| wcs[index].clone()))
+            .filter(move |where_clause| {
+                // exists<P..> { WC }
+                let mut gb = GoalBuilder::new(self.db);
+                let goal = gb.exists(where_clause, (), |gb, _, where_clause, ()| {
+                    where_clause.clone().cast::<Goal<I>>(gb.interner())
+                });
+
+                debug!("where clause goal: {:?}", goal);
+
+                let mut fresh_solver = (self.solver_builder)();
+                fresh_solver
+                    .solve(self.db, &goal.into_closed_goal(interner))
+                    .is_none()
+            })
+    }
+
     pub fn verify_opaque_ty_decl(&self, opaque_ty_id: OpaqueTyId<I>) -> Result<(), WfError<I>> {
         // Given an opaque type like
         // ```notrust
@@ -396,6 +546,36 @@ where
         }
     }
 
+    /// Verify the where clauses of an explicit impl of an auto trait. Such
+    /// an impl may only depend on other auto traits and on outlives
+    /// bounds, as in `impl<T> Send for Foo<T> where T: Send`. Anything
+    /// else, such as `impl<T> Send for Foo<T> where T: Bar`, is rejected.
+    pub fn verify_auto_trait_impl(&self, impl_id: ImplId<I>) -> Result<(), WfError<I>> {
+        let impl_datum = self.db.impl_datum(impl_id);
+        if !impl_datum.is_positive() {
+            return Ok(());
+        }
+
+        let is_legal = impl_datum
+            .binders
+            .skip_binders()
+            .where_clauses
+            .iter()
+            .all(|wc| match wc.skip_binders() {
+                WhereClause::Implemented(trait_ref) => {
+                    self.db.trait_datum(trait_ref.trait_id).is_auto_trait()
+                }
+                WhereClause::LifetimeOutlives(..) | WhereClause::TypeOutlives(..) => true,
+                WhereClause::AliasEq(..) => false,
+            });
+
+        if is_legal {
+            Ok(())
+        } else {
+            Err(WfError::IllFormedTraitImpl(impl_datum.trait_id()))
+        }
+    }
+
     /// Verify builtin rules for well-known traits
     pub fn verify_well_known_impl(
         &self,
@@ -556,9 +736,9 @@ fn compute_assoc_ty_goal<I: Interner>(
 
     // Create `forall<T, 'a> { .. }`
     Some(gb.forall(
-        &assoc_ty.value.map_ref(|v| &v.ty),
+        &assoc_ty.value,
         assoc_ty_id,
-        |gb, assoc_ty_substitution, value_ty, assoc_ty_id| {
+        |gb, assoc_ty_substitution, value, assoc_ty_id| {
             let interner = gb.interner();
             let db = gb.db();
 
@@ -611,10 +791,13 @@ fn compute_assoc_ty_goal<I: Interner>(
                     .clone()
                     .substitute(interner, &projection.substitution);
 
-                // Create `if (/* where clauses on associated type value */) { .. }`
+                // Create `if (/* where clauses on associated type value */) { .. }`,
+                // assuming both those from the trait and those on the value itself.
+                let value_ty = &value.ty;
                 gb.implies(
                     defn_where_clauses
                         .iter()
+                        .chain(&value.where_clauses)
                         .cloned()
                         .map(|qwc| qwc.into_from_env_goal(interner)),
                     |gb| {
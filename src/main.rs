@@ -4,6 +4,7 @@ extern crate serde_derive;
 use std::fs::File;
 use std::io::Read;
 use std::process::exit;
+use std::time::Instant;
 
 use chalk_integration::db::ChalkDatabase;
 use chalk_integration::interner::ChalkIr;
@@ -12,7 +13,7 @@ use chalk_integration::query::LoweringDatabase;
 use chalk_integration::SolverChoice;
 use chalk_solve::ext::*;
 use chalk_solve::logging;
-use chalk_solve::RustIrDatabase;
+use chalk_solve::{RustIrDatabase, Solution, SubstitutionResult};
 use docopt::Docopt;
 use rustyline::error::ReadlineError;
 
@@ -28,49 +29,133 @@ Usage:
 Options:
   --help              Show this screen.
   --program=PATH      Specifies the path to the `.chalk` file containing traits/impls.
+  --program-dir=PATH  Specifies a directory whose `.chalk` files, in sorted filename order,
+                      together make up the program.
   --goal=GOAL         Specifies a goal to evaluate (may be given more than once).
+  --goal-file=PATH    Specifies a file of goals to evaluate, one per line.
   --overflow-depth=N  Specifies the overflow depth [default: 10].
+  --max-tables=N      Limits the number of tables the solver may create for each goal.
   --multiple          Output multiple answers instead of ambiguous solution.
+  --warn-unused-clauses  After evaluating the goals, report custom clauses that were never used.
+  --quiet             Do not print solutions; only report the outcome in the exit code.
+  --time              Print the wall-clock time taken to solve each goal, in seconds.
+  --no-implied-bounds  Do not elaborate the hypotheses of `if` goals into their implied bounds.
+  --explain           When a goal has no solution, name a subgoal that failed. This solves
+                      the goal a second time.
+
+When evaluating goals, the exit code is 0 if every goal has a unique
+solution, 2 if some goal is ambiguous, and 1 if some goal has no solution
+or could not be evaluated.
 ";
 
 /// This struct represents the various command line options available.
 #[derive(Debug, Deserialize)]
 struct Args {
     flag_program: Option<String>,
+    flag_program_dir: Option<String>,
     flag_goal: Vec<String>,
+    flag_goal_file: Option<String>,
     flag_overflow_depth: usize,
+    flag_max_tables: Option<usize>,
     flag_multiple: bool,
+    flag_warn_unused_clauses: bool,
+    flag_quiet: bool,
+    flag_time: bool,
+    flag_no_implied_bounds: bool,
+    flag_explain: bool,
+}
+
+/// How a goal was answered. When evaluating goals in batch mode, the worst
+/// outcome determines the exit code.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Outcome {
+    Unique,
+    Ambiguous,
+    NoSolution,
+}
+
+impl Outcome {
+    fn exit_code(self) -> i32 {
+        match self {
+            Outcome::Unique => 0,
+            Outcome::Ambiguous => 2,
+            Outcome::NoSolution => 1,
+        }
+    }
 }
 
 /// A loaded and parsed program.
 struct LoadedProgram {
     text: String,
     db: ChalkDatabase,
+    goal_lowering: GoalLoweringOptions,
 }
 
 impl LoadedProgram {
-    /// Creates a new Program struct, given a `.chalk` file as a String and
-    /// a [`SolverChoice`].
+    /// Creates a new Program struct, given a `.chalk` file as a String,
+    /// a [`SolverChoice`], and the options used to lower goals.
     ///
     /// [`SolverChoice`]: struct.solve.SolverChoice.html
-    fn new(text: String, solver_choice: SolverChoice) -> Result<LoadedProgram> {
+    fn new(
+        text: String,
+        solver_choice: SolverChoice,
+        goal_lowering: GoalLoweringOptions,
+    ) -> Result<LoadedProgram> {
         let db = ChalkDatabase::with(&text, solver_choice);
-        Ok(LoadedProgram { text, db })
+        Ok(LoadedProgram {
+            text,
+            db,
+            goal_lowering,
+        })
     }
 
     /// Parse a goal and attempt to solve it, using the specified solver.
+    /// Unless `quiet` is set, the solution is printed, along with a failed
+    /// subgoal if there is no solution and `explain` is set. If `time` is
+    /// set, the time taken to solve the goal is printed after the solution.
     fn goal(
+        &self,
+        rl: Option<&mut rustyline::Editor<()>>,
+        text: &str,
+        multiple_answers: bool,
+        quiet: bool,
+        time: bool,
+        explain: bool,
+    ) -> Result<Outcome> {
+        let start = Instant::now();
+        let outcome = self.solve_goal(rl, text, multiple_answers, quiet, explain)?;
+        if time {
+            println!("time: {:.6}s", start.elapsed().as_secs_f64());
+        }
+        Ok(outcome)
+    }
+
+    /// Does the work of `goal`, apart from timing it.
+    fn solve_goal(
         &self,
         mut rl: Option<&mut rustyline::Editor<()>>,
         text: &str,
         multiple_answers: bool,
-    ) -> Result<()> {
+        quiet: bool,
+        explain: bool,
+    ) -> Result<Outcome> {
         let program = self.db.checked_program()?;
-        let goal = lower_goal(&*chalk_parse::parse_goal(text)?, &*program)?;
+        let goal = lower_goal_with_options(
+            &*chalk_parse::parse_goal(text)?,
+            &*program,
+            self.goal_lowering,
+        )?;
         let peeled_goal = goal.into_peeled_goal(self.db.interner());
         if multiple_answers {
+            // A goal is only unique if it has exactly one definite answer.
+            let mut answers = 0;
+            let mut ambiguous = false;
             if self.db.solve_multiple(&peeled_goal, &mut |v, has_next| {
-                println!("{}\n", v.as_ref().map(|v| v.display(&ChalkIr)));
+                answers += 1;
+                ambiguous |= !matches!(v, SubstitutionResult::Definite(_));
+                if !quiet {
+                    println!("{}\n", v.as_ref().map(|v| v.display(&ChalkIr)));
+                }
                 if has_next {
                     if let Some(ref mut rl) = rl {
                         loop {
@@ -92,16 +177,52 @@ impl LoadedProgram {
                 } else {
                     true
                 }
-            }) {
+            }) && !quiet
+            {
                 println!("No more solutions");
             }
+            Ok(match answers {
+                0 => Outcome::NoSolution,
+                1 if !ambiguous => Outcome::Unique,
+                _ => Outcome::Ambiguous,
+            })
         } else {
-            match self.db.solve(&peeled_goal) {
-                Some(v) => println!("{}\n", v.display(&ChalkIr)),
-                None => println!("No possible solution.\n"),
+            // Explaining a failure means solving the goal again, so only
+            // do it when asked to.
+            let (solution, reason) = if explain && !quiet {
+                self.db.solve_with_explanation(&peeled_goal)
+            } else {
+                (self.db.solve(&peeled_goal), None)
+            };
+            if !quiet {
+                match (&solution, reason) {
+                    (Some(v), _) => println!("{}\n", v.display(&ChalkIr)),
+                    (None, Some(reason)) => println!(
+                        "No possible solution.\nno answers for `{:?}`\n",
+                        reason.goal.canonical.value.goal
+                    ),
+                    (None, None) => println!("No possible solution.\n"),
+                }
+            }
+            Ok(match solution {
+                Some(Solution::Unique(_)) => Outcome::Unique,
+                Some(Solution::Ambig(_)) => Outcome::Ambiguous,
+                None => Outcome::NoSolution,
+            })
+        }
+    }
+
+    /// Print a warning for each custom clause that none of the goals solved
+    /// so far could have used.
+    fn warn_unused_clauses(&self) {
+        match self.db.unused_custom_clauses() {
+            Some(clauses) => {
+                for clause in clauses {
+                    eprintln!("warning: custom clause is never used: {:?}", clause);
+                }
             }
+            None => eprintln!("warning: the current solver cannot report unused clauses"),
         }
-        Ok(())
     }
 }
 
@@ -117,10 +238,19 @@ fn run() -> Result<()> {
         exit(1);
     }
 
-    // Load the .chalk file, if given.
+    // Load the .chalk file(s), if given.
+    let loaded = match (&args.flag_program, &args.flag_program_dir) {
+        (Some(_), Some(_)) => {
+            eprintln!("error: `--program` and `--program-dir` cannot be combined");
+            exit(1);
+        }
+        (Some(program), None) => Some(load_program(args, program)),
+        (None, Some(dir)) => Some(load_program_dir(args, dir)),
+        (None, None) => None,
+    };
     let mut prog = None;
-    if let Some(program) = &args.flag_program {
-        match load_program(args, program) {
+    if let Some(loaded) = loaded {
+        match loaded {
             Ok(p) => prog = Some(p),
             Err(err) => {
                 eprintln!("error loading program: {}", err);
@@ -129,7 +259,7 @@ fn run() -> Result<()> {
         }
     }
 
-    if args.flag_goal.is_empty() {
+    if args.flag_goal.is_empty() && args.flag_goal_file.is_none() {
         // The user specified no goal. Enter interactive mode.
         readline_loop(&mut rustyline::Editor::new(), "?- ", |rl, line| {
             if let Err(e) = process(args, line, rl, &mut prog) {
@@ -140,22 +270,61 @@ fn run() -> Result<()> {
         // Check that a program was provided.
         // TODO: It's customary to print Usage info when an error like this
         // happens.
-        let prog =
-            prog.ok_or("error: cannot eval without a program; use `--program` to specify one.")?;
+        let prog = prog.ok_or(
+            "error: cannot eval without a program; use `--program` or `--program-dir` to specify one.",
+        )?;
+
+        let file_goals = match &args.flag_goal_file {
+            Some(path) => read_goal_file(path)?,
+            None => vec![],
+        };
 
         // Evaluate the goal(s). If any goal returns an error, print the error
-        // and exit.
-        prog.db.with_program(|_| -> Result<()> {
+        // and exit. Otherwise, exit according to the worst outcome.
+        let outcome = prog.db.with_program(|_| {
+            let mut outcome = Outcome::Unique;
             for g in &args.flag_goal {
-                if let Err(e) = prog.goal(None, g, args.flag_multiple) {
-                    eprintln!("error: {}", e);
-                    exit(1);
+                match prog.goal(
+                    None,
+                    g,
+                    args.flag_multiple,
+                    args.flag_quiet,
+                    args.flag_time,
+                    args.flag_explain,
+                ) {
+                    Ok(o) => outcome = outcome.max(o),
+                    Err(e) => {
+                        eprintln!("error: {}", e);
+                        exit(1);
+                    }
                 }
             }
-            Ok(())
-        })?;
+            for g in &file_goals {
+                if !args.flag_quiet {
+                    print!("{} => ", g);
+                }
+                match prog.goal(
+                    None,
+                    g,
+                    args.flag_multiple,
+                    args.flag_quiet,
+                    args.flag_time,
+                    args.flag_explain,
+                ) {
+                    Ok(o) => outcome = outcome.max(o),
+                    Err(e) => {
+                        eprintln!("error: {}", e);
+                        exit(1);
+                    }
+                }
+            }
+            if args.flag_warn_unused_clauses {
+                prog.warn_unused_clauses();
+            }
+            outcome
+        });
 
-        Ok(())
+        exit(outcome.exit_code());
     }
 }
 
@@ -207,7 +376,11 @@ fn process(
         help()
     } else if command == "program" {
         // Load a .chalk file via stdin, until EOF is found.
-        let chalk_prog = LoadedProgram::new(read_program(rl)?, args.solver_choice())?;
+        let chalk_prog = LoadedProgram::new(
+            read_program(rl)?,
+            args.solver_choice(),
+            args.goal_lowering_options(),
+        )?;
         // Let's do a sanity check before going forward.
         let _ = chalk_prog.db.checked_program()?;
         *prog = Some(chalk_prog);
@@ -218,13 +391,17 @@ fn process(
         // Let's do a sanity check before going forward.
         let _ = chalk_prog.db.checked_program()?;
         *prog = Some(chalk_prog);
+    } else if command == "intern-stats" {
+        let stats = ChalkIr.intern_stats();
+        println!("interned types: {}", stats.tys);
+        println!("interned goals: {}", stats.goals);
     } else if command.starts_with("debug ") {
         match command.split_whitespace().nth(1) {
             Some(level) => std::env::set_var("CHALK_DEBUG", level),
             None => println!("debug <level> set debug level to <level>"),
         }
     } else {
-        // The command is either "print", "lowered", or a goal.
+        // The command is either "print", "lowered", "tables", or a goal.
 
         // Check that a program has been loaded.
         let prog = prog
@@ -240,10 +417,25 @@ fn process(
                 // TODO: Write a line of documentation here.
                 "lowered" => println!("{:#?}", prog.db.environment()),
 
+                // Print out the tables the solver has built up so far.
+                "tables" => match prog.db.dump_tables() {
+                    Some(dump) => print!("{}", dump),
+                    None => println!("the current solver does not keep tables"),
+                },
+
                 // Assume this is a goal.
                 // TODO: Print out "type 'help' to see available commands" if it
                 // fails to parse?
-                _ => prog.goal(Some(rl), command, args.flag_multiple)?,
+                _ => {
+                    prog.goal(
+                        Some(rl),
+                        command,
+                        args.flag_multiple,
+                        args.flag_quiet,
+                        args.flag_time,
+                        args.flag_explain,
+                    )?;
+                }
             }
             Ok(())
         })?
@@ -258,7 +450,56 @@ fn process(
 fn load_program(args: &Args, filename: &str) -> Result<LoadedProgram> {
     let mut text = String::new();
     File::open(filename)?.read_to_string(&mut text)?;
-    Ok(LoadedProgram::new(text, args.solver_choice())?)
+    Ok(LoadedProgram::new(
+        text,
+        args.solver_choice(),
+        args.goal_lowering_options(),
+    )?)
+}
+
+/// Load all `.chalk` files in the directory, concatenated in sorted filename
+/// order, and parse them as a single program. The name of each file is
+/// reported on stderr as it is loaded.
+fn load_program_dir(args: &Args, dirname: &str) -> Result<LoadedProgram> {
+    let in_dir = |e: std::io::Error| format!("`{}`: {}", dirname, e);
+    let mut paths = vec![];
+    for entry in std::fs::read_dir(dirname).map_err(in_dir)? {
+        let path = entry.map_err(in_dir)?.path();
+        if path.is_file() && path.extension() == Some("chalk".as_ref()) {
+            paths.push(path);
+        }
+    }
+    if paths.is_empty() {
+        return Err(format!("no `.chalk` files found in `{}`", dirname).into());
+    }
+    paths.sort();
+
+    // Parse each file on its own before joining them, so that a parse
+    // error names the file it is in and its offsets are relative to it.
+    let mut text = String::new();
+    for path in &paths {
+        let file_text =
+            std::fs::read_to_string(path).map_err(|e| format!("`{}`: {}", path.display(), e))?;
+        chalk_parse::parse_program(&file_text)
+            .map_err(|e| format!("`{}`: {}", path.display(), e))?;
+        text += &file_text;
+        text += "\n";
+        eprintln!("loaded {}", path.display());
+    }
+    LoadedProgram::new(text, args.solver_choice(), args.goal_lowering_options())
+}
+
+/// Read the goals in a goal file, one per line. Empty lines and lines
+/// starting with `//` are skipped.
+fn read_goal_file(filename: &str) -> Result<Vec<String>> {
+    let mut text = String::new();
+    File::open(filename)?.read_to_string(&mut text)?;
+    Ok(text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("//"))
+        .map(String::from)
+        .collect())
 }
 
 /// Print out help for commands in interpreter mode.
@@ -270,8 +511,10 @@ fn help() {
     println!("  load <file>   load program from <file>");
     println!("  print         print the current program");
     println!("  lowered       print the lowered program");
+    println!("  tables        print the solver's tables");
     println!("  <goal>        attempt to solve <goal>");
     println!("  debug <level> set debug level to <level>");
+    println!("  intern-stats  print how many types and goals were interned");
 }
 
 /// Read a program from the command-line. Stop reading when EOF is read. If
@@ -291,6 +534,14 @@ impl Args {
         SolverChoice::SLG {
             max_size: self.flag_overflow_depth,
             expected_answers: None,
+            max_tables: self.flag_max_tables,
+        }
+    }
+
+    fn goal_lowering_options(&self) -> GoalLoweringOptions {
+        GoalLoweringOptions {
+            elaborate_implied_bounds: !self.flag_no_implied_bounds,
+            ..GoalLoweringOptions::default()
         }
     }
 }
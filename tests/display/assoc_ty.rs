@@ -15,6 +15,24 @@ fn test_trait_impl_assoc_type() {
     );
 }
 #[test]
+fn test_impl_assoc_type_with_where_clause() {
+    // Test printing where clauses on an associated type value
+    reparse_test!(
+        program {
+            struct Foo<T> { }
+            trait Baz { }
+            trait Bar {
+                type Assoc;
+            }
+            impl<T> Bar for Foo<T> {
+                type Assoc = T
+                where
+                    T: Baz;
+            }
+        }
+    );
+}
+#[test]
 fn test_trait_with_multiple_assoc_types() {
     // Test multiple associated types per trait
     reparse_test!(
@@ -113,3 +113,41 @@ fn test_struct_repr_with_flags() {
         }
     );
 }
+
+#[test]
+fn test_raw_identifier_names() {
+    // Test that names which are keywords are written as raw identifiers
+    reparse_test!(
+        program {
+            struct r#struct {}
+            trait r#reveal {
+                type r#type;
+            }
+            impl r#reveal for r#struct {
+                type r#type = r#struct;
+            }
+        }
+    );
+}
+
+#[test]
+fn test_every_keyword_as_raw_identifier() {
+    // Every word the grammar reserves must be escaped by the writer, so a
+    // struct named after any of them has to survive a round trip. The words
+    // are taken from the grammar itself so that a new keyword fails here
+    // until it is added to the writer's keyword list too.
+    let grammar = include_str!("../../chalk-parse/src/parser.lalrpop");
+    let keywords: std::collections::BTreeSet<&str> =
+        regex::Regex::new(r#""([A-Za-z_][A-Za-z0-9_]*)""#)
+            .unwrap()
+            .captures_iter(grammar)
+            .map(|c| c.get(1).unwrap().as_str())
+            // These are compared against an `Id` in the grammar's actions
+            // rather than being terminals, so they stay usable as names.
+            .filter(|kw| !["C", "Sized", "packed"].contains(kw))
+            .collect();
+    assert!(keywords.contains("struct"));
+    for kw in keywords {
+        crate::display::util::reparse_test(&format!("struct r#{} {{}}", kw));
+    }
+}
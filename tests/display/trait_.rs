@@ -108,3 +108,39 @@ fn test_lang_with_flag() {
         }
     );
 }
+
+#[test]
+fn test_trait_alias() {
+    // Test printing trait aliases, with bounds and where clauses
+    reparse_test!(
+        program {
+            trait Foo<T> {}
+            trait Bar {
+                type Item;
+            }
+            trait Baz {}
+            trait Alias<T> = Foo<T> + Bar<Item = T> where T: Baz;
+            trait Empty = ;
+        }
+    );
+}
+
+#[test]
+fn test_trait_provided_clauses() {
+    // Test printing the clauses a trait provides, including ones with their
+    // own binders, several conditions and trait parameters
+    reparse_test!(
+        program {
+            trait Show {}
+            trait Cmp<T> {}
+            trait Debug {
+                clause Self: Show;
+                clause forall<T> { Self: Cmp<T> :- T: Debug };
+            }
+            trait Eq<U> {
+                clause forall<T, 'a> { Self: Cmp<T> :- T: Cmp<U>, not { U: Show } };
+                clause WellFormed(Self: Show) :- exists<V> { V: Eq<U> };
+            }
+        }
+    );
+}
@@ -225,9 +225,10 @@ pub fn run_reparse_with_duplicate_names<'a>(program_text: &'a str) -> ReparseTes
             e, program_text
         )
     });
-    let output_text = tls::set_current_program(&original_program, || {
+    let output_text = {
+        let _guard = tls::set_current_program(&original_program);
         write_program_duplicated_names(&*original_program)
-    });
+    };
     let output_db = chalk_integration::db::ChalkDatabase::with(&output_text, <_>::default());
     let output_program = output_db.program_ir().unwrap_or_else(|e| {
         panic!(
@@ -201,8 +201,10 @@ pub fn reparse_into_different_test<'a>(
             e, program_text
         )
     });
-    let output_text =
-        tls::set_current_program(&original_program, || write_program(&original_program));
+    let output_text = {
+        let _guard = tls::set_current_program(&original_program);
+        write_program(&original_program)
+    };
     let output_db = chalk_integration::db::ChalkDatabase::with(&output_text, <_>::default());
     let output_program = output_db.program_ir().unwrap_or_else(|e| {
         panic!(
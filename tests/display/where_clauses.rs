@@ -238,3 +238,21 @@ fn test_forall_in_where() {
         }
     );
 }
+
+#[test]
+fn test_implicit_sized_bounds() {
+    // Type parameters get an implicit `Sized` bound unless declared
+    // `?Sized`; neither should change when the program is reparsed.
+    reparse_test!(
+        program {
+            #[lang(sized)]
+            trait Sized { }
+            struct Ref<T: ?Sized> { }
+            struct Val<T> { }
+            struct Both<T, U: ?Sized> where T: Sized { }
+            trait Foo<T: ?Sized, U> { }
+            impl<T: ?Sized, U> Foo<T, U> for Ref<T> { }
+            fn foo<T: ?Sized, U>(t: Ref<T>) -> Val<U>;
+        }
+    );
+}
@@ -0,0 +1,51 @@
+use chalk_integration::db::ChalkDatabase;
+use chalk_integration::query::LoweringDatabase;
+use chalk_integration::SolverChoice;
+use chalk_ir::*;
+use chalk_solve::rust_ir::InlineBound;
+use chalk_solve::split::Split;
+
+#[test]
+fn associated_ty_info_splits_gat_parameters() {
+    let db = ChalkDatabase::with(
+        "
+            trait Clone { }
+            trait Foo<T> {
+                type Assoc<'a, U>: Clone;
+            }
+        ",
+        SolverChoice::default(),
+    );
+    let program = db.checked_program().unwrap();
+    let (&assoc_id, _) = program
+        .associated_ty_data
+        .iter()
+        .find(|(_, datum)| datum.name.as_ref() == "Assoc")
+        .unwrap();
+    let info = program.associated_ty_info(assoc_id);
+
+    assert_eq!(info.trait_id, program.trait_ids[&"Foo".into()]);
+    assert_eq!(
+        info.trait_parameter_kinds,
+        vec![
+            VariableKind::Ty(TyVariableKind::General),
+            VariableKind::Ty(TyVariableKind::General),
+        ]
+    );
+    assert_eq!(
+        info.associated_ty_parameter_kinds,
+        vec![
+            VariableKind::Lifetime,
+            VariableKind::Ty(TyVariableKind::General)
+        ]
+    );
+
+    let bounds = info.bounds.skip_binders();
+    assert_eq!(bounds.len(), 1);
+    match bounds[0].skip_binders() {
+        InlineBound::TraitBound(bound) => {
+            assert_eq!(bound.trait_id, program.trait_ids[&"Clone".into()])
+        }
+        bound => panic!("expected a trait bound, found {:?}", bound),
+    }
+}
@@ -0,0 +1,52 @@
+use chalk_integration::db::ChalkDatabase;
+use chalk_ir::*;
+use chalk_solve::ext::GoalExt;
+use chalk_solve::RustIrDatabase;
+
+#[test]
+fn goal_assuming_where_clauses() {
+    for solver_choice in super::solver_choices() {
+        let db = ChalkDatabase::with(
+            "trait Clone { } struct Vec<T> { } impl<T> Clone for Vec<T> where T: Clone { }",
+            solver_choice,
+        );
+        let goal = db
+            .parse_and_lower_goal("forall<T> { Vec<T>: Clone }")
+            .unwrap();
+        db.with_program(|program| {
+            let interner = db.interner();
+            assert!(db.solve(&goal.clone().into_peeled_goal(interner)).is_none());
+
+            // forall<T> { if (T: Clone) { Vec<T>: Clone } }
+            let t_clone = WhereClause::Implemented(TraitRef {
+                trait_id: program.trait_ids[&"Clone".into()],
+                substitution: Substitution::from1(
+                    interner,
+                    TyKind::BoundVar(BoundVar::new(DebruijnIndex::INNERMOST, 0)).intern(interner),
+                ),
+            });
+            let binders = match goal.data(interner) {
+                GoalData::Quantified(QuantifierKind::ForAll, binders) => binders.clone(),
+                _ => panic!("expected a `forall` goal"),
+            };
+            let binders = binders.map(|goal| goal.assuming(interner, vec![t_clone]));
+
+            // The assumption is a `FromEnv` fact, as with `if (T: Clone)`.
+            match binders.skip_binders().data(interner) {
+                GoalData::Implies(clauses, _) => {
+                    let clauses = clauses.as_slice(interner);
+                    assert_eq!(clauses.len(), 1);
+                    assert!(matches!(
+                        clauses[0].data(interner).0.skip_binders().consequence,
+                        DomainGoal::FromEnv(FromEnv::Trait(_))
+                    ));
+                }
+                _ => panic!("expected an `if` goal"),
+            }
+
+            let goal = GoalData::Quantified(QuantifierKind::ForAll, binders).intern(interner);
+            let solution = db.solve(&goal.into_peeled_goal(interner)).unwrap();
+            assert!(solution.is_unique());
+        });
+    }
+}
@@ -0,0 +1,41 @@
+use super::counting_db::CountingDatabase;
+use chalk_integration::db::ChalkDatabase;
+use chalk_integration::interner::ChalkIr;
+use chalk_solve::caching_db::CachingRustIrDatabase;
+
+#[test]
+fn trait_datum_fetched_once_per_id() {
+    for solver_choice in super::solver_choices() {
+        let (db, goals) = ChalkDatabase::with_program_and_goals(
+            "
+                trait Foo { }
+                trait Bar { }
+                struct S<T> { }
+                struct A { }
+                struct B { }
+                impl Bar for A { }
+                impl Bar for B { }
+                impl<T> Foo for S<T> where T: Bar { }
+                impl<T> Bar for S<T> where T: Foo { }
+            ",
+            solver_choice,
+            &["S<A>: Foo", "S<S<S<B>>>: Foo"],
+        )
+        .unwrap();
+        db.with_program(|program| {
+            let counting_db = CountingDatabase::new(program);
+            let caching_db =
+                CachingRustIrDatabase::<ChalkIr, CountingDatabase<'_>, _>::new(&counting_db);
+
+            let mut solver = solver_choice.into_solver();
+            for goal in &goals {
+                let solution = solver.solve(&caching_db, goal).unwrap();
+                assert!(solution.is_unique());
+            }
+
+            let calls = counting_db.trait_datum_calls.borrow();
+            assert_eq!(calls.len(), 2);
+            assert!(calls.values().all(|&count| count == 1), "{:?}", calls);
+        });
+    }
+}
@@ -0,0 +1,36 @@
+use super::counting_db::CountingDatabase;
+use chalk_integration::db::ChalkDatabase;
+use chalk_integration::interner::ChalkIr;
+use chalk_ir::{Goal, GoalData};
+use chalk_solve::ext::GoalExt;
+
+/// A conjunction containing `CannotProve` is ambiguous at best, so the
+/// solvers don't bother solving the other conjuncts -- even one that
+/// would otherwise fail outright.
+#[test]
+fn cannot_prove_short_circuits_conjunction() {
+    let interner = &ChalkIr;
+    for solver_choice in super::solver_choices() {
+        let db = ChalkDatabase::with("trait Foo { } struct S { }", solver_choice);
+        let goal = db.parse_and_lower_goal("S: Foo").unwrap();
+        let cannot_prove = GoalData::CannotProve.intern(interner);
+        let conjunction = Goal::all(interner, vec![cannot_prove, goal.clone()]);
+
+        db.with_program(|program| {
+            let counting_db = CountingDatabase::new(program);
+            let mut solver = solver_choice.into_solver();
+            let solution = solver.solve(&counting_db, &goal.into_peeled_goal(interner));
+            assert!(solution.is_none());
+            assert!(counting_db.impls_for_trait_calls.get() > 0);
+
+            // `S: Foo` is never looked at, so its impls are never enumerated.
+            let counting_db = CountingDatabase::new(program);
+            let mut solver = solver_choice.into_solver();
+            let solution = solver
+                .solve(&counting_db, &conjunction.into_peeled_goal(interner))
+                .unwrap();
+            assert!(solution.is_ambig());
+            assert_eq!(counting_db.impls_for_trait_calls.get(), 0);
+        });
+    }
+}
@@ -0,0 +1,59 @@
+use chalk_engine::solve::SLGSolver;
+use chalk_integration::db::ChalkDatabase;
+use chalk_integration::SolverChoice;
+use chalk_solve::ext::GoalExt;
+use chalk_solve::{RustIrDatabase, Solver};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+fn hash_of<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn separately_built_environments_share_a_table() {
+    let db = ChalkDatabase::with(
+        "struct Vec<T> { } trait Clone { } impl<T> Clone for Vec<T> where T: Clone { }",
+        SolverChoice::slg_default(),
+    );
+    let goal_text = "forall<T> { if (T: Clone) { Vec<T>: Clone } }";
+    let goal_a = db.parse_and_lower_goal(goal_text).unwrap();
+    let goal_b = db.parse_and_lower_goal(goal_text).unwrap();
+    db.with_program(|_| {
+        let interner = db.interner();
+        let goal_a = goal_a.into_peeled_goal(interner);
+        let goal_b = goal_b.into_peeled_goal(interner);
+
+        // The two goals share no allocations, but compare and hash by content.
+        assert!(!Arc::ptr_eq(
+            goal_a.canonical.value.goal.interned(),
+            goal_b.canonical.value.goal.interned()
+        ));
+        assert!(!goal_a
+            .canonical
+            .value
+            .environment
+            .clauses
+            .is_empty(interner));
+        assert_eq!(goal_a, goal_b);
+        assert_eq!(hash_of(&goal_a), hash_of(&goal_b));
+
+        let mut solver = SLGSolver::new(10, None, None);
+        assert!(solver.has_unique_solution(&db, &goal_a));
+        let tables = solver.dump().tables.len();
+        assert!(solver.has_unique_solution(&db, &goal_b));
+
+        let dump = solver.dump();
+        assert_eq!(dump.tables.len(), tables);
+        let matching: Vec<_> = dump
+            .tables
+            .iter()
+            .filter(|table| table.goal == goal_b)
+            .map(|table| table.index)
+            .collect();
+        assert_eq!(matching.len(), 1);
+    });
+}
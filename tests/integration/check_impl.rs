@@ -0,0 +1,67 @@
+use super::counting_db::CountingDatabase;
+use chalk_integration::db::ChalkDatabase;
+use chalk_integration::query::LoweringDatabase;
+use chalk_integration::tls;
+use chalk_integration::SolverChoice;
+use chalk_solve::coherence::{check_impl_against, CoherenceError};
+
+#[test]
+fn new_impl_checked_only_against_candidates() {
+    for solver_choice in super::solver_choices() {
+        let db = ChalkDatabase::with(
+            "
+                trait Foo { }
+                struct S<T> { }
+                struct A { }
+                struct B { }
+                struct C { }
+                impl Foo for A { }
+                impl Foo for B { }
+                impl Foo for C { }
+                impl<T> Foo for S<T> { }
+                impl<U> Foo for S<U> { }
+            ",
+            solver_choice,
+        );
+        let program = db.program_ir().unwrap();
+        let _guard = tls::set_current_program(&program);
+
+        // Impl ids are handed out in declaration order, so the last one
+        // is the `impl<U> Foo for S<U>` we pretend was just added.
+        let impl_ids: Vec<_> = program.impl_data.keys().copied().collect();
+        let new_impl_id = *impl_ids.last().unwrap();
+
+        let counting_db = CountingDatabase::new(&program);
+        let solver_builder = || solver_choice.into_solver();
+        let result = check_impl_against(&counting_db, &solver_builder, new_impl_id);
+        assert!(matches!(result, Err(CoherenceError::OverlappingImpls(_))));
+
+        // Only the two impls for `S` were ever looked at.
+        let calls = counting_db.impl_datum_calls.borrow();
+        let mut fetched: Vec<_> = calls.keys().copied().collect();
+        fetched.sort();
+        assert_eq!(fetched, impl_ids[3..].to_vec());
+    }
+}
+
+#[test]
+fn new_impl_without_overlap() {
+    let db = ChalkDatabase::with(
+        "
+            trait Foo { }
+            struct S<T> { }
+            struct A { }
+            struct B { }
+            impl Foo for S<A> { }
+            impl Foo for S<B> { }
+        ",
+        SolverChoice::default(),
+    );
+    let program = db.program_ir().unwrap();
+    let _guard = tls::set_current_program(&program);
+
+    let solver_builder = || SolverChoice::default().into_solver();
+    for &impl_id in program.impl_data.keys() {
+        assert!(check_impl_against(&*program, &solver_builder, impl_id).is_ok());
+    }
+}
@@ -0,0 +1,79 @@
+use chalk_integration::db::ChalkDatabase;
+use chalk_integration::interner::ChalkIr;
+use chalk_integration::SolverChoice;
+use chalk_ir::{
+    Binders, Canonical, CanonicalVarKinds, ClausePriority, Constraints, Environment, Goal,
+    GoalData, Goals, InEnvironment, ProgramClauseData, ProgramClauseImplication, UCanonical,
+};
+use chalk_solve::{RustIrDatabase, Solution};
+
+fn solve_in_env(
+    db: &ChalkDatabase,
+    implication: ProgramClauseImplication<ChalkIr>,
+    goal: &Goal<ChalkIr>,
+) -> Vec<Option<Solution<ChalkIr>>> {
+    let interner = db.interner();
+    let clause = ProgramClauseData(Binders::empty(interner, implication)).intern(interner);
+    let environment = Environment::new(interner).add_clauses(interner, Some(clause));
+    let goal = UCanonical {
+        canonical: Canonical {
+            binders: CanonicalVarKinds::empty(interner),
+            value: InEnvironment::new(&environment, goal.clone()),
+        },
+        universes: 1,
+    };
+    vec![
+        SolverChoice::slg_default().into_solver().solve(db, &goal),
+        SolverChoice::recursive_default()
+            .into_solver()
+            .solve(db, &goal),
+    ]
+}
+
+#[test]
+fn with_conditions_reverses_source_order() {
+    let db = ChalkDatabase::with(
+        "struct Foo { } struct Bar { } \
+         trait IsFoo { } impl IsFoo for Foo { } \
+         trait Enumerable { } impl Enumerable for Foo { } impl Enumerable for Bar { } \
+         trait Debug1 { } \
+         forall<> { Bar: Debug1 if Bar: Enumerable, not { Bar: IsFoo } }",
+        SolverChoice::default(),
+    );
+    let lower = |text| db.parse_and_lower_goal(text).unwrap();
+    let goal = lower("Bar: Debug1");
+    let enumerable = lower("Bar: Enumerable");
+    let not_is_foo = lower("not { Bar: IsFoo }");
+
+    db.with_program(|program| {
+        let interner = db.interner();
+        let consequence = match goal.data(interner) {
+            GoalData::DomainGoal(domain_goal) => domain_goal.clone(),
+            _ => unreachable!(),
+        };
+
+        let built = ProgramClauseImplication::with_conditions(
+            interner,
+            consequence.clone(),
+            vec![enumerable.clone(), not_is_foo.clone()],
+        );
+        let reversed = ProgramClauseImplication {
+            consequence,
+            conditions: Goals::from_iter(interner, vec![not_is_foo.clone(), enumerable.clone()]),
+            constraints: Constraints::empty(interner),
+            priority: ClausePriority::High,
+            impl_id: None,
+        };
+        assert_eq!(built, reversed);
+
+        // The lowered custom clause is built the same way.
+        assert_eq!(
+            program.custom_clauses[0].data(interner).0,
+            Binders::empty(interner, built.clone())
+        );
+
+        let solutions = solve_in_env(&db, built, &goal);
+        assert_eq!(solutions, solve_in_env(&db, reversed, &goal));
+        assert!(solutions.iter().all(|s| s.as_ref().unwrap().is_unique()));
+    });
+}
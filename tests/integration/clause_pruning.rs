@@ -0,0 +1,59 @@
+use super::counting_db::CountingDatabase;
+use chalk_integration::db::ChalkDatabase;
+use chalk_integration::interner::ChalkIr;
+use chalk_integration::SolverChoice;
+use chalk_ir::{Canonical, GoalData, InEnvironment, UCanonical};
+use chalk_solve::clauses::program_clauses_for_goal;
+use chalk_solve::ext::GoalExt;
+
+/// The impls of a trait are looked up once when pruning clauses, however
+/// many of the clauses have conditions on the trait.
+#[test]
+fn pruning_looks_up_each_trait_once() {
+    let interner = &ChalkIr;
+    let db = ChalkDatabase::with(
+        "
+            trait Foo { }
+            trait Bar { }
+            struct A { }
+            struct B { }
+            struct C { }
+            struct W<T> { }
+            impl Bar for A { }
+            impl Foo for W<A> where A: Bar { }
+            impl Foo for W<B> where B: Bar { }
+            impl Foo for W<C> where C: Bar { }
+        ",
+        SolverChoice::default(),
+    );
+    let goal = db
+        .parse_and_lower_goal("exists<T> { W<T>: Foo }")
+        .unwrap()
+        .into_peeled_goal(interner);
+    let domain_goal = match goal.canonical.value.goal.data(interner) {
+        GoalData::DomainGoal(domain_goal) => domain_goal.clone(),
+        goal => panic!("expected a domain goal, got {:?}", goal),
+    };
+    let goal = UCanonical {
+        canonical: Canonical {
+            binders: goal.canonical.binders,
+            value: InEnvironment::new(&goal.canonical.value.environment, domain_goal),
+        },
+        universes: goal.universes,
+    };
+
+    db.with_program(|program| {
+        let counting_db = CountingDatabase::new(program);
+        assert!(program_clauses_for_goal(&counting_db, &goal).is_ok());
+
+        // Once for the impls of `Foo`, and once for those of `Bar`.
+        assert_eq!(counting_db.impls_for_trait_calls.get(), 2);
+        let bar_impl = program
+            .impl_data
+            .iter()
+            .find(|(_, datum)| datum.trait_id() == program.trait_ids[&"Bar".into()])
+            .map(|(&id, _)| id)
+            .unwrap();
+        assert_eq!(counting_db.impl_datum_calls.borrow()[&bar_impl], 1);
+    });
+}
@@ -0,0 +1,35 @@
+use chalk_engine::solve::SLGSolver;
+use chalk_integration::db::ChalkDatabase;
+use chalk_integration::SolverChoice;
+use chalk_solve::ext::GoalExt;
+use chalk_solve::solve::test_support::ExpectedSolution;
+use chalk_solve::{RustIrDatabase, Solver};
+
+#[test]
+fn disabling_coinduction_breaks_auto_trait_cycle() {
+    // Proving `List<TypeA>: Send` requires `Ptr<List<TypeA>>: Send` for
+    // the `next` field, which in turn requires `List<TypeA>: Send`; this
+    // cycle is only accepted because `Send` is coinductive.
+    let db = ChalkDatabase::with(
+        "#[auto] trait Send { } \
+         struct TypeA { } \
+         struct Ptr<T> { } \
+         impl<T> Send for Ptr<T> where T: Send { } \
+         struct List<T> { data: T, next: Ptr<List<T>> }",
+        SolverChoice::slg_default(),
+    );
+    let goal = db.parse_and_lower_goal("List<TypeA>: Send").unwrap();
+    db.with_program(|_| {
+        let peeled_goal = goal.into_peeled_goal(db.interner());
+
+        let mut solver = SLGSolver::new(10, None, None);
+        let solution = solver.solve(&db, &peeled_goal).unwrap();
+        assert!(solution.matches(db.interner(), &ExpectedSolution::unique()));
+
+        solver.set_coinduction(false);
+        assert_eq!(solver.solve(&db, &peeled_goal), None);
+
+        solver.set_coinduction(true);
+        assert!(solver.has_unique_solution(&db, &peeled_goal));
+    });
+}
@@ -0,0 +1,45 @@
+use super::counting_db::CountingDatabase;
+use chalk_integration::db::ChalkDatabase;
+use chalk_integration::interner::ChalkIr;
+use chalk_ir::{Ty, TyKind};
+
+/// Answers `is_copy_fast_path` for scalars only.
+fn scalars_are_copy(ty: &Ty<ChalkIr>) -> Option<bool> {
+    match ty.kind(&ChalkIr) {
+        TyKind::Scalar(_) => Some(true),
+        _ => None,
+    }
+}
+
+#[test]
+fn copy_fast_path_skips_impls() {
+    for solver_choice in super::solver_choices() {
+        let (db, goals) = ChalkDatabase::with_program_and_goals(
+            "
+                #[lang(copy)]
+                trait Copy { }
+                struct Foo { }
+                impl Copy for Foo { }
+            ",
+            solver_choice,
+            &["u32: Copy", "Foo: Copy"],
+        )
+        .unwrap();
+        db.with_program(|program| {
+            let fast_path_db = CountingDatabase {
+                copy_fast_path: Some(scalars_are_copy),
+                ..CountingDatabase::new(program)
+            };
+
+            let mut solver = solver_choice.into_solver();
+            let solution = solver.solve(&fast_path_db, &goals[0]).unwrap();
+            assert!(solution.is_unique());
+            assert_eq!(fast_path_db.impls_for_trait_calls.get(), 0);
+
+            // Types the hook doesn't know about still go through the impls.
+            let solution = solver.solve(&fast_path_db, &goals[1]).unwrap();
+            assert!(solution.is_unique());
+            assert!(fast_path_db.impls_for_trait_calls.get() > 0);
+        });
+    }
+}
@@ -0,0 +1,200 @@
+//! A `RustIrDatabase` for tests that need to watch or tweak the queries
+//! the solvers make: it forwards everything to a `Program`, counts some of
+//! the calls, and lets tests override individual answers.
+
+use chalk_integration::interner::ChalkIr;
+use chalk_integration::program::Program;
+use chalk_ir::*;
+use chalk_solve::rust_ir::*;
+use chalk_solve::RustIrDatabase;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Wraps a `Program`, counting how often each trait and impl datum is
+/// requested and how often impls are enumerated.
+#[derive(Debug)]
+pub(crate) struct CountingDatabase<'a> {
+    pub(crate) program: &'a Program,
+    pub(crate) trait_datum_calls: RefCell<HashMap<TraitId<ChalkIr>, usize>>,
+    pub(crate) impl_datum_calls: RefCell<HashMap<ImplId<ChalkIr>, usize>>,
+    pub(crate) impls_for_trait_calls: Cell<usize>,
+
+    /// If set, answers `is_copy_fast_path` in place of the program.
+    pub(crate) copy_fast_path: Option<CopyFastPath>,
+}
+
+pub(crate) type CopyFastPath = fn(&Ty<ChalkIr>) -> Option<bool>;
+
+impl<'a> CountingDatabase<'a> {
+    pub(crate) fn new(program: &'a Program) -> Self {
+        CountingDatabase {
+            program,
+            trait_datum_calls: RefCell::new(HashMap::new()),
+            impl_datum_calls: RefCell::new(HashMap::new()),
+            impls_for_trait_calls: Cell::new(0),
+            copy_fast_path: None,
+        }
+    }
+}
+
+impl UnificationDatabase<ChalkIr> for CountingDatabase<'_> {
+    fn fn_def_variance(&self, fn_def_id: FnDefId<ChalkIr>) -> Variances<ChalkIr> {
+        self.program.fn_def_variance(fn_def_id)
+    }
+
+    fn adt_variance(&self, adt_id: AdtId<ChalkIr>) -> Variances<ChalkIr> {
+        self.program.adt_variance(adt_id)
+    }
+}
+
+impl RustIrDatabase<ChalkIr> for CountingDatabase<'_> {
+    fn is_copy_fast_path(&self, ty: &Ty<ChalkIr>) -> Option<bool> {
+        match self.copy_fast_path {
+            Some(copy_fast_path) => copy_fast_path(ty),
+            None => self.program.is_copy_fast_path(ty),
+        }
+    }
+
+    fn impls_for_trait(
+        &self,
+        trait_id: TraitId<ChalkIr>,
+        parameters: &[GenericArg<ChalkIr>],
+        binders: &CanonicalVarKinds<ChalkIr>,
+    ) -> Vec<ImplId<ChalkIr>> {
+        self.impls_for_trait_calls
+            .set(self.impls_for_trait_calls.get() + 1);
+        self.program.impls_for_trait(trait_id, parameters, binders)
+    }
+
+    fn custom_clauses(&self) -> Vec<ProgramClause<ChalkIr>> {
+        self.program.custom_clauses()
+    }
+
+    fn associated_ty_data(&self, ty: AssocTypeId<ChalkIr>) -> Arc<AssociatedTyDatum<ChalkIr>> {
+        self.program.associated_ty_data(ty)
+    }
+
+    fn trait_datum(&self, trait_id: TraitId<ChalkIr>) -> Arc<TraitDatum<ChalkIr>> {
+        *self
+            .trait_datum_calls
+            .borrow_mut()
+            .entry(trait_id)
+            .or_insert(0) += 1;
+        self.program.trait_datum(trait_id)
+    }
+
+    fn adt_datum(&self, adt_id: AdtId<ChalkIr>) -> Arc<AdtDatum<ChalkIr>> {
+        self.program.adt_datum(adt_id)
+    }
+
+    fn generator_datum(&self, generator_id: GeneratorId<ChalkIr>) -> Arc<GeneratorDatum<ChalkIr>> {
+        self.program.generator_datum(generator_id)
+    }
+
+    fn generator_witness_datum(
+        &self,
+        generator_id: GeneratorId<ChalkIr>,
+    ) -> Arc<GeneratorWitnessDatum<ChalkIr>> {
+        self.program.generator_witness_datum(generator_id)
+    }
+
+    fn adt_repr(&self, id: AdtId<ChalkIr>) -> Arc<AdtRepr<ChalkIr>> {
+        self.program.adt_repr(id)
+    }
+
+    fn fn_def_datum(&self, fn_def_id: FnDefId<ChalkIr>) -> Arc<FnDefDatum<ChalkIr>> {
+        self.program.fn_def_datum(fn_def_id)
+    }
+
+    fn impl_datum(&self, impl_id: ImplId<ChalkIr>) -> Arc<ImplDatum<ChalkIr>> {
+        *self
+            .impl_datum_calls
+            .borrow_mut()
+            .entry(impl_id)
+            .or_insert(0) += 1;
+        self.program.impl_datum(impl_id)
+    }
+
+    fn associated_ty_value(
+        &self,
+        id: AssociatedTyValueId<ChalkIr>,
+    ) -> Arc<AssociatedTyValue<ChalkIr>> {
+        self.program.associated_ty_value(id)
+    }
+
+    fn opaque_ty_data(&self, id: OpaqueTyId<ChalkIr>) -> Arc<OpaqueTyDatum<ChalkIr>> {
+        self.program.opaque_ty_data(id)
+    }
+
+    fn hidden_opaque_type(&self, id: OpaqueTyId<ChalkIr>) -> Ty<ChalkIr> {
+        self.program.hidden_opaque_type(id)
+    }
+
+    fn local_impls_to_coherence_check(&self, trait_id: TraitId<ChalkIr>) -> Vec<ImplId<ChalkIr>> {
+        self.program.local_impls_to_coherence_check(trait_id)
+    }
+
+    fn impl_provided_for(&self, auto_trait_id: TraitId<ChalkIr>, ty: &TyKind<ChalkIr>) -> bool {
+        self.program.impl_provided_for(auto_trait_id, ty)
+    }
+
+    fn well_known_trait_id(&self, well_known_trait: WellKnownTrait) -> Option<TraitId<ChalkIr>> {
+        self.program.well_known_trait_id(well_known_trait)
+    }
+
+    fn program_clauses_for_env(
+        &self,
+        environment: &Environment<ChalkIr>,
+    ) -> ProgramClauses<ChalkIr> {
+        self.program.program_clauses_for_env(environment)
+    }
+
+    fn interner(&self) -> &ChalkIr {
+        self.program.interner()
+    }
+
+    fn is_object_safe(&self, trait_id: TraitId<ChalkIr>) -> bool {
+        self.program.is_object_safe(trait_id)
+    }
+
+    fn closure_kind(
+        &self,
+        closure_id: ClosureId<ChalkIr>,
+        substs: &Substitution<ChalkIr>,
+    ) -> ClosureKind {
+        self.program.closure_kind(closure_id, substs)
+    }
+
+    fn closure_inputs_and_output(
+        &self,
+        closure_id: ClosureId<ChalkIr>,
+        substs: &Substitution<ChalkIr>,
+    ) -> Binders<FnDefInputsAndOutputDatum<ChalkIr>> {
+        self.program.closure_inputs_and_output(closure_id, substs)
+    }
+
+    fn closure_upvars(
+        &self,
+        closure_id: ClosureId<ChalkIr>,
+        substs: &Substitution<ChalkIr>,
+    ) -> Binders<Ty<ChalkIr>> {
+        self.program.closure_upvars(closure_id, substs)
+    }
+
+    fn closure_fn_substitution(
+        &self,
+        closure_id: ClosureId<ChalkIr>,
+        substs: &Substitution<ChalkIr>,
+    ) -> Substitution<ChalkIr> {
+        self.program.closure_fn_substitution(closure_id, substs)
+    }
+
+    fn unification_database(&self) -> &dyn UnificationDatabase<ChalkIr> {
+        self
+    }
+
+    fn discriminant_type(&self, ty: Ty<ChalkIr>) -> Ty<ChalkIr> {
+        self.program.discriminant_type(ty)
+    }
+}
@@ -0,0 +1,74 @@
+use chalk_engine::solve::SLGSolver;
+use chalk_integration::db::ChalkDatabase;
+use chalk_integration::SolverChoice;
+use chalk_solve::ext::EnvironmentExt;
+use chalk_solve::Solver;
+
+#[test]
+fn elaborated_environment() {
+    let (db, goals) = ChalkDatabase::with_program_and_goals(
+        "
+            trait A { }
+            trait B where Self: A { }
+            trait C where Self: B { }
+            trait D { }
+            trait E<T> where T: D { }
+            struct Foo { }
+        ",
+        SolverChoice::slg_default(),
+        &[
+            "forall<T> { if (T: C) { T: A } }",
+            "forall<T> { if (T: C) { T: D } }",
+            "forall<T> { if (Foo: E<T>) { T: D } }",
+            "forall<T> { if (T: A) { T: C } }",
+        ],
+    )
+    .unwrap();
+    db.with_program(|_| {
+        for goal in &goals {
+            let mut elaborated_goal = goal.clone();
+            elaborated_goal.canonical.value.environment =
+                goal.canonical.value.environment.elaborate(&db);
+
+            let mut solver = SLGSolver::new(10, None, None);
+            let mut elaborated_solver = SLGSolver::new(10, None, None);
+            assert_eq!(
+                solver.solve(&db, goal),
+                elaborated_solver.solve(&db, &elaborated_goal)
+            );
+            assert!(elaborated_solver.dump().tables.len() <= solver.dump().tables.len());
+        }
+
+        // `FromEnv(T: A)` is found directly rather than through
+        // `FromEnv(T: B)` and `FromEnv(T: C)`.
+        let mut elaborated_goal = goals[0].clone();
+        elaborated_goal.canonical.value.environment =
+            goals[0].canonical.value.environment.elaborate(&db);
+        let mut solver = SLGSolver::new(10, None, None);
+        let mut elaborated_solver = SLGSolver::new(10, None, None);
+        solver.solve(&db, &goals[0]);
+        elaborated_solver.solve(&db, &elaborated_goal);
+        assert_eq!(solver.dump().tables.len(), 4);
+        assert_eq!(elaborated_solver.dump().tables.len(), 2);
+    });
+}
+
+#[test]
+fn elaborate_terminates_on_growing_bounds() {
+    let (db, goals) = ChalkDatabase::with_program_and_goals(
+        "
+            trait G where Vec<Self>: G { }
+            struct Vec<T> { }
+        ",
+        SolverChoice::slg_default(),
+        &["forall<T> { if (T: G) { Vec<Vec<T>>: G } }"],
+    )
+    .unwrap();
+    db.with_program(|_| {
+        let mut elaborated_goal = goals[0].clone();
+        elaborated_goal.canonical.value.environment =
+            goals[0].canonical.value.environment.elaborate(&db);
+        let mut solver = SLGSolver::new(10, None, None);
+        assert!(solver.solve(&db, &elaborated_goal).is_some());
+    });
+}
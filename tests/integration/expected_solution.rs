@@ -0,0 +1,126 @@
+use chalk_integration::db::ChalkDatabase;
+use chalk_ir::{
+    Constraint, Environment, InEnvironment, LifetimeData, PlaceholderIndex, Substitution, TyKind,
+    UniverseIndex,
+};
+use chalk_solve::ext::GoalExt;
+use chalk_solve::solve::test_support::ExpectedSolution;
+use chalk_solve::RustIrDatabase;
+
+const PROGRAM: &str = "
+    struct Foo { }
+    struct Bar { }
+    struct Vec<T> { }
+    struct Ref<'a> { }
+    trait Clone { }
+    impl Clone for Foo { }
+    impl Clone for Bar { }
+    impl<T> Clone for Vec<T> where T: Clone { }
+";
+
+#[test]
+fn expected_solution_unique() {
+    for solver_choice in super::solver_choices() {
+        let db = ChalkDatabase::with(PROGRAM, solver_choice);
+        let goal = db
+            .parse_and_lower_goal("exists<T> { T = Foo, T: Clone }")
+            .unwrap();
+        db.with_program(|program| {
+            let interner = db.interner();
+            let adt = |name: &str| {
+                TyKind::Adt(program.adt_ids[&name.into()], Substitution::empty(interner))
+                    .intern(interner)
+            };
+            let solution = db.solve(&goal.into_peeled_goal(interner)).unwrap();
+            assert!(solution.matches(interner, &ExpectedSolution::unique()));
+            assert!(solution.matches(
+                interner,
+                &ExpectedSolution::unique().with_subst(Substitution::from1(interner, adt("Foo")))
+            ));
+            assert!(!solution.matches(
+                interner,
+                &ExpectedSolution::unique().with_subst(Substitution::from1(interner, adt("Bar")))
+            ));
+            assert!(solution.matches(
+                interner,
+                &ExpectedSolution::unique().with_constraints(vec![])
+            ));
+            assert!(!solution.matches(interner, &ExpectedSolution::ambiguous()));
+        });
+    }
+}
+
+#[test]
+fn expected_solution_ambiguous() {
+    for solver_choice in super::solver_choices() {
+        let db = ChalkDatabase::with(PROGRAM, solver_choice);
+        let goal = db
+            .parse_and_lower_goal("exists<T> { Vec<T>: Clone }")
+            .unwrap();
+        db.with_program(|program| {
+            let interner = db.interner();
+            let foo = TyKind::Adt(
+                program.adt_ids[&"Foo".into()],
+                Substitution::empty(interner),
+            )
+            .intern(interner);
+            let solution = db.solve(&goal.into_peeled_goal(interner)).unwrap();
+            assert!(solution.matches(interner, &ExpectedSolution::ambiguous()));
+            assert!(solution.matches(interner, &ExpectedSolution::unknown()));
+            assert!(!solution.matches(interner, &ExpectedSolution::definite()));
+            assert!(!solution.matches(
+                interner,
+                &ExpectedSolution::ambiguous().with_subst(Substitution::from1(interner, foo))
+            ));
+            assert!(!solution.matches(interner, &ExpectedSolution::unique()));
+        });
+    }
+}
+
+#[test]
+fn expected_solution_constraints() {
+    for solver_choice in super::solver_choices() {
+        let db = ChalkDatabase::with(PROGRAM, solver_choice);
+        let goal = db
+            .parse_and_lower_goal("forall<'a, 'b> { Ref<'a> = Ref<'b> }")
+            .unwrap();
+        db.with_program(|_| {
+            let interner = db.interner();
+            let placeholder = |idx| {
+                LifetimeData::Placeholder(PlaceholderIndex {
+                    ui: UniverseIndex { counter: 1 },
+                    idx,
+                })
+                .intern(interner)
+            };
+            let outlives = |a, b| {
+                InEnvironment::new(
+                    &Environment::new(interner),
+                    Constraint::LifetimeOutlives(placeholder(a), placeholder(b)),
+                )
+            };
+            let solution = db.solve(&goal.into_peeled_goal(interner)).unwrap();
+
+            // The constraints may come in any order.
+            for constraints in [
+                vec![outlives(0, 1), outlives(1, 0)],
+                vec![outlives(1, 0), outlives(0, 1)],
+            ] {
+                assert!(solution.matches(
+                    interner,
+                    &ExpectedSolution::unique()
+                        .with_subst(Substitution::empty(interner))
+                        .with_constraints(constraints)
+                ));
+            }
+            assert!(!solution.matches(
+                interner,
+                &ExpectedSolution::unique().with_constraints(vec![outlives(0, 1)])
+            ));
+            assert!(!solution.matches(
+                interner,
+                &ExpectedSolution::unique().with_constraints(vec![outlives(0, 1), outlives(0, 1)])
+            ));
+        });
+    }
+}
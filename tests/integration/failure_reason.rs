@@ -0,0 +1,64 @@
+use chalk_integration::db::ChalkDatabase;
+use chalk_integration::SolverChoice;
+use chalk_solve::ext::GoalExt;
+use chalk_solve::RustIrDatabase;
+
+const PROGRAM: &str = "struct Foo { } \
+                       struct Vec<T> { } \
+                       trait Clone { } \
+                       impl Clone for Foo { } \
+                       impl<T> Clone for Vec<T> where T: Clone { }";
+
+#[test]
+fn names_failed_subgoal() {
+    let (db, goals) = ChalkDatabase::with_program_and_goals(
+        PROGRAM,
+        SolverChoice::slg_default(),
+        &["Vec<u32>: Clone", "Vec<Vec<u32>>: Clone", "u32: Clone"],
+    )
+    .unwrap();
+    let missing = db
+        .parse_and_lower_goal("u32: Clone")
+        .unwrap()
+        .into_peeled_goal(db.interner());
+
+    db.with_program(|_| {
+        // The second goal's subgoals are already tabled by the time it is
+        // solved; the reason must not depend on that.
+        for goal in &goals[..2] {
+            let (solution, reason) = db.solve_with_explanation(goal);
+            assert_eq!(solution, None);
+            assert_eq!(reason.unwrap().goal, missing);
+        }
+
+        // A goal that fails outright has no subgoal to blame.
+        assert_eq!(db.solve_with_explanation(&goals[2]), (None, None));
+    });
+}
+
+#[test]
+fn no_reason_for_solvable_goal() {
+    for solver_choice in super::solver_choices() {
+        let (db, goals) =
+            ChalkDatabase::with_program_and_goals(PROGRAM, solver_choice, &["Vec<Foo>: Clone"])
+                .unwrap();
+        db.with_program(|_| {
+            let (solution, reason) = db.solve_with_explanation(&goals[0]);
+            assert!(solution.unwrap().is_unique());
+            assert_eq!(reason, None);
+        });
+    }
+}
+
+#[test]
+fn recursive_solver_gives_no_reason() {
+    let (db, goals) = ChalkDatabase::with_program_and_goals(
+        PROGRAM,
+        SolverChoice::recursive_default(),
+        &["Vec<u32>: Clone"],
+    )
+    .unwrap();
+    db.with_program(|_| {
+        assert_eq!(db.solve_with_explanation(&goals[0]), (None, None));
+    });
+}
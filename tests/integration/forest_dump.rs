@@ -0,0 +1,168 @@
+use chalk_engine::solve::SLGSolver;
+use chalk_integration::db::ChalkDatabase;
+use chalk_integration::SolverChoice;
+use chalk_ir::{Scalar, Substitution, TyKind, UintTy};
+use chalk_solve::ext::GoalExt;
+use chalk_solve::solve::test_support::ExpectedSolution;
+use chalk_solve::{RustIrDatabase, Solver};
+
+#[test]
+fn dump_lists_tables() {
+    let db = ChalkDatabase::with(
+        "struct Foo { } struct Vec<T> { } trait Clone { } \
+         impl Clone for Foo { } impl<T> Clone for Vec<T> where T: Clone { }",
+        SolverChoice::slg_default(),
+    );
+    let goal = db.parse_and_lower_goal("Vec<Foo>: Clone").unwrap();
+    db.with_program(|_| {
+        let mut solver = SLGSolver::new(10, None, None);
+        assert!(solver.dump().tables.is_empty());
+
+        let peeled_goal = goal.into_peeled_goal(db.interner());
+        assert!(solver.has_unique_solution(&db, &peeled_goal));
+
+        // Tables for `Vec<Foo>: Clone`, the `FromEnv` goal tried for it, the
+        // impl's `Foo: Clone` where clause (wrapped in an empty `ForAll<>`),
+        // that goal itself and its `FromEnv` goal. Only the `FromEnv` goals
+        // have no answers.
+        let dump = solver.dump();
+        let tables: Vec<_> = dump
+            .tables
+            .iter()
+            .map(|table| {
+                (
+                    format!("{:?}", table.goal.canonical.value.goal),
+                    !table.answers.is_empty(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            tables,
+            [
+                ("Implemented(Vec<Foo>: Clone)".to_string(), true),
+                ("FromEnv(Vec<Foo>: Clone)".to_string(), false),
+                ("ForAll<> { Implemented(Foo: Clone) }".to_string(), true),
+                ("Implemented(Foo: Clone)".to_string(), true),
+                ("FromEnv(Foo: Clone)".to_string(), false),
+            ]
+        );
+        assert!(dump.tables.iter().all(|table| table.strands == 0));
+        assert_eq!(solver.dump_tables(), Some(dump.to_string()));
+    });
+}
+
+#[test]
+fn max_tables_halts_unbounded_search() {
+    // Every `S<..>: Foo` goal requires a goal on a bigger type, so without
+    // a limit the solver keeps creating tables until the types reach
+    // `max_size`.
+    let db = ChalkDatabase::with(
+        "struct S<T> { } trait Foo { } impl<T> Foo for S<T> where S<S<T>>: Foo { }",
+        SolverChoice::slg_default(),
+    );
+    let goal = db.parse_and_lower_goal("S<u32>: Foo").unwrap();
+    db.with_program(|_| {
+        let mut solver = SLGSolver::new(1000, None, Some(20));
+        let peeled_goal = goal.into_peeled_goal(db.interner());
+        let solution = solver.solve(&db, &peeled_goal).unwrap();
+        assert!(solution.matches(db.interner(), &ExpectedSolution::unknown()));
+        assert_eq!(solver.dump().tables.len(), 20);
+    });
+}
+
+#[test]
+fn max_tables_applies_to_each_goal() {
+    // `S<..S<u32>..>: Bar` needs a few tables for each level of nesting.
+    // Ten levels fit within the limit, twenty don't. Hitting the limit on
+    // the deeper goal, even twice, must not leave floundered tables behind
+    // for the goals it passed through on the way.
+    let nested = |depth| format!("{}u32{}: Bar", "S<".repeat(depth), ">".repeat(depth));
+    let db = ChalkDatabase::with(
+        "struct S<T> { } trait Bar { } \
+         impl<T> Bar for S<T> where T: Bar { } \
+         impl Bar for u32 { }",
+        SolverChoice::slg_default(),
+    );
+    let deep = db.parse_and_lower_goal(&nested(20)).unwrap();
+    let shallow = db.parse_and_lower_goal(&nested(10)).unwrap();
+    db.with_program(|_| {
+        let interner = db.interner();
+        let deep = deep.into_peeled_goal(interner);
+        let shallow = shallow.into_peeled_goal(interner);
+        let mut solver = SLGSolver::new(1000, None, Some(40));
+
+        for _ in 0..2 {
+            let solution = solver.solve(&db, &deep).unwrap();
+            assert!(solution.matches(interner, &ExpectedSolution::unknown()));
+        }
+        let solution = solver.solve(&db, &shallow).unwrap();
+        assert!(solution.matches(interner, &ExpectedSolution::unique()));
+    });
+}
+
+#[test]
+fn inapplicable_impls_create_no_tables() {
+    // The impls for `A`, `B` and `C` require bounds that no impl of
+    // `Scalar` can satisfy, so their clauses are dropped before solving
+    // and the solver never creates tables for those bounds.
+    let db = ChalkDatabase::with(
+        "struct A { } struct B { } struct C { } \
+         trait Scalar { } impl Scalar for u32 { } impl Scalar for i32 { } \
+         trait Foo { } \
+         impl Foo for A where A: Scalar { } \
+         impl Foo for B where B: Scalar { } \
+         impl Foo for C where C: Scalar { } \
+         impl Foo for u32 where u32: Scalar { }",
+        SolverChoice::slg_default(),
+    );
+    let goal = db.parse_and_lower_goal("exists<T> { T: Foo }").unwrap();
+    db.with_program(|_| {
+        let mut solver = SLGSolver::new(10, None, None);
+        let peeled_goal = goal.into_peeled_goal(db.interner());
+        let solution = solver.solve(&db, &peeled_goal).unwrap();
+        let interner = db.interner();
+        let u32_ty = TyKind::Scalar(Scalar::Uint(UintTy::U32)).intern(interner);
+        assert!(solution.matches(
+            interner,
+            &ExpectedSolution::unique().with_subst(Substitution::from1(interner, u32_ty))
+        ));
+
+        // Tables for `?T: Foo` and the `u32: Scalar` subgoal from the one
+        // applicable impl, plus the `FromEnv` and `ForAll<>` goals.
+        let dump = solver.dump();
+        assert_eq!(dump.tables.len(), 5);
+        assert!(!dump.to_string().contains("(A: Scalar)"));
+    });
+}
+
+#[test]
+fn subsumed_strands_are_dropped() {
+    // The first clause gives the answer `?0 := B` for any `?1`; the second
+    // can only produce `?0 := B, ?1 := A`, which that answer subsumes. Its
+    // strand is dropped rather than pursued, which also means it no longer
+    // makes the solution ambiguous.
+    let db = ChalkDatabase::with(
+        "struct A { } struct B { } trait Foo<T> { } \
+         forall<T> { B: Foo<T> } \
+         forall<T> { B: Foo<T> if T = A }",
+        SolverChoice::slg_default(),
+    );
+    let goal = db
+        .parse_and_lower_goal("exists<T, U> { T: Foo<U> }")
+        .unwrap();
+    db.with_program(|_| {
+        let mut solver = SLGSolver::new(10, None, None);
+        let peeled_goal = goal.into_peeled_goal(db.interner());
+        let solution = solver.solve(&db, &peeled_goal).unwrap();
+        assert!(solution.matches(db.interner(), &ExpectedSolution::unique()));
+
+        let dump = solver.dump();
+        let table = dump
+            .tables
+            .iter()
+            .find(|table| table.goal == peeled_goal)
+            .unwrap();
+        assert_eq!(table.answers.len(), 1);
+        assert_eq!(table.strands, 0);
+    });
+}
@@ -0,0 +1,43 @@
+use chalk_integration::db::ChalkDatabase;
+use chalk_integration::SolverChoice;
+use chalk_ir::{Substitution, TyKind};
+use chalk_recursive::RecursiveSolver;
+use chalk_solve::solve::test_support::ExpectedSolution;
+use chalk_solve::{RustIrDatabase, Solver};
+
+#[test]
+fn fulfill_round_limit_yields_ambiguity() {
+    // `T: Foo` is ambiguous until `T: Bar` has been solved, which only
+    // happens after it, so proving `T: Foo` takes a second round.
+    let (db, goals) = ChalkDatabase::with_program_and_goals(
+        "trait Foo { } \
+         trait Bar { } \
+         struct A { } \
+         struct B { } \
+         impl Foo for A { } \
+         impl Foo for B { } \
+         impl Bar for A { }",
+        SolverChoice::recursive_default(),
+        &["exists<T> { T: Bar, T: Foo }"],
+    )
+    .unwrap();
+    let peeled_goal = &goals[0];
+    db.with_program(|program| {
+        let interner = db.interner();
+        let a = TyKind::Adt(program.adt_ids[&"A".into()], Substitution::empty(interner))
+            .intern(interner);
+        let subst = Substitution::from1(interner, a);
+
+        let mut solver = RecursiveSolver::new(10, 30, None);
+        let solution = solver.solve(&db, peeled_goal).unwrap();
+        assert!(solution.matches(
+            interner,
+            &ExpectedSolution::unique().with_subst(subst.clone())
+        ));
+
+        let mut solver = RecursiveSolver::new(10, 30, None);
+        solver.set_max_fulfill_rounds(1);
+        let solution = solver.solve(&db, peeled_goal).unwrap();
+        assert!(solution.matches(interner, &ExpectedSolution::definite().with_subst(subst)));
+    });
+}
@@ -0,0 +1,55 @@
+use chalk_integration::db::ChalkDatabase;
+use chalk_integration::query::LoweringDatabase;
+use chalk_ir::*;
+use chalk_solve::ext::GoalExt;
+use chalk_solve::rust_ir::*;
+use chalk_solve::solve::test_support::ExpectedSolution;
+use chalk_solve::RustIrDatabase;
+
+#[test]
+fn solve_with_hypothetical_impl() {
+    for solver_choice in super::solver_choices() {
+        let db = ChalkDatabase::with(
+            "struct Foo { } struct Bar { } trait Clone { } impl Clone for Foo { }",
+            solver_choice,
+        );
+        let goal = db.parse_and_lower_goal("Bar: Clone").unwrap();
+        db.with_program(|program| {
+            let interner = db.interner();
+            let bar = TyKind::Adt(
+                program.adt_ids[&"Bar".into()],
+                Substitution::empty(interner),
+            )
+            .intern(interner);
+
+            // `impl Clone for Bar { }`
+            let impl_datum = ImplDatum {
+                polarity: Polarity::Positive,
+                binders: Binders::empty(
+                    interner,
+                    ImplDatumBound {
+                        trait_ref: TraitRef {
+                            trait_id: program.trait_ids[&"Clone".into()],
+                            substitution: Substitution::from1(interner, bar),
+                        },
+                        where_clauses: vec![],
+                    },
+                ),
+                impl_type: ImplType::Local,
+                associated_ty_value_ids: vec![],
+            };
+
+            let peeled_goal = goal.into_peeled_goal(interner);
+            let solver = db.solver();
+            let mut solver = solver.lock().unwrap();
+            assert_eq!(solver.solve(&db, &peeled_goal), None);
+            let solution = solver
+                .solve_with_impl(&db, &peeled_goal, &impl_datum)
+                .unwrap();
+            assert!(solution.matches(
+                interner,
+                &ExpectedSolution::unique().with_subst(Substitution::empty(interner))
+            ));
+        });
+    }
+}
@@ -0,0 +1,43 @@
+use chalk_integration::db::ChalkDatabase;
+use chalk_integration::query::LoweringDatabase;
+use chalk_ir::*;
+use chalk_solve::solve::implemented_traits;
+use chalk_solve::RustIrDatabase;
+
+#[test]
+fn u32_implemented_traits() {
+    for solver_choice in super::solver_choices() {
+        let db = ChalkDatabase::with(
+            "
+                trait Clone { }
+                trait Copy where Self: Clone { }
+                impl Clone for u32 { }
+                impl Copy for u32 { }
+                trait Marker { }
+                trait Into<T> { }
+                struct Foo { }
+                impl Into<Foo> for u32 { }
+            ",
+            solver_choice,
+        );
+        db.with_program(|program| {
+            let interner = db.interner();
+            let u32_ty = TyKind::Scalar(Scalar::Uint(UintTy::U32)).intern(interner);
+            let trait_id = |name: &str| program.trait_ids[&name.into()];
+            let traits = ["Clone", "Copy", "Marker", "Into"]
+                .iter()
+                .map(|n| trait_id(n));
+
+            let solver = db.solver();
+            let mut solver = solver.lock().unwrap();
+            assert_eq!(
+                implemented_traits(&mut **solver, &db, &u32_ty, traits.clone(), usize::MAX),
+                vec![trait_id("Clone"), trait_id("Copy"), trait_id("Into")]
+            );
+            assert_eq!(
+                implemented_traits(&mut **solver, &db, &u32_ty, traits, 1),
+                vec![trait_id("Clone")]
+            );
+        });
+    }
+}
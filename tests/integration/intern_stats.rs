@@ -0,0 +1,32 @@
+use chalk_integration::db::ChalkDatabase;
+use chalk_integration::interner::ChalkIr;
+use chalk_integration::query::LoweringDatabase;
+use chalk_integration::SolverChoice;
+
+#[test]
+fn counters_grow_with_repeated_types() {
+    let fields: String = (0..50).map(|i| format!("field{}: Vec<Foo>, ", i)).collect();
+    let db = ChalkDatabase::with(
+        &format!(
+            "
+                struct Foo {{ }}
+                struct Vec<T> {{ }}
+                struct Many {{ {} }}
+                trait Bar {{ }}
+                impl Bar for Vec<Foo> {{ }}
+            ",
+            fields
+        ),
+        SolverChoice::default(),
+    );
+
+    let before = ChalkIr.intern_stats();
+    db.program_ir().unwrap();
+    let after = ChalkIr.intern_stats();
+
+    // Each `Vec<Foo>` field interns its own copy of both types.
+    assert!(after.tys - before.tys >= 100, "{:?} -> {:?}", before, after);
+
+    db.parse_and_lower_goal("Vec<Foo>: Bar").unwrap();
+    assert!(ChalkIr.intern_stats().goals > after.goals);
+}
@@ -0,0 +1,51 @@
+use chalk_integration::db::ChalkDatabase;
+use chalk_integration::query::LoweringDatabase;
+use chalk_integration::SolverChoice;
+
+#[test]
+fn reports_independent_errors() {
+    let db = ChalkDatabase::with(
+        "trait Foo { } trait Clone { } \
+         struct S { } impl Foo for S { } impl Foo for S { } \
+         struct Bounded<T> where T: Clone { } \
+         struct NotClone { } \
+         struct Holder { field: Bounded<NotClone> }",
+        SolverChoice::default(),
+    );
+    let errors: Vec<String> = db
+        .lower_and_check()
+        .unwrap_err()
+        .iter()
+        .map(|error| error.to_string())
+        .collect();
+    assert_eq!(
+        errors,
+        vec![
+            "overlapping impls of trait `Foo`",
+            "type declaration `Holder` does not meet well-formedness requirements",
+        ]
+    );
+
+    // `checked_program` stops at the first of these.
+    assert_eq!(
+        db.checked_program().unwrap_err().to_string(),
+        "overlapping impls of trait `Foo`"
+    );
+}
+
+#[test]
+fn lowering_error_is_reported_alone() {
+    let db = ChalkDatabase::with("struct S { field: T }", SolverChoice::default());
+    let errors = db.lower_and_check().unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].to_string(), "invalid parameter name `T`");
+}
+
+#[test]
+fn well_formed_program_has_no_errors() {
+    let db = ChalkDatabase::with(
+        "trait Foo { } struct S { } impl Foo for S { }",
+        SolverChoice::default(),
+    );
+    assert!(db.lower_and_check().is_ok());
+}
@@ -0,0 +1,39 @@
+use chalk_integration::db::ChalkDatabase;
+use chalk_integration::SolverChoice;
+use chalk_ir::cast::Cast;
+use chalk_ir::*;
+use chalk_solve::RustIrDatabase;
+
+#[test]
+fn map_env_adds_clause() {
+    let db = ChalkDatabase::with("trait Clone { } struct Foo { }", SolverChoice::default());
+    let goal = db.parse_and_lower_goal("Foo: Clone").unwrap();
+    db.with_program(|_| {
+        let interner = db.interner();
+        let trait_ref = match goal.data(interner) {
+            GoalData::DomainGoal(DomainGoal::Holds(WhereClause::Implemented(trait_ref))) => {
+                trait_ref.clone()
+            }
+            _ => panic!("expected `Foo: Clone`, got {:?}", goal),
+        };
+        let to_canonical = |value: InEnvironment<Goal<_>>| UCanonical {
+            canonical: Canonical {
+                binders: CanonicalVarKinds::empty(interner),
+                value,
+            },
+            universes: 1,
+        };
+
+        let in_env = InEnvironment::new(&Environment::new(interner), goal.clone());
+        assert!(db.solve(&to_canonical(in_env.clone())).is_none());
+
+        // Assuming `Foo: Clone` in the environment makes the goal provable.
+        let in_env = in_env.map_env(|environment| {
+            environment.add_clauses(interner, Some(trait_ref.cast::<ProgramClause<_>>(interner)))
+        });
+        assert_eq!(in_env.environment.clauses.len(interner), 1);
+        assert_eq!(in_env.goal, goal);
+        let solution = db.solve(&to_canonical(in_env)).unwrap();
+        assert!(solution.is_unique());
+    });
+}
@@ -1 +1,45 @@
+mod associated_ty_info;
+mod assuming;
+mod caching_db;
+mod cannot_prove;
+mod canonical_hash;
+mod check_impl;
+mod clause_conditions;
+mod clause_pruning;
+mod coinduction;
+mod copy_fast_path;
+mod counting_db;
+mod elaborate_env;
+mod expected_solution;
+mod failure_reason;
+mod forest_dump;
+mod fulfill_rounds;
+mod hypothetical_impl;
+mod implemented_traits;
+mod intern_stats;
+mod lower_and_check;
+mod map_env;
+mod normalization_memo;
 mod panic;
+mod program_and_goals;
+mod quantifier_depth;
+mod recursive_cache;
+mod repl;
+mod selected_impl;
+mod solve_in_place;
+mod solver_agreement;
+mod tls_guard;
+mod trait_ref_debug;
+mod truncation;
+mod well_formed_goal;
+mod whole_program;
+
+use chalk_integration::SolverChoice;
+
+/// The solvers that tests which should hold for every solver run against.
+fn solver_choices() -> [SolverChoice; 2] {
+    [
+        SolverChoice::slg_default(),
+        SolverChoice::recursive_default(),
+    ]
+}
@@ -0,0 +1,47 @@
+use super::counting_db::CountingDatabase;
+use chalk_integration::db::ChalkDatabase;
+use chalk_integration::SolverChoice;
+use chalk_recursive::RecursiveSolver;
+use chalk_solve::Solver;
+
+/// Solves each goal with an uncached recursive solver and returns how
+/// often trait data was fetched while doing so.
+fn trait_datum_calls(goals: &[&str]) -> Vec<usize> {
+    let (db, goals) = ChalkDatabase::with_program_and_goals(
+        "
+            trait Iterator { type Item; }
+            struct Counter { }
+            impl Iterator for Counter { type Item = u32; }
+        ",
+        SolverChoice::recursive_default(),
+        goals,
+    )
+    .unwrap();
+    db.with_program(|program| {
+        goals
+            .iter()
+            .map(|goal| {
+                let counting_db = CountingDatabase::new(program);
+                let mut solver = RecursiveSolver::new(30, 10, None);
+                let solution = solver.solve(&counting_db, goal).unwrap();
+                assert!(solution.is_unique());
+                let calls = counting_db.trait_datum_calls.borrow();
+                calls.values().sum()
+            })
+            .collect()
+    })
+}
+
+#[test]
+fn repeated_projection_is_normalized_once() {
+    let calls = trait_datum_calls(&[
+        "exists<A> { A = <Counter as Iterator>::Item }",
+        "exists<A, B, C, D> { \
+            A = <Counter as Iterator>::Item, \
+            B = <Counter as Iterator>::Item, \
+            C = <Counter as Iterator>::Item, \
+            D = <Counter as Iterator>::Item \
+        }",
+    ]);
+    assert_eq!(calls[0], calls[1]);
+}
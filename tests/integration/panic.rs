@@ -68,7 +68,7 @@ impl RustIrDatabase<ChalkIr> for MockDatabase {
         Arc::new(TraitDatum {
             id,
             binders: Binders::new(
-                VariableKinds::empty(&ChalkIr),
+                VariableKinds::from1(&ChalkIr, VariableKind::Ty(TyVariableKind::General)),
                 TraitDatumBound {
                     where_clauses: vec![],
                 },
@@ -80,9 +80,11 @@ impl RustIrDatabase<ChalkIr> for MockDatabase {
                 fundamental: false,
                 non_enumerable: false,
                 coinductive: false,
+                alias: false,
             },
             associated_ty_ids: vec![],
             well_known: None,
+            provided_clauses: vec![],
         })
     }
 
@@ -0,0 +1,60 @@
+use chalk_integration::db::ChalkDatabase;
+use chalk_integration::query::LoweringDatabase;
+use chalk_integration::SolverChoice;
+use chalk_solve::ext::GoalExt;
+use chalk_solve::RustIrDatabase;
+
+const PROGRAM: &str = "struct Foo { } \
+                       struct Vec<T> { } \
+                       trait Clone { } \
+                       impl Clone for Foo { } \
+                       impl<T> Clone for Vec<T> where T: Clone { }";
+
+#[test]
+fn same_as_lowering_manually() {
+    let goal_texts = ["Vec<Foo>: Clone", "exists<T> { Vec<T>: Clone }"];
+    let (db, goals) =
+        ChalkDatabase::with_program_and_goals(PROGRAM, SolverChoice::default(), &goal_texts)
+            .unwrap();
+
+    let manual_db = ChalkDatabase::with(PROGRAM, SolverChoice::default());
+    manual_db.checked_program().unwrap();
+    let manual_goals: Vec<_> = goal_texts
+        .iter()
+        .map(|text| {
+            manual_db
+                .parse_and_lower_goal(text)
+                .unwrap()
+                .into_peeled_goal(manual_db.interner())
+        })
+        .collect();
+
+    assert_eq!(goals, manual_goals);
+    db.with_program(|_| {
+        for goal in &goals {
+            assert_eq!(db.solve(goal), manual_db.solve(goal));
+        }
+    });
+}
+
+#[test]
+fn reports_errors() {
+    let error = ChalkDatabase::with_program_and_goals(
+        "struct Foo<T> { x: U }",
+        SolverChoice::default(),
+        &["Foo<Foo<i32>>: Sized"],
+    )
+    .unwrap_err();
+    assert_eq!(error.to_string(), "invalid parameter name `U`");
+
+    let error = ChalkDatabase::with_program_and_goals(
+        PROGRAM,
+        SolverChoice::default(),
+        &["Foo: Clone", "Bar: Clone"],
+    )
+    .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "error in goal `Bar: Clone`: invalid parameter name `Bar`"
+    );
+}
@@ -0,0 +1,48 @@
+use chalk_integration::db::ChalkDatabase;
+use chalk_integration::lowering::{lower_goal_with_options, GoalLoweringOptions};
+use chalk_integration::query::LoweringDatabase;
+use chalk_integration::SolverChoice;
+
+fn nested_foralls(depth: usize) -> String {
+    let mut goal = String::new();
+    for i in 0..depth {
+        goal.push_str(&format!("forall<T{}> {{ ", i));
+    }
+    goal.push_str("T0: Foo");
+    for _ in 0..depth {
+        goal.push_str(" }");
+    }
+    goal
+}
+
+#[test]
+fn deeply_nested_quantifiers() {
+    let db = ChalkDatabase::with("trait Foo { }", SolverChoice::default());
+    let error = db
+        .parse_and_lower_goal(&nested_foralls(10_000))
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "quantifier binding `T256` is nested more than 256 quantifiers deep"
+    );
+    assert!(error.span().is_some());
+
+    assert!(db.parse_and_lower_goal(&nested_foralls(256)).is_ok());
+}
+
+#[test]
+fn configurable_quantifier_depth() {
+    let db = ChalkDatabase::with("trait Foo { }", SolverChoice::default());
+    let program = db.checked_program().unwrap();
+    let goal = chalk_parse::parse_goal(&nested_foralls(3)).unwrap();
+    let with_max_depth = |max_quantifier_depth| GoalLoweringOptions {
+        max_quantifier_depth,
+        ..GoalLoweringOptions::default()
+    };
+    assert!(lower_goal_with_options(&goal, &program, with_max_depth(3)).is_ok());
+    let error = lower_goal_with_options(&goal, &program, with_max_depth(2)).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "quantifier binding `T2` is nested more than 2 quantifiers deep"
+    );
+}
@@ -0,0 +1,38 @@
+use chalk_integration::db::ChalkDatabase;
+use chalk_integration::SolverChoice;
+use chalk_recursive::{Cache, RecursiveSolver};
+use chalk_solve::Solver;
+
+#[test]
+fn solving_twice_hits_cache() {
+    let (db, goals) = ChalkDatabase::with_program_and_goals(
+        "trait Clone { } \
+         struct Foo { } \
+         struct Vec<T> { } \
+         impl Clone for Foo { } \
+         impl<T> Clone for Vec<T> where T: Clone { }",
+        SolverChoice::recursive_default(),
+        &["Vec<Vec<Foo>>: Clone"],
+    )
+    .unwrap();
+    let peeled_goal = &goals[0];
+    db.with_program(|_| {
+        let cache = Cache::new();
+        let mut solver = RecursiveSolver::new(10, 30, Some(cache.clone()));
+
+        let first = solver.solve(&db, peeled_goal);
+        assert!(first.as_ref().is_some_and(|s| s.is_unique()));
+        let hits = cache.hits();
+
+        // The root goal was fully solved the first time around, so the second
+        // query is answered straight from the cache.
+        let second = solver.solve(&db, peeled_goal);
+        assert_eq!(first, second);
+        assert_eq!(cache.hits(), hits + 1);
+
+        // A fresh solver sharing the same cache benefits as well.
+        let mut other = RecursiveSolver::new(10, 30, Some(cache.clone()));
+        assert_eq!(other.solve(&db, peeled_goal), first);
+        assert_eq!(cache.hits(), hits + 2);
+    });
+}
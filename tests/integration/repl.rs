@@ -0,0 +1,259 @@
+use std::fs;
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Runs the `chalk` binary with `args` in a fresh temporary directory that
+/// holds `files`, given as `(name, contents)` pairs, so that `args` can
+/// refer to those files by name.
+fn run_chalk(files: &[(&str, &str)], args: &[&str]) -> Output {
+    static RUNS: AtomicUsize = AtomicUsize::new(0);
+    let dir = std::env::temp_dir().join(format!(
+        "chalk-repl-{}-{}",
+        std::process::id(),
+        RUNS.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::create_dir_all(&dir).unwrap();
+    for (name, contents) in files {
+        fs::write(dir.join(name), contents).unwrap();
+    }
+    let output = Command::new(env!("CARGO_BIN_EXE_chalk"))
+        .current_dir(&dir)
+        .args(args)
+        .output()
+        .unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    output
+}
+
+#[test]
+fn goal_file() {
+    let output = run_chalk(
+        &[
+            (
+                "program.chalk",
+                "struct Foo { } struct Bar { } trait Clone { } impl Clone for Foo { }",
+            ),
+            (
+                "goals.txt",
+                "// goals to evaluate\nFoo: Clone\n\nBar: Clone\nexists<T> { T: Clone }\n",
+            ),
+        ],
+        &["--program=program.chalk", "--goal-file=goals.txt"],
+    );
+
+    // `Bar: Clone` has no solution.
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().filter(|l| !l.is_empty()).collect();
+    assert_eq!(
+        lines,
+        [
+            "Foo: Clone => Unique; substitution [], lifetime constraints []",
+            "Bar: Clone => No possible solution.",
+            "exists<T> { T: Clone } => Unique; substitution [?0 := Foo], lifetime constraints []",
+        ]
+    );
+}
+
+#[test]
+fn warn_unused_clauses() {
+    let output = run_chalk(
+        &[(
+            "program.chalk",
+            "struct Foo { } struct Bar { } trait Clone { } trait Copy { } \
+             forall<T> { T: Clone if T = Foo } \
+             forall<T> { T: Copy if T = Bar }",
+        )],
+        &[
+            "--program=program.chalk",
+            "--goal=Foo: Clone",
+            "--warn-unused-clauses",
+        ],
+    );
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("Unique"));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let warnings: Vec<&str> = stderr
+        .lines()
+        .filter(|l| l.starts_with("warning:"))
+        .collect();
+    assert_eq!(
+        warnings,
+        ["warning: custom clause is never used: for<type> Implemented(^0.0: Copy) :- (^0.0 = Bar)"]
+    );
+}
+
+#[test]
+fn exit_codes() {
+    let run = |goals: &[&str]| {
+        let output = run_chalk(
+            &[
+                (
+                    "program.chalk",
+                    "struct Foo { } struct Bar { } struct Baz { } trait Clone { } \
+                     impl Clone for Foo { } impl Clone for Bar { }",
+                ),
+                ("goals.txt", &goals.join("\n")),
+            ],
+            &[
+                "--program=program.chalk",
+                "--goal-file=goals.txt",
+                "--quiet",
+            ],
+        );
+        assert!(output.stdout.is_empty());
+        output.status.code()
+    };
+
+    assert_eq!(run(&["Foo: Clone", "Bar: Clone"]), Some(0));
+    assert_eq!(run(&["Foo: Clone", "exists<T> { T: Clone }"]), Some(2));
+    assert_eq!(run(&["Baz: Clone"]), Some(1));
+    assert_eq!(run(&["exists<T> { T: Clone }", "Baz: Clone"]), Some(1));
+    assert_eq!(run(&["Qux: Clone"]), Some(1));
+}
+
+#[test]
+fn time_goals() {
+    let output = run_chalk(
+        &[(
+            "program.chalk",
+            "struct Foo { } trait Clone { } impl Clone for Foo { }",
+        )],
+        &["--program=program.chalk", "--goal=Foo: Clone", "--time"],
+    );
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().filter(|l| !l.is_empty()).collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("Unique"));
+    let seconds = lines[1]
+        .strip_prefix("time: ")
+        .and_then(|t| t.strip_suffix('s'))
+        .unwrap_or_else(|| panic!("expected a timing line, found {:?}", lines[1]));
+    let seconds: f64 = seconds.parse().unwrap();
+    assert!(seconds >= 0.0);
+}
+
+#[test]
+fn no_implied_bounds() {
+    let run = |extra_args: &[&str]| {
+        let mut args = vec![
+            "--program=program.chalk",
+            "--goal=forall<T> { if (T: Sub) { T: Super } }",
+        ];
+        args.extend(extra_args);
+        run_chalk(
+            &[(
+                "program.chalk",
+                "trait Super { } trait Sub where Self: Super { }",
+            )],
+            &args,
+        )
+    };
+    let elaborated = run(&[]);
+    let not_elaborated = run(&["--no-implied-bounds"]);
+
+    assert!(elaborated.status.success());
+    assert!(String::from_utf8(elaborated.stdout)
+        .unwrap()
+        .starts_with("Unique"));
+    assert_eq!(not_elaborated.status.code(), Some(1));
+    assert!(String::from_utf8(not_elaborated.stdout)
+        .unwrap()
+        .starts_with("No possible solution"));
+}
+
+#[test]
+fn failure_reason() {
+    let run = |extra_args: &[&str]| {
+        let mut args = vec!["--program=program.chalk", "--goal=Vec<u32>: Clone"];
+        args.extend(extra_args);
+        run_chalk(
+            &[(
+                "program.chalk",
+                "struct Vec<T> { } trait Clone { } impl<T> Clone for Vec<T> where T: Clone { }",
+            )],
+            &args,
+        )
+    };
+    let output = run(&["--explain"]);
+    let unexplained = run(&[]);
+
+    // Without `--explain`, the goal is only solved once and no subgoal is
+    // named.
+    assert_eq!(unexplained.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(unexplained.stdout).unwrap(),
+        "No possible solution.\n\n"
+    );
+
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().filter(|l| !l.is_empty()).collect();
+    assert_eq!(
+        lines,
+        [
+            "No possible solution.",
+            "no answers for `Implemented(Uint(U32): Clone)`"
+        ]
+    );
+}
+
+#[test]
+fn program_dir() {
+    let output = run_chalk(
+        &[
+            ("b_impls.chalk", "impl Clone for Foo { }"),
+            ("a_decls.chalk", "struct Foo { } trait Clone { }"),
+            ("notes.txt", "not a program"),
+        ],
+        &["--program-dir=.", "--goal=Foo: Clone"],
+    );
+    let empty_output = run_chalk(
+        &[("notes.txt", "not a program")],
+        &["--program-dir=.", "--goal=Foo: Clone"],
+    );
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("Unique"));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let loaded: Vec<&str> = stderr
+        .lines()
+        .filter_map(|l| l.strip_prefix("loaded "))
+        .collect();
+    assert_eq!(loaded, ["./a_decls.chalk", "./b_impls.chalk"]);
+
+    assert_eq!(empty_output.status.code(), Some(1));
+    assert!(String::from_utf8(empty_output.stderr)
+        .unwrap()
+        .contains("no `.chalk` files found"));
+}
+
+#[test]
+fn program_dir_errors() {
+    let missing = run_chalk(&[], &["--program-dir=missing", "--goal=Foo: Clone"]);
+    let parse_error = run_chalk(
+        &[
+            ("a_decls.chalk", "struct Foo { } trait Clone { }"),
+            ("b_impls.chalk", "impl Clone for { }"),
+        ],
+        &["--program-dir=.", "--goal=Foo: Clone"],
+    );
+
+    assert_eq!(missing.status.code(), Some(1));
+    let stderr = String::from_utf8(missing.stderr).unwrap();
+    assert!(stderr.contains("`missing`: "), "{}", stderr);
+
+    // The error offsets are within `b_impls.chalk`, not the joined program.
+    assert_eq!(parse_error.status.code(), Some(1));
+    let stderr = String::from_utf8(parse_error.stderr).unwrap();
+    assert!(
+        stderr.contains("`./b_impls.chalk`: parse error: Unrecognized token `{` found at 15:16"),
+        "{}",
+        stderr
+    );
+}
@@ -0,0 +1,60 @@
+use chalk_integration::db::ChalkDatabase;
+use chalk_integration::query::LoweringDatabase;
+
+#[test]
+fn selected_impl_for_trait_goal() {
+    for solver_choice in super::solver_choices() {
+        let (db, goals) = ChalkDatabase::with_program_and_goals(
+            "
+                trait Clone { }
+                struct Foo { }
+                struct Bar { }
+                struct Vec<T> { }
+                impl Clone for Foo { }
+                impl<T> Clone for Vec<T> where T: Clone { }
+            ",
+            solver_choice,
+            &[
+                "Vec<Foo>: Clone",
+                "Foo: Clone",
+                "Vec<Bar>: Clone",
+                "exists<T> { Vec<T>: Clone }",
+                "forall<T> { if (T: Clone) { T: Clone } }",
+                "forall<T> { if (T: Clone) { Vec<T>: Clone } }",
+                "forall<T> { if (Vec<T>: Clone) { Vec<T>: Clone } }",
+            ],
+        )
+        .unwrap();
+        db.with_program(|program| {
+            let impl_for = |self_ty: &str| {
+                program
+                    .impl_data
+                    .iter()
+                    .find(|(_, datum)| {
+                        format!("{:?}", datum.binders.skip_binders().trait_ref).starts_with(self_ty)
+                    })
+                    .map(|(&id, _)| id)
+                    .unwrap()
+            };
+
+            let solver = db.solver();
+            let mut solver = solver.lock().unwrap();
+            let selected: Vec<_> = goals
+                .iter()
+                .map(|goal| solver.selected_impl(&db, goal))
+                .collect();
+            assert_eq!(
+                selected,
+                [
+                    Some(impl_for("Vec")),
+                    Some(impl_for("Foo")),
+                    None,
+                    None,
+                    None,
+                    Some(impl_for("Vec")),
+                    None,
+                ]
+            );
+        });
+    }
+}
@@ -0,0 +1,155 @@
+use chalk_integration::db::ChalkDatabase;
+use chalk_integration::query::LoweringDatabase;
+use chalk_ir::cast::Cast;
+use chalk_ir::*;
+use chalk_solve::infer::{InPlaceSolution, InferenceTable};
+use chalk_solve::RustIrDatabase;
+
+#[test]
+fn solve_with_pre_bound_variables() {
+    for solver_choice in super::solver_choices() {
+        let db = ChalkDatabase::with(
+            "
+                trait Clone { }
+                struct Foo { }
+                struct Bar { }
+                struct Vec<T> { }
+                impl Clone for Vec<Foo> { }
+            ",
+            solver_choice,
+        );
+        db.with_program(|program| {
+            let interner = db.interner();
+            let vec = |ty: Ty<_>| {
+                TyKind::Adt(
+                    program.adt_ids[&"Vec".into()],
+                    Substitution::from1(interner, ty),
+                )
+                .intern(interner)
+            };
+            let clone_goal = |ty: Ty<_>| -> InEnvironment<Goal<_>> {
+                let trait_ref = TraitRef {
+                    trait_id: program.trait_ids[&"Clone".into()],
+                    substitution: Substitution::from1(interner, ty),
+                };
+                InEnvironment::new(&Environment::new(interner), trait_ref.cast(interner))
+            };
+
+            let solver = db.solver();
+            let mut solver = solver.lock().unwrap();
+            let mut table = InferenceTable::new();
+            let u0 = table.new_universe();
+            let var0 = table.new_variable(u0).to_ty(interner);
+            let var1 = table.new_variable(u0).to_ty(interner);
+
+            // ?0 = Vec<?1>
+            table
+                .relate(
+                    interner,
+                    db.unification_database(),
+                    &Environment::new(interner),
+                    Variance::Invariant,
+                    &var0,
+                    &vec(var1.clone()),
+                )
+                .unwrap();
+
+            // Solving `?0: Clone` binds `?1` in the caller's table.
+            let solution = table
+                .solve_in_place(interner, &db, &mut **solver, clone_goal(var0.clone()))
+                .unwrap();
+            assert!(solution.is_unique());
+            let foo = TyKind::Adt(
+                program.adt_ids[&"Foo".into()],
+                Substitution::empty(interner),
+            )
+            .intern(interner);
+            assert_eq!(
+                table.normalize_ty_shallow(interner, &var1),
+                Some(foo.clone())
+            );
+            assert_eq!(
+                table.normalize_ty_shallow(interner, &var0),
+                Some(vec(var1.clone()))
+            );
+
+            // Once `?1` is bound, a conflicting goal has no solution.
+            let bar = TyKind::Adt(
+                program.adt_ids[&"Bar".into()],
+                Substitution::empty(interner),
+            )
+            .intern(interner);
+            let var2 = table.new_variable(u0).to_ty(interner);
+            table
+                .relate(
+                    interner,
+                    db.unification_database(),
+                    &Environment::new(interner),
+                    Variance::Invariant,
+                    &var2,
+                    &vec(bar),
+                )
+                .unwrap();
+            assert!(table
+                .solve_in_place(interner, &db, &mut **solver, clone_goal(var2))
+                .is_none());
+        });
+    }
+}
+
+#[test]
+fn constraints_refer_to_caller_variables() {
+    for solver_choice in super::solver_choices() {
+        let db = ChalkDatabase::with(
+            "
+                trait Foo { }
+                struct Ref<'a, T> { }
+                impl<'a, T> Foo for Ref<'a, T> where T: 'a { }
+            ",
+            solver_choice,
+        );
+        db.with_program(|program| {
+            let interner = db.interner();
+            let solver = db.solver();
+            let mut solver = solver.lock().unwrap();
+            let mut table = InferenceTable::new();
+            let u0 = table.new_universe();
+            let var0 = table.new_variable(u0).to_ty(interner);
+
+            // Solving `Ref<'static, ?0>: Foo` requires `?0: 'static`.
+            let static_lifetime = LifetimeData::Static.intern(interner);
+            let ref_ty = TyKind::Adt(
+                program.adt_ids[&"Ref".into()],
+                Substitution::from_iter(
+                    interner,
+                    vec![
+                        static_lifetime.cast(interner),
+                        var0.clone().cast::<GenericArg<_>>(interner),
+                    ],
+                ),
+            )
+            .intern(interner);
+            let trait_ref = TraitRef {
+                trait_id: program.trait_ids[&"Foo".into()],
+                substitution: Substitution::from1(interner, ref_ty),
+            };
+            let goal = InEnvironment::new(&Environment::new(interner), trait_ref.cast(interner));
+
+            let constraints = match table.solve_in_place(interner, &db, &mut **solver, goal) {
+                Some(InPlaceSolution::Unique(constraints)) => constraints,
+                solution => panic!("expected a unique solution, got {:?}", solution),
+            };
+            let expected = Constraints::from1(
+                interner,
+                InEnvironment::new(
+                    &Environment::new(interner),
+                    Constraint::TypeOutlives(var0, static_lifetime),
+                ),
+            );
+            assert_eq!(
+                table.normalize_deep(interner, constraints),
+                table.normalize_deep(interner, expected)
+            );
+        });
+    }
+}
@@ -0,0 +1,80 @@
+//! Checks that the SLG and recursive solvers agree on a set of goals
+//! against `libstd.chalk`.
+
+use chalk_integration::db::ChalkDatabase;
+use chalk_integration::SolverChoice;
+use chalk_solve::ext::GoalExt;
+use chalk_solve::solve::test_support::solve_with_all;
+use chalk_solve::RustIrDatabase;
+
+const GOALS: &[&str] = &[
+    "i32: Copy",
+    "Vec<Box<i32>>: Clone",
+    "Vec<Rc<u32>>: Copy",
+    "Rc<Vec<u32>>: Clone",
+    "Slice<u32>: Clone",
+    "Box<Vec<i32>>: AsRef<Vec<i32>>",
+    "forall<T> { Rc<T>: Clone }",
+    "forall<T> { Box<T>: Clone }",
+    "forall<T> { if (T: Clone) { Box<T>: Clone } }",
+    "forall<T> { if (T: Copy) { Vec<T>: Clone } }",
+    "forall<T> { if (T: Clone; T: Sized) { Vec<T>: Clone } }",
+    "exists<T> { Box<T>: AsRef<u32> }",
+    "exists<T> { Vec<u32>: AsRef<T> }",
+    "exists<T> { T: Copy }",
+    "exists<T> { Vec<T>: Clone }",
+    "exists<T> { Rc<T>: Clone }",
+    "exists<T> { Slice<T>: AsRef<Slice<u32>> }",
+    "exists<T> { T: Clone }",
+    "exists<T, U> { Vec<T>: AsRef<U> }",
+    "exists<T> { Vec<T>: AsRef<Slice<T>> }",
+    "forall<T> { if (T: Sized) { exists<U> { Vec<T>: AsRef<U> } } }",
+];
+
+/// Goals on which the solvers are known to give different answers, each
+/// with a comment explaining why. Entries that start agreeing must be
+/// removed.
+const KNOWN_DIFFERENCES: &[&str] = &[];
+
+#[test]
+fn solvers_agree_on_libstd() {
+    let db = ChalkDatabase::with(include_str!("../../libstd.chalk"), SolverChoice::default());
+    db.with_program(|_| {
+        let mut slg = SolverChoice::slg_default().into_solver();
+        let mut recursive = SolverChoice::recursive_default().into_solver();
+        for goal_text in GOALS {
+            let goal = db.parse_and_lower_goal(goal_text).unwrap();
+            let goal = goal.into_peeled_goal(db.interner());
+            let result = solve_with_all(&db, &goal, &mut [&mut *slg, &mut *recursive]);
+            match result {
+                Ok(_) => assert!(
+                    !KNOWN_DIFFERENCES.contains(goal_text),
+                    "`{}` is listed as a known difference, but the solvers agree",
+                    goal_text
+                ),
+                Err(solutions) => assert!(
+                    KNOWN_DIFFERENCES.contains(goal_text),
+                    "solvers disagree on `{}`: {:?}",
+                    goal_text,
+                    solutions
+                ),
+            }
+        }
+    });
+}
+
+#[test]
+fn solve_with_all_reports_disagreement() {
+    let db = ChalkDatabase::with(include_str!("../../libstd.chalk"), SolverChoice::default());
+    db.with_program(|_| {
+        // With a single table the solver cannot look at the subgoals of
+        // `Vec<Box<i32>>: Clone`, so it can only answer ambiguously.
+        let mut slg = SolverChoice::slg_default().into_solver();
+        let mut limited = SolverChoice::slg_with_max_tables(10, 1).into_solver();
+        let goal = db.parse_and_lower_goal("Vec<Box<i32>>: Clone").unwrap();
+        let goal = goal.into_peeled_goal(db.interner());
+        let solutions = solve_with_all(&db, &goal, &mut [&mut *slg, &mut *limited]).unwrap_err();
+        assert!(solutions[0].as_ref().unwrap().is_unique());
+        assert!(solutions[1].as_ref().unwrap().is_ambig());
+    });
+}
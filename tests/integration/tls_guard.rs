@@ -0,0 +1,28 @@
+use chalk_integration::db::ChalkDatabase;
+use chalk_integration::query::LoweringDatabase;
+use chalk_integration::tls;
+use chalk_integration::SolverChoice;
+
+#[test]
+fn nested_program_guards() {
+    let outer = ChalkDatabase::with("trait Outer { }", SolverChoice::default())
+        .program_ir()
+        .unwrap();
+    let inner = ChalkDatabase::with("trait Inner { }", SolverChoice::default())
+        .program_ir()
+        .unwrap();
+    let trait_id = *outer.trait_ids.values().next().unwrap();
+    let unresolved = format!("{:?}", trait_id);
+
+    {
+        let _outer_guard = tls::set_current_program(&outer);
+        assert_eq!(format!("{:?}", trait_id), "Outer");
+        {
+            let _inner_guard = tls::set_current_program(&inner);
+            assert_eq!(format!("{:?}", trait_id), "Inner");
+        }
+        assert_eq!(format!("{:?}", trait_id), "Outer");
+    }
+    assert_eq!(format!("{:?}", trait_id), unresolved);
+    assert!(tls::with_current_program(|program| program.is_none()));
+}
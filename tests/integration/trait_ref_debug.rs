@@ -0,0 +1,22 @@
+use chalk_integration::db::ChalkDatabase;
+use chalk_integration::SolverChoice;
+
+#[test]
+fn trait_ref_debug_puts_self_before_colon() {
+    let db = ChalkDatabase::with(
+        "trait Into<T> { } struct Foo { } struct Bar { }",
+        SolverChoice::default(),
+    );
+    let goal = db.parse_and_lower_goal("Foo: Into<Bar>").unwrap();
+
+    db.with_program(|_| {
+        assert_eq!(format!("{:?}", goal), "Implemented(Foo: Into<Bar>)");
+    });
+
+    // Without a program to look up names, the self type still comes
+    // first and only the remaining parameters follow the trait.
+    assert_eq!(
+        format!("{:?}", goal),
+        "Implemented(Ty(AdtId(#1)<[]>): TraitId(#0)<Ty(AdtId(#2)<[]>)>)"
+    );
+}
@@ -0,0 +1,69 @@
+use chalk_integration::db::ChalkDatabase;
+use chalk_integration::interner::ChalkIr;
+use chalk_integration::SolverChoice;
+use chalk_recursive::{CannotProveReason, RecursiveSolver};
+use chalk_solve::ext::GoalExt;
+use chalk_solve::solve::truncate::Truncated;
+use chalk_solve::{Guidance, Solution};
+
+#[test]
+fn recursive_solver_reports_truncated_size() {
+    let db = ChalkDatabase::with(
+        "
+            trait Foo { }
+            struct S<T> { }
+            impl<T> Foo for S<T> where S<S<T>>: Foo { }
+        ",
+        SolverChoice::default(),
+    );
+    let goal = db.parse_and_lower_goal("S<u32>: Foo").unwrap();
+
+    let mut solver = RecursiveSolver::new(10, 3, None);
+    let (solution, reasons) =
+        db.with_program(|_| solver.solve_with_reasons(&db, &goal.into_peeled_goal(&ChalkIr)));
+    assert_eq!(solution, Some(Solution::Ambig(Guidance::Unknown)));
+
+    // `S<S<S<u32>>>` has size 4, one more than the maximum size.
+    assert_eq!(
+        reasons,
+        [CannotProveReason::Truncated(Truncated {
+            size: 4,
+            max_size: 3
+        })]
+    );
+}
+
+#[test]
+fn recursive_solver_reports_every_truncation() {
+    // Each impl gives up on a subgoal of a different size, and the goal is
+    // ambiguous because of both.
+    let db = ChalkDatabase::with(
+        "
+            trait Foo { }
+            struct S<T> { }
+            struct W<T> { }
+            impl<T> Foo for S<T> where S<S<T>>: Foo { }
+            impl<T> Foo for W<T> where S<S<S<W<T>>>>: Foo { }
+        ",
+        SolverChoice::default(),
+    );
+    let goal = db.parse_and_lower_goal("exists<T> { T: Foo }").unwrap();
+
+    let mut solver = RecursiveSolver::new(10, 3, None);
+    let (solution, reasons) =
+        db.with_program(|_| solver.solve_with_reasons(&db, &goal.into_peeled_goal(&ChalkIr)));
+    assert!(solution.unwrap().is_ambig());
+    assert_eq!(
+        reasons,
+        [
+            CannotProveReason::Truncated(Truncated {
+                size: 4,
+                max_size: 3
+            }),
+            CannotProveReason::Truncated(Truncated {
+                size: 5,
+                max_size: 3
+            }),
+        ]
+    );
+}
@@ -0,0 +1,31 @@
+use chalk_integration::db::ChalkDatabase;
+use chalk_integration::SolverChoice;
+use chalk_ir::*;
+use chalk_solve::ext::GoalExt;
+use chalk_solve::RustIrDatabase;
+
+#[test]
+fn goal_well_formed() {
+    let db = ChalkDatabase::with(
+        "trait Clone { } struct Foo { } impl Clone for Foo { }",
+        SolverChoice::default(),
+    );
+    let goal = db.parse_and_lower_goal("Foo: Clone").unwrap();
+    db.with_program(|_| {
+        let interner = db.interner();
+        let wf_goal = goal.clone().well_formed(interner).unwrap();
+        match (goal.data(interner), wf_goal.data(interner)) {
+            (
+                GoalData::DomainGoal(DomainGoal::Holds(WhereClause::Implemented(trait_ref))),
+                GoalData::DomainGoal(DomainGoal::WellFormed(WellFormed::Trait(wf_trait_ref))),
+            ) => assert_eq!(trait_ref, wf_trait_ref),
+            _ => panic!("expected `WellFormed(Foo: Clone)`, got {:?}", wf_goal),
+        }
+
+        let solution = db.solve(&wf_goal.into_peeled_goal(interner)).unwrap();
+        assert!(solution.is_unique());
+    });
+
+    let goal = db.parse_and_lower_goal("forall<T> { T: Clone }").unwrap();
+    assert!(goal.well_formed(db.interner()).is_none());
+}
@@ -0,0 +1,27 @@
+use chalk_integration::db::ChalkDatabase;
+use chalk_integration::query::LoweringDatabase;
+
+#[test]
+fn whole_program_disables_orphan_rules() {
+    for solver_choice in super::solver_choices() {
+        let mut db = ChalkDatabase::with(
+            "
+                #[upstream] trait Remote { }
+                #[upstream] struct Vec<T> { }
+                struct Local { }
+                impl Remote for Vec<Local> { }
+            ",
+            solver_choice,
+        );
+        assert_eq!(
+            db.checked_program().unwrap_err().to_string(),
+            "impl for trait `Remote` violates the orphan rules"
+        );
+
+        db.set_whole_program(true);
+        assert!(db.checked_program().is_ok());
+
+        db.set_whole_program(false);
+        assert!(db.checked_program().is_err());
+    }
+}
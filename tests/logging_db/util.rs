@@ -39,34 +39,33 @@ pub fn logging_db_output_sufficient(
 
         let program = db.program_ir().unwrap();
         let wrapped = LoggingRustIrDatabase::<_, Program, _>::new(program.clone());
-        chalk_integration::tls::set_current_program(&program, || {
-            for (goal_text, solver_choice, expected) in goals.clone() {
-                let mut solver = solver_choice.into_solver();
+        let _guard = chalk_integration::tls::set_current_program(&program);
+        for (goal_text, solver_choice, expected) in goals.clone() {
+            let mut solver = solver_choice.into_solver();
 
-                println!("----------------------------------------------------------------------");
-                println!("---- first run on original test code ---------------------------------");
-                println!("goal {}", goal_text);
-                assert!(goal_text.starts_with("{"));
-                assert!(goal_text.ends_with("}"));
-                let goal = lower_goal(
-                    &*chalk_parse::parse_goal(&goal_text[1..goal_text.len() - 1]).unwrap(),
-                    &*program,
-                )
-                .unwrap();
+            println!("----------------------------------------------------------------------");
+            println!("---- first run on original test code ---------------------------------");
+            println!("goal {}", goal_text);
+            assert!(goal_text.starts_with("{"));
+            assert!(goal_text.ends_with("}"));
+            let goal = lower_goal(
+                &*chalk_parse::parse_goal(&goal_text[1..goal_text.len() - 1]).unwrap(),
+                &*program,
+            )
+            .unwrap();
 
-                println!("using solver: {:?}", solver_choice);
-                let peeled_goal = goal.into_peeled_goal(db.interner());
-                match expected {
-                    TestGoal::Aggregated(expected) => {
-                        let result = solver.solve(&wrapped, &peeled_goal);
-                        assert_result(result, expected, db.interner());
-                    }
-                    _ => panic!("only aggregated test goals supported for logger goals"),
+            println!("using solver: {:?}", solver_choice);
+            let peeled_goal = goal.into_peeled_goal(db.interner());
+            match expected {
+                TestGoal::Aggregated(expected) => {
+                    let result = solver.solve(&wrapped, &peeled_goal);
+                    assert_result(result, expected, db.interner());
                 }
+                _ => panic!("only aggregated test goals supported for logger goals"),
             }
+        }
 
-            wrapped.to_string()
-        })
+        wrapped.to_string()
     };
 
     println!("----------------------------------------------------------------------");
@@ -84,27 +83,26 @@ pub fn logging_db_output_sufficient(
     for (goal_text, solver_choice, expected) in goals {
         let mut solver = solver_choice.into_solver();
 
-        chalk_integration::tls::set_current_program(&new_program, || {
-            println!("----------------------------------------------------------------------");
-            println!("---- second run on code output by logger -----------------------------");
-            println!("goal {}", goal_text);
-            assert!(goal_text.starts_with("{"));
-            assert!(goal_text.ends_with("}"));
-            let goal = lower_goal(
-                &*chalk_parse::parse_goal(&goal_text[1..goal_text.len() - 1]).unwrap(),
-                &*new_program,
-            )
-            .unwrap();
+        let _guard = chalk_integration::tls::set_current_program(&new_program);
+        println!("----------------------------------------------------------------------");
+        println!("---- second run on code output by logger -----------------------------");
+        println!("goal {}", goal_text);
+        assert!(goal_text.starts_with("{"));
+        assert!(goal_text.ends_with("}"));
+        let goal = lower_goal(
+            &*chalk_parse::parse_goal(&goal_text[1..goal_text.len() - 1]).unwrap(),
+            &*new_program,
+        )
+        .unwrap();
 
-            println!("using solver: {:?}", solver_choice);
-            let peeled_goal = goal.into_peeled_goal(db.interner());
-            match expected {
-                TestGoal::Aggregated(expected) => {
-                    let result = solver.solve(&db, &peeled_goal);
-                    assert_result(result, expected, db.interner());
-                }
-                _ => panic!("only aggregated test goals supported for logger goals"),
+        println!("using solver: {:?}", solver_choice);
+        let peeled_goal = goal.into_peeled_goal(db.interner());
+        match expected {
+            TestGoal::Aggregated(expected) => {
+                let result = solver.solve(&db, &peeled_goal);
+                assert_result(result, expected, db.interner());
             }
-        });
+            _ => panic!("only aggregated test goals supported for logger goals"),
+        }
     }
 }
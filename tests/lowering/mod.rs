@@ -1,6 +1,9 @@
 use chalk_integration::db::ChalkDatabase;
 use chalk_integration::query::LoweringDatabase;
 use chalk_integration::SolverChoice;
+use chalk_ir::{AliasTy, DomainGoal, GoalData, Normalize, QuantifierKind, Substitution, TyKind};
+use chalk_solve::split::Split;
+use chalk_solve::RustIrDatabase;
 
 #[test]
 fn lower_success() {
@@ -22,7 +25,52 @@ fn not_trait() {
             impl Foo for Bar { }
         }
         error_msg {
-            "expected a trait, found `Foo`, which is not a trait"
+            "`Foo` is a struct, not a trait"
+        }
+    }
+}
+
+#[test]
+fn not_trait_sort() {
+    lowering_error! {
+        program {
+            struct Vec<T> { }
+            struct Foo { }
+            fn bar<T>() where T: Vec<Foo>;
+        }
+        error_msg {
+            "`Vec` is a struct, not a trait"
+        }
+    }
+
+    lowering_error! {
+        program {
+            enum Option<T> { }
+            trait Bar { }
+            impl<T> Bar for T where T: Option<T> { }
+        }
+        error_msg {
+            "`Option` is an enum, not a trait"
+        }
+    }
+
+    lowering_error! {
+        program {
+            struct Foo { }
+            trait Bar where Self: Foo { }
+        }
+        error_msg {
+            "`Foo` is a struct, not a trait"
+        }
+    }
+
+    lowering_error! {
+        program {
+            struct Foo { }
+            struct Baz { x: dyn Foo + 'static }
+        }
+        error_msg {
+            "`Foo` is a struct, not a trait"
         }
     }
 }
@@ -110,6 +158,18 @@ fn invalid_name() {
     }
 }
 
+#[test]
+fn invalid_name_span() {
+    let program_text = "struct Foo { field: Bar }";
+    let db = ChalkDatabase::with(program_text, SolverChoice::default());
+    let error = db.program_ir().unwrap_err();
+    assert_eq!(error.to_string(), "invalid parameter name `Bar`");
+
+    let span = error.span().unwrap();
+    let start = program_text.find("Bar").unwrap();
+    assert_eq!((span.lo, span.hi), (start, start + "Bar".len()));
+}
+
 #[test]
 fn type_parameter() {
     lowering_success! {
@@ -193,7 +253,8 @@ fn atc_accounting() {
     impl_id: ImplId(#2),
     associated_ty_id: (Iterable::Iter),
     value: for<lifetime, type> AssociatedTyValueBound {
-        ty: Iter<'^0.0, ^0.1>
+        ty: Iter<'^0.0, ^0.1>,
+        where_clauses: []
     },
 }"#
             .replace(",\n", "\n"),
@@ -814,3 +875,207 @@ fn algebraic_data_types() {
         }
     }
 }
+
+#[test]
+fn trait_alias_impl() {
+    lowering_error! {
+        program {
+            trait Foo { }
+            trait Alias = Foo;
+            struct S { }
+            impl Alias for S { }
+        }
+        error_msg {
+            "cannot implement trait alias `Alias`"
+        }
+    }
+}
+
+#[test]
+fn trait_ref_from_projection() {
+    let db = ChalkDatabase::with(
+        "trait Iterator<X> { type Item<Y>; } struct Foo { }",
+        SolverChoice::default(),
+    );
+    let goal = db
+        .parse_and_lower_goal("forall<T> { Normalize(<T as Iterator<Foo>>::Item<T> -> Foo) }")
+        .unwrap();
+    db.with_program(|_| {
+        let interner = db.interner();
+        let projection = match goal.data(interner) {
+            GoalData::Quantified(QuantifierKind::ForAll, goal) => {
+                match goal.skip_binders().data(interner) {
+                    GoalData::DomainGoal(DomainGoal::Normalize(Normalize {
+                        alias: AliasTy::Projection(projection),
+                        ..
+                    })) => projection.clone(),
+                    goal => panic!("unexpected goal: {:?}", goal),
+                }
+            }
+            goal => panic!("unexpected goal: {:?}", goal),
+        };
+        assert_eq!(
+            format!("{:?}", db.trait_ref_from_projection(&projection)),
+            "^0.0 as Iterator<Foo>"
+        );
+    });
+}
+
+#[test]
+fn associated_type_debug() {
+    let db = ChalkDatabase::with(
+        "trait Iterator { type Item; } struct Foo { }",
+        SolverChoice::default(),
+    );
+    db.with_program(|program| {
+        let interner = db.interner();
+        let assoc_ty_id = *program.associated_ty_data.keys().next().unwrap();
+        let foo = TyKind::Adt(
+            program.adt_ids[&"Foo".into()],
+            Substitution::empty(interner),
+        )
+        .intern(interner);
+        let ty = TyKind::AssociatedType(assoc_ty_id, Substitution::from1(interner, foo))
+            .intern(interner);
+        assert_eq!(format!("{:?}", ty), "(Iterator::Item)<Foo>");
+    });
+}
+
+#[test]
+fn dead_impls() {
+    let db = ChalkDatabase::with(
+        "
+            trait Foo { }
+            trait Unimplemented { }
+            trait Bar { }
+            struct Live { }
+            struct Vec<T> { }
+            impl Foo for Live { }
+            impl<T> Bar for Vec<T> where T: Foo { }
+            impl<T> Foo for T where u32: Unimplemented { }
+        ",
+        SolverChoice::default(),
+    );
+    let dead_impls = db.dead_impls().unwrap();
+    db.with_program(|program| {
+        assert_eq!(dead_impls.len(), 1);
+        let impl_datum = &program.impl_data[&dead_impls[0]];
+        assert_eq!(
+            format!("{:?}", impl_datum.binders.skip_binders().where_clauses),
+            "[for<> Implemented(Uint(U32): Unimplemented)]"
+        );
+    });
+}
+
+#[test]
+fn unsatisfiable_where_clauses() {
+    let db = ChalkDatabase::with(
+        "
+            trait Foo { }
+            trait Unimplemented { }
+            trait Bar<T> where T: Foo, u32: Unimplemented { }
+            struct Live { }
+            struct Vec<T> where T: Foo { }
+            struct Dead<T> where T: Unimplemented { }
+            impl Foo for Live { }
+        ",
+        SolverChoice::default(),
+    );
+    let where_clauses = db.unsatisfiable_where_clauses().unwrap();
+    db.with_program(|program| {
+        let messages: Vec<_> = where_clauses
+            .iter()
+            .map(|wc| wc.display(program).to_string())
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "where clause `u32: Unimplemented` on trait `Bar` can never be satisfied",
+                "where clause `_1_0: Unimplemented` on type `Dead` can never be satisfied",
+            ]
+        );
+    });
+}
+
+#[test]
+fn empty_quantifiers() {
+    let db = ChalkDatabase::with("#[lang(copy)] trait Copy { }", SolverChoice::default());
+    let goal = db.parse_and_lower_goal("u32: Copy").unwrap();
+    assert_eq!(
+        db.parse_and_lower_goal("forall<> { u32: Copy }").unwrap(),
+        goal
+    );
+    assert_eq!(
+        db.parse_and_lower_goal("exists<> { u32: Copy }").unwrap(),
+        goal
+    );
+    assert_eq!(
+        db.parse_and_lower_goal("forall<> { exists<> { u32: Copy } }")
+            .unwrap(),
+        goal
+    );
+}
+
+#[test]
+fn goal_quantifier_kinds() {
+    let db = ChalkDatabase::with("trait Foo<'a> { }", SolverChoice::default());
+    let goal = db
+        .parse_and_lower_goal("forall<T, 'b> { exists<'a> { T: Foo<'a> } }")
+        .unwrap();
+    db.with_program(|_| {
+        assert_eq!(
+            format!("{:?}", goal),
+            "ForAll<type, lifetime> { Exists<lifetime> { Implemented(^1.0: Foo<'^0.0>) } }"
+        );
+    });
+}
+
+#[test]
+fn raw_identifiers() {
+    let db = ChalkDatabase::with(
+        "struct r#struct { } trait r#trait { } impl r#trait for r#struct { }",
+        SolverChoice::default(),
+    );
+    let goal = db.parse_and_lower_goal("r#struct: r#trait").unwrap();
+    db.with_program(|program| {
+        assert!(program.adt_ids.contains_key(&"struct".into()));
+        assert!(program.trait_ids.contains_key(&"trait".into()));
+        assert_eq!(format!("{:?}", goal), "Implemented(struct: trait)");
+    });
+}
+
+#[test]
+fn object_safe_goal_requires_trait() {
+    let db = ChalkDatabase::with("struct S { } trait Foo { }", SolverChoice::default());
+    assert!(db.parse_and_lower_goal("ObjectSafe(Foo)").is_ok());
+    assert_eq!(
+        db.parse_and_lower_goal("ObjectSafe(S)")
+            .unwrap_err()
+            .to_string(),
+        "`S` is a struct, not a trait"
+    );
+}
+
+#[test]
+fn struct_where_clause_mentions_self() {
+    lowering_success! {
+        program {
+            trait SomeTrait { type Assoc; }
+            struct S<K> where S<K>: SomeTrait { }
+            struct T<K> where T<K>: SomeTrait, <T<K> as SomeTrait>::Assoc: SomeTrait { }
+        }
+    }
+}
+
+#[test]
+fn only_sized_can_be_relaxed() {
+    lowering_error! {
+        program {
+            trait Copy { }
+            struct S<T: ?Copy> { }
+        }
+        error_msg {
+            "parse error: only `Sized` bounds can be relaxed with `?`"
+        }
+    }
+}
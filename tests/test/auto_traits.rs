@@ -368,3 +368,29 @@ fn phantom_auto_trait() {
         }
     }
 }
+
+/// An explicit negative impl opts a type out of an auto trait, even if
+/// all of its fields implement the trait.
+#[test]
+fn negative_impl_overrides_fields() {
+    test! {
+        program {
+            #[auto] trait Send { }
+            struct SendType { }
+            struct Foo { x: SendType }
+            impl !Send for Foo { }
+        }
+
+        goal {
+            SendType: Send
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            Foo: Send
+        } yields {
+            "No possible solution"
+        }
+    }
+}
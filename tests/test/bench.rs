@@ -1,12 +1,16 @@
 //! Benchmarking tests.
 
+extern crate test;
 use self::test::Bencher;
 
-use chalk_ir;
+use chalk_integration::db::ChalkDatabase;
+use chalk_integration::query::LoweringDatabase;
+use chalk_integration::SolverChoice;
+use chalk_solve::coherence::CoherenceSolver;
 use chalk_solve::ext::*;
-use chalk_solve::SolverChoice;
+use chalk_solve::RustIrDatabase;
 
-use super::{assert_result, parse_and_lower_goal, parse_and_lower_program_with_env};
+use super::assert_result;
 
 fn run_bench(
     program_text: &str,
@@ -15,20 +19,21 @@ fn run_bench(
     bencher: &mut Bencher,
     expected: &str,
 ) {
-    let (program, env) = parse_and_lower_program_with_env(program_text, solver_choice).unwrap();
-    chalk_ir::tls::set_current_program(&program, || {
-        let goal = parse_and_lower_goal(&program, goal_text).unwrap();
-        let peeled_goal = goal.into_peeled_goal();
+    let db = ChalkDatabase::with(program_text, solver_choice);
+    let program = db.checked_program().unwrap();
+    let _guard = chalk_integration::tls::set_current_program(&program);
+    let goal = db.parse_and_lower_goal(goal_text).unwrap();
+    let peeled_goal = goal.into_peeled_goal(db.interner());
 
-        // Execute once to get an expected result.
-        let result = solver_choice.solve_root_goal(&env, &peeled_goal);
+    // Execute once to get an expected result.
+    let result = solver_choice.into_solver().solve(&db, &peeled_goal);
 
-        // Check expectation.
-        assert_result(&result, expected);
+    // Check expectation.
+    assert_result(result, expected, db.interner());
 
-        // Then do it many times to measure time.
-        bencher.iter(|| solver_choice.solve_root_goal(&env, &peeled_goal));
-    });
+    // Then do it many times to measure time. Each iteration uses a fresh
+    // solver, so that nothing is cached between them.
+    bencher.iter(|| solver_choice.into_solver().solve(&db, &peeled_goal));
 }
 
 const CYCLEY: &str = "
@@ -37,12 +42,10 @@ trait Clone { }
 trait Copy where Self: Clone { }
 trait Sized { }
 
-struct i32 { }
 impl Copy for i32 { }
 impl Clone for i32 { }
 impl Sized for i32 { }
 
-struct u32 { }
 impl Copy for u32 { }
 impl Clone for u32 { }
 impl Sized for u32 { }
@@ -97,9 +100,118 @@ forall<T> {
 fn cycley_slg(b: &mut Bencher) {
     run_bench(
         CYCLEY,
-        SolverChoice::SLG { max_size: 20 },
+        SolverChoice::slg(20, None),
         CYCLEY_GOAL,
         b,
-        "Unique",
+        "Unique; substitution [], lifetime constraints []",
+    );
+}
+
+/// Blanket impls layered on top of each other: `ToString` is implemented
+/// for anything `Display`, which in turn is implemented structurally.
+const BLANKET: &str = "
+trait Display { }
+trait ToString { }
+impl<T> ToString for T where T: Display { }
+
+struct Foo { }
+struct Bar { }
+struct Vec<T> { }
+struct Option<T> { }
+struct Pair<T, U> { }
+
+impl Display for Foo { }
+impl Display for Bar { }
+impl<T> Display for Vec<T> where T: Display { }
+impl<T> Display for Option<T> where T: Display { }
+impl<T, U> Display for Pair<T, U> where T: Display, U: Display { }
+";
+
+const BLANKET_GOAL: &str = "
+Pair<Vec<Option<Foo>>, Option<Pair<Bar, Vec<Foo>>>>: ToString
+";
+
+#[bench]
+fn blanket_impl_slg(b: &mut Bencher) {
+    run_bench(
+        BLANKET,
+        SolverChoice::slg_default(),
+        BLANKET_GOAL,
+        b,
+        "Unique; substitution [], lifetime constraints []",
+    );
+}
+
+#[bench]
+fn blanket_impl_recursive(b: &mut Bencher) {
+    run_bench(
+        BLANKET,
+        SolverChoice::recursive_default(),
+        BLANKET_GOAL,
+        b,
+        "Unique; substitution [], lifetime constraints []",
+    );
+}
+
+/// Normalizing `<S<..S<Z>..> as Next>::Out` takes one projection per `S`.
+const DEEP_PROJECTION: &str = "
+trait Next { type Out; }
+struct Z { }
+struct S<T> { }
+impl Next for Z { type Out = S<Z>; }
+impl<T> Next for S<T> where T: Next { type Out = S<<T as Next>::Out>; }
+";
+
+const DEEP_PROJECTION_GOAL: &str = "
+Normalize(<S<S<S<S<S<Z>>>>> as Next>::Out -> S<S<S<S<S<S<Z>>>>>>)
+";
+
+#[bench]
+fn deep_projection_slg(b: &mut Bencher) {
+    run_bench(
+        DEEP_PROJECTION,
+        SolverChoice::slg_default(),
+        DEEP_PROJECTION_GOAL,
+        b,
+        "Unique; substitution [], lifetime constraints []",
+    );
+}
+
+#[bench]
+fn deep_projection_recursive(b: &mut Bencher) {
+    run_bench(
+        DEEP_PROJECTION,
+        SolverChoice::recursive_default(),
+        DEEP_PROJECTION_GOAL,
+        b,
+        "Unique; substitution [], lifetime constraints []",
     );
 }
+
+/// A trait implemented for each of `n` structs and for `Wrapper` of each
+/// of them; the coherence check compares all of these impls pairwise.
+fn many_impls_program(n: usize) -> String {
+    let mut program = String::from("trait Foo { }\nstruct Wrapper<T> { }\n");
+    for i in 0..n {
+        program.push_str(&format!(
+            "struct S{i} {{ }}\nimpl Foo for S{i} {{ }}\nimpl Foo for Wrapper<S{i}> {{ }}\n",
+            i = i
+        ));
+    }
+    program
+}
+
+#[bench]
+fn coherence_many_impls(b: &mut Bencher) {
+    let db = ChalkDatabase::with(&many_impls_program(30), SolverChoice::default());
+    let program = db.program_ir().unwrap();
+    let _guard = chalk_integration::tls::set_current_program(&program);
+    let trait_id = program.trait_ids[&"Foo".into()];
+    let solver_builder = || db.solver_choice().into_solver();
+
+    b.iter(|| {
+        CoherenceSolver::new(&db, &solver_builder, trait_id)
+            .specialization_priorities()
+            .unwrap()
+    });
+}
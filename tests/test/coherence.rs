@@ -27,6 +27,31 @@ fn two_impls_for_same_type() {
     }
 }
 
+#[test]
+fn impls_for_dyn_types() {
+    lowering_error! {
+        program {
+            trait A { }
+            trait B { }
+            impl B for dyn A + 'static { }
+            impl B for dyn A + 'static { }
+        }
+        error_msg {
+            "overlapping impls of trait `B`"
+        }
+    }
+
+    lowering_success! {
+        program {
+            trait A { }
+            trait B { }
+            trait C { }
+            impl B for dyn A + 'static { }
+            impl B for dyn C + 'static { }
+        }
+    }
+}
+
 #[test]
 fn generic_vec_and_specific_vec() {
     lowering_success! {
@@ -517,6 +542,50 @@ fn orphan_check() {
     }
 }
 
+#[test]
+fn fundamental_type_no_parameters() {
+    // A fundamental marker type without parameters is local
+    // exactly when it is not upstream
+    lowering_success! {
+        program {
+            #[fundamental]
+            struct Marker { }
+
+            #[upstream]
+            trait Remote { }
+
+            impl Remote for Marker { }
+        }
+    }
+
+    lowering_error! {
+        program {
+            #[upstream]
+            #[fundamental]
+            struct Marker { }
+
+            #[upstream]
+            trait Remote { }
+
+            impl Remote for Marker { }
+        } error_msg {
+            "impl for trait `Remote` violates the orphan rules"
+        }
+    }
+
+    lowering_success! {
+        program {
+            #[upstream]
+            #[fundamental]
+            struct Marker { }
+
+            trait Local { }
+
+            impl Local for Marker { }
+        }
+    }
+}
+
 #[test]
 fn fundamental_type_multiple_parameters() {
     // Test that implementing a local trait on a fundamental
@@ -587,3 +656,56 @@ fn fundamental_type_multiple_parameters() {
         }
     }
 }
+
+#[test]
+fn overlapping_negative_positive_auto_trait_impls() {
+    lowering_error! {
+        program {
+            #[auto] trait Send { }
+            struct SendType { }
+            struct Foo { x: SendType }
+
+            impl Send for Foo { }
+            impl !Send for Foo { }
+        } error_msg {
+            "overlapping impls of trait `Send`"
+        }
+    }
+}
+
+#[test]
+fn upstream_impls_are_not_checked_against_each_other() {
+    lowering_success! {
+        program {
+            #[upstream] trait Foo { }
+            #[upstream] struct Bar { }
+            #[upstream] impl Foo for Bar { }
+            #[upstream] impl Foo for Bar { }
+        }
+    }
+}
+
+#[test]
+fn local_impl_overlapping_upstream_impl() {
+    lowering_error! {
+        program {
+            trait Foo { }
+            struct Bar { }
+            #[upstream] impl Foo for Bar { }
+            impl Foo for Bar { }
+        }
+        error_msg {
+            "overlapping impls of trait `Foo`"
+        }
+    }
+
+    lowering_success! {
+        program {
+            trait Foo { }
+            struct Bar { }
+            struct Baz { }
+            #[upstream] impl Foo for Bar { }
+            impl Foo for Baz { }
+        }
+    }
+}
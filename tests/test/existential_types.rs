@@ -396,6 +396,55 @@ fn dyn_lifetime_bound() {
     }
 }
 
+#[test]
+fn dyn_static_lifetime_bound() {
+    test! {
+        program {
+            trait Foo { }
+
+            trait Eq<A> { }
+
+            impl<A> Eq<A> for A { }
+        }
+
+        goal {
+            forall<'a> {
+                dyn Foo + 'static: Eq<dyn Foo + 'a>
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints [\
+            InEnvironment { environment: Env([]), goal: '!1_0: 'static }, \
+            InEnvironment { environment: Env([]), goal: 'static: '!1_0 }\
+            ]"
+        }
+
+        goal {
+            dyn Foo + 'static: Eq<dyn Foo + 'static>
+        } yields {
+            "Unique; substitution [], lifetime constraints []"
+        }
+    }
+}
+
+#[test]
+fn dyn_lifetime_bound_outlives() {
+    test! {
+        program {
+            trait Foo { }
+        }
+
+        goal {
+            forall<'a, 'b> {
+                dyn Foo + 'a: 'b
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints [\
+            InEnvironment { environment: Env([]), goal: dyn for<type> [for<> Implemented(^1.0: Foo)] + '!1_0: '!1_1 }\
+            ]"
+        }
+    }
+}
+
 #[test]
 fn dyn_associated_type_binding() {
     test! {
@@ -417,3 +466,54 @@ fn dyn_associated_type_binding() {
         }
     }
 }
+
+#[test]
+fn impl_for_dyn() {
+    test! {
+        program {
+            trait A { }
+            trait B { }
+            trait C { }
+
+            impl B for dyn A + 'static { }
+            impl<'a> C for dyn B + 'a { }
+        }
+
+        goal {
+            dyn A + 'static: B
+        } yields {
+            "Unique; substitution [], lifetime constraints []"
+        }
+
+        goal {
+            dyn A + 'static: C
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            dyn B + 'static: B
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            forall<'a> {
+                dyn B + 'a: C
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints []"
+        }
+
+        goal {
+            forall<'a> {
+                dyn A + 'a: B
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints [\
+            InEnvironment { environment: Env([]), goal: '!1_0: 'static }, \
+            InEnvironment { environment: Env([]), goal: 'static: '!1_0 }\
+            ]"
+        }
+    }
+}
@@ -677,3 +677,42 @@ fn unify_types_in_impl() {
         }
     }
 }
+
+/// A blanket impl whose where clause mentions an associated type of
+/// the receiver requires normalizing that projection before the where
+/// clause can be proven.
+#[test]
+fn blanket_impl_with_projection_where_clause() {
+    test! {
+        program {
+            struct X { }
+            struct Y { }
+            struct Z { }
+            trait Clone { }
+            trait Bar { type Item; }
+            trait Foo { }
+            impl<T> Foo for T where T: Bar, <T as Bar>::Item: Clone { }
+            impl Clone for u32 { }
+            impl Bar for X { type Item = u32; }
+            impl Bar for Y { type Item = Z; }
+        }
+
+        goal {
+            X: Foo
+        } yields {
+            "Unique; substitution [], lifetime constraints []"
+        }
+
+        goal {
+            Y: Foo
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            Z: Foo
+        } yields {
+            "No possible solution"
+        }
+    }
+}
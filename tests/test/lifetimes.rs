@@ -181,3 +181,60 @@ fn empty_impls() {
         }
     }
 }
+
+#[test]
+fn lifetime_only_existentials() {
+    test! {
+        program {}
+
+        goal {
+            exists<'a> {
+                'a = 'static
+            }
+        } yields {
+            "Unique; substitution [?0 := 'static], lifetime constraints []"
+        }
+
+        goal {
+            exists<'a> {
+                'a: 'static
+            }
+        } yields {
+            "Unique; for<?U0> { substitution [?0 := '^0.0], lifetime constraints [InEnvironment { environment: Env([]), goal: '^0.0: 'static }] }"
+        }
+
+        goal {
+            forall<'b> {
+                exists<'a> {
+                    'a: 'b
+                }
+            }
+        } yields {
+            "Unique; for<?U1> { substitution [?0 := '^0.0], lifetime constraints [InEnvironment { environment: Env([]), goal: '^0.0: '!1_0 }] }"
+        }
+    }
+}
+
+#[test]
+fn nested_outlives_constraints() {
+    // The `'x: 'y` constraint is produced while proving the where clause
+    // of the `Qux` impl, and must be carried into the final solution.
+    test! {
+        program {
+            struct Bar<'a> {}
+            trait Baz<'b> {}
+            trait Qux<'b> {}
+
+            impl<'x, 'y> Baz<'y> for Bar<'x> where 'x: 'y {}
+            impl<'x, 'y> Qux<'y> for Bar<'x> where Bar<'x>: Baz<'y> {}
+        }
+
+        goal {
+            forall<'a, 'b> {
+                Bar<'a>: Qux<'b>
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints [InEnvironment { environment: Env([]), goal: '!1_0: '!1_1 }]"
+        }
+    }
+}
@@ -1,6 +1,7 @@
 //! Tests that don't fit a single category
 
 use super::*;
+use chalk_solve::solve::test_support::ExpectedSolution;
 
 // Regression test for rust-lang/chalk#111
 #[test]
@@ -748,11 +749,10 @@ fn empty_definite_guidance() {
             }
             // the program fails coherence, so which answer we get here exactly
             // isn't that important -- this is mainly a regression test for a
-            // recursive solver infinite loop.
-        } yields[SolverChoice::slg_default()] {
+            // recursive solver infinite loop. Both solvers stop at the first
+            // trivial answer.
+        } yields {
             "Unique"
-        } yields[SolverChoice::recursive_default()] {
-            "Ambiguous"
         }
     }
 }
@@ -774,8 +774,11 @@ fn ambiguous_unification_in_fn() {
             exists<int T, U> {
                 MyClosure<fn(&'static U) -> ()>: FnOnce<(&'static T,)>
             }
-        } yields {
-            "Unique"
+        } yields[SolverChoice::slg_default()] {
+            // The SLG solver repeats the `'static: 'static` constraint.
+            "Unique; for<?U0> { substitution [?0 := ^0.0, ?1 := ^0.0], lifetime constraints [InEnvironment { environment: Env([]), goal: 'static: 'static }, InEnvironment { environment: Env([]), goal: 'static: 'static }] }"
+        } yields[SolverChoice::recursive_default()] {
+            "Unique; for<?U0> { substitution [?0 := ^0.0, ?1 := ^0.0], lifetime constraints [InEnvironment { environment: Env([]), goal: 'static: 'static }] }"
         }
     }
 }
@@ -828,3 +831,257 @@ fn env_bound_vars() {
         }
     }
 }
+
+#[test]
+fn environment_add_clauses_is_idempotent() {
+    use chalk_ir::cast::Cast;
+    use chalk_ir::{DomainGoal, Environment, ProgramClause};
+
+    let interner = &ChalkIr;
+    let compatible: ProgramClause<ChalkIr> = DomainGoal::Compatible.cast(interner);
+    let reveal: ProgramClause<ChalkIr> = DomainGoal::Reveal.cast(interner);
+
+    let env =
+        Environment::new(interner).add_clauses(interner, vec![compatible.clone(), reveal.clone()]);
+    assert_eq!(
+        env.clauses.as_slice(interner),
+        &[compatible.clone(), reveal.clone()]
+    );
+
+    // Re-adding clauses, in any order, leaves the environment unchanged.
+    assert_eq!(env.add_clauses(interner, vec![compatible.clone()]), env);
+    assert_eq!(
+        env.add_clauses(interner, vec![reveal.clone(), compatible.clone()]),
+        env
+    );
+    assert_eq!(
+        Environment::new(interner)
+            .add_clauses(interner, vec![compatible.clone(), compatible, reveal]),
+        env
+    );
+}
+
+#[test]
+fn default_solvers_solve_libstd() {
+    use chalk_ir::{Scalar, Substitution, TyKind, UintTy};
+
+    let mut db = ChalkDatabase::with(include_str!("../../libstd.chalk"), SolverChoice::default());
+    let interner = &ChalkIr;
+    let goals = [
+        ("Vec<Box<i32>>: Clone", Some(ExpectedSolution::unique())),
+        ("Vec<Rc<u32>>: Copy", None),
+        (
+            "forall<T> { if (T: Clone) { Box<T>: Clone } }",
+            Some(ExpectedSolution::unique()),
+        ),
+        (
+            "exists<T> { Box<T>: AsRef<u32> }",
+            Some(ExpectedSolution::unique().with_subst(Substitution::from1(
+                interner,
+                TyKind::Scalar(Scalar::Uint(UintTy::U32)).intern(interner),
+            ))),
+        ),
+    ];
+
+    for solver_choice in [
+        SolverChoice::slg_default(),
+        SolverChoice::recursive_default(),
+    ] {
+        db.set_solver_choice(solver_choice);
+        db.with_program(|_| {
+            for (goal_text, expected) in &goals {
+                let goal = db.parse_and_lower_goal(goal_text).unwrap();
+                let result = db.solve(&goal.into_peeled_goal(interner));
+                match (&result, expected) {
+                    (Some(solution), Some(expected)) => assert!(
+                        solution.matches(interner, expected),
+                        "`{}`: expected {}, got {}",
+                        goal_text,
+                        expected,
+                        solution.display(interner)
+                    ),
+                    (None, None) => {}
+                    _ => panic!("`{}`: got {:?}", goal_text, result),
+                }
+            }
+        });
+    }
+}
+
+#[test]
+fn same_substitution_different_lifetime_constraints() {
+    // The impl and the environment each prove the goal, under different
+    // region constraints. Neither answer subsumes the other and we can't
+    // express an `OR` of the two, so the result is ambiguous. The SLG
+    // solver gives no guidance for the empty substitution.
+    test! {
+        program {
+            trait Foo { }
+            struct Ref<'a, 'b> { }
+            impl<'a, 'b> Foo for Ref<'a, 'b> where 'a: 'b { }
+        }
+
+        goal {
+            forall<'a, 'b> {
+                if (forall<> { Ref<'a, 'b>: Foo :- 'b: 'a }) {
+                    Ref<'a, 'b>: Foo
+                }
+            }
+        } yields[SolverChoice::slg_default()] {
+            "Ambiguous; no inference guidance"
+        } yields[SolverChoice::recursive_default()] {
+            "Ambiguous; definite substitution []"
+        }
+    }
+}
+
+#[test]
+fn same_substitution_subset_of_lifetime_constraints() {
+    // The environment proves the goal outright, while the impl needs
+    // `'a: 'b`. The unconstrained answer subsumes the constrained one.
+    test! {
+        program {
+            trait Foo { }
+            struct Ref<'a, 'b> { }
+            impl<'a, 'b> Foo for Ref<'a, 'b> where 'a: 'b { }
+        }
+
+        goal {
+            forall<'a, 'b> {
+                if (Ref<'a, 'b>: Foo) {
+                    Ref<'a, 'b>: Foo
+                }
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints []"
+        }
+    }
+}
+
+/// Clauses from the environment are tried before the clauses of the
+/// program, so the hypothesis of the `if` gives the first answer.
+#[test]
+fn environment_clauses_first() {
+    test! {
+        program {
+            trait Tr { type Out; }
+            struct A { }
+            struct B { }
+            impl Tr for A { type Out = A; }
+        }
+
+        goal {
+            if (A: Tr<Out = B>) {
+                exists<T> { A: Tr<Out = T> }
+            }
+        } yields_first[SolverChoice::slg(10, None)] {
+            "substitution [?0 := B]",
+            "substitution [?0 := (Tr::Out)<A>]"
+        }
+    }
+}
+
+/// After the environment, the clauses of the program are tried before the
+/// custom clauses.
+#[test]
+fn custom_clauses_last() {
+    test! {
+        program {
+            trait Tr<T> { }
+            struct A { }
+            struct B { }
+            impl Tr<A> for A { }
+            forall<> { A: Tr<B> }
+        }
+
+        goal {
+            exists<T> { A: Tr<T> }
+        } yields_first[SolverChoice::slg(10, None)] {
+            "substitution [?0 := A]",
+            "substitution [?0 := B]"
+        }
+    }
+}
+
+#[test]
+fn trait_provided_clauses() {
+    test! {
+        program {
+            trait Show { }
+            trait Cmp<T> { }
+            trait Debug {
+                clause Self: Show;
+                clause forall<T> { Self: Cmp<T> :- T: Debug };
+            }
+
+            struct A { }
+            struct B { }
+            impl Debug for A { }
+        }
+
+        goal {
+            forall<T> { if (T: Debug) { T: Show } }
+        } yields {
+            "Unique; substitution []"
+        }
+
+        goal {
+            forall<T> { T: Show }
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            forall<T> { if (T: Debug) { T: Cmp<A> } }
+        } yields {
+            "Unique; substitution []"
+        }
+
+        goal {
+            forall<T> { if (T: Debug) { T: Cmp<B> } }
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            forall<T> { if (T: Show) { T: Cmp<A> } }
+        } yields {
+            "No possible solution"
+        }
+    }
+}
+
+/// A goal whose parameters have the wrong kinds has no solution. Such a
+/// goal can't be written in a program, but integrators may build one.
+#[test]
+fn mismatched_parameter_kinds() {
+    use chalk_ir::cast::Cast;
+    use chalk_ir::{Goal, LifetimeData, Substitution, TraitRef, TyKind};
+
+    let db = ChalkDatabase::with(
+        "#[lang(sized)] trait Sized { } struct Foo<T> { t: T }",
+        SolverChoice::default(),
+    );
+    let program = db.checked_program().unwrap();
+    let interner = db.interner();
+    db.with_program(|_| {
+        // `Foo<'static>: Sized`, where `Foo` takes a type.
+        let foo = TyKind::Adt(
+            program.adt_ids[&"Foo".into()],
+            Substitution::from1(interner, LifetimeData::Static.intern(interner)),
+        )
+        .intern(interner);
+        let goal: Goal<ChalkIr> = TraitRef {
+            trait_id: program.trait_ids[&"Sized".into()],
+            substitution: Substitution::from1(interner, foo),
+        }
+        .cast(interner);
+        let peeled_goal = goal.into_peeled_goal(interner);
+        for solver_choice in &[
+            SolverChoice::slg_default(),
+            SolverChoice::recursive_default(),
+        ] {
+            assert_eq!(solver_choice.into_solver().solve(&db, &peeled_goal), None);
+        }
+    });
+}
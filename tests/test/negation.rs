@@ -354,3 +354,208 @@ fn negative_reorder() {
         }
     }
 }
+
+/// `NotHolds(WC)` assumes that `WC` is false, which proves `not { WC }`
+/// without consulting the impls of `WC`.
+#[test]
+fn negative_assumption() {
+    test! {
+        program {
+            trait Foo { }
+            trait Bar { }
+            struct S { }
+            impl<T> Foo for T where T: Bar { }
+        }
+
+        goal {
+            forall<T> {
+                if (T: Bar) {
+                    not { T: Foo }
+                }
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            forall<T> {
+                if (T: Bar; NotHolds(T: Foo)) {
+                    not { T: Foo }
+                }
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints []"
+        }
+
+        goal {
+            if (NotHolds(S: Foo)) {
+                not { S: Foo }
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints []"
+        }
+
+        // The assumption only applies to the exact where clause.
+        goal {
+            forall<T> {
+                if (T: Bar; NotHolds(S: Foo)) {
+                    not { T: Foo }
+                }
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            exists<T> {
+                if (NotHolds(S: Foo)) {
+                    T = S, not { T: Foo }
+                }
+            }
+        } yields {
+            "Unique; substitution [?0 := S], lifetime constraints []"
+        }
+    }
+}
+
+/// The binder of `forall<T> { not { ... } }` is instantiated with a
+/// placeholder before the negative goal is inverted, so the refutation
+/// asks whether *any* type implements the trait.
+#[test]
+fn negation_under_forall() {
+    test! {
+        program {
+            trait Debug { }
+            trait Display { }
+            trait Clone { }
+            struct Foo { }
+            struct Bar { }
+            impl Debug for Foo { }
+            impl Display for Foo { }
+            impl Display for Bar { }
+            impl<T> Clone for T { }
+        }
+
+        goal {
+            forall<T> { not { T: Debug } }
+        } yields {
+            "No possible solution"
+        }
+
+        // With two impls, the inverted goal `exists<T> { T: Display }` is
+        // ambiguous for the recursive solver, which cannot tell that one
+        // of its candidates is a definite answer. SLG finds that answer
+        // and refutes. Both are sound.
+        goal {
+            forall<T> { not { T: Display } }
+        } yields[SolverChoice::slg_default()] {
+            "No possible solution"
+        } yields[SolverChoice::recursive_default()] {
+            "Ambiguous; no inference guidance"
+        }
+
+        goal {
+            forall<T> { not { T: Clone } }
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            forall<T> { not { Foo: Debug } }
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            forall<T> { not { Bar: Debug } }
+        } yields {
+            "Unique; substitution [], lifetime constraints []"
+        }
+    }
+}
+
+/// `not { exists<T> { G } }` holds only if no instantiation of `T`
+/// satisfies `G`. The goal is closed, so the negation is decided once
+/// the existential goal is: it succeeds if there is no `T` at all, and
+/// fails as soon as one `T` is found.
+#[test]
+fn negated_existentials() {
+    test! {
+        program {
+            trait NeverImpl { }
+            trait Clone { }
+            struct Foo { }
+            impl Clone for Foo { }
+        }
+
+        goal {
+            not { exists<T> { T: NeverImpl } }
+        } yields {
+            "Unique; substitution [], lifetime constraints []"
+        }
+
+        goal {
+            not { exists<T> { T = u32 } }
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            not { exists<T> { T: Clone } }
+        } yields {
+            "No possible solution"
+        }
+
+        // With a free existential outside the negation, we cannot decide
+        // until it is known, so the result is ambiguous.
+        goal {
+            exists<U> { not { exists<T> { T = U } } }
+        } yields {
+            "Ambiguous; no inference guidance"
+        }
+    }
+}
+
+/// `not { not { G } }` is folded into `G` when `G` is closed, which lets
+/// us report region constraints that the negation could not express.
+/// When `G` mentions a variable from outside, the double negation is left
+/// alone, since `G` might bind that variable while the negation cannot.
+#[test]
+fn double_negation() {
+    test! {
+        program {
+            trait Foo<'a> { }
+            trait Bar { }
+            struct Baz { }
+            struct Qux { }
+            impl<'a> Foo<'a> for &'a Baz { }
+            impl Bar for Baz { }
+        }
+
+        goal {
+            not { not { Baz: Bar } }
+        } yields {
+            "Unique; substitution [], lifetime constraints []"
+        }
+
+        goal {
+            not { not { Qux: Bar } }
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            forall<'a, 'b> { not { not { &'a Baz: Foo<'b> } } }
+        } yields {
+            "Unique; substitution [], lifetime constraints [\
+             InEnvironment { environment: Env([]), goal: '!1_0: '!1_1 }, \
+             InEnvironment { environment: Env([]), goal: '!1_1: '!1_0 }]"
+        }
+
+        goal {
+            exists<T> { not { not { T: Bar } } }
+        } yields {
+            "Ambiguous; no inference guidance"
+        }
+    }
+}
@@ -266,8 +266,11 @@ fn shl_ice() {
             exists<U> {
                 u32: Shl<U>
             }
-        } yields {
-            "Ambiguous"
+        } yields[SolverChoice::slg_default()] {
+            // Both impls have `U = &'a _`, which only the SLG solver notices.
+            "Ambiguous; definite substitution for<?U0,?U0> { [?0 := (&'^0.0 ^0.1)] }"
+        } yields[SolverChoice::recursive_default()] {
+            "Ambiguous; no inference guidance"
         }
     }
 }
@@ -283,3 +283,40 @@ fn opaque_super_trait() {
         }
     }
 }
+
+#[test]
+fn opaque_reveal_compatible() {
+    test! {
+        program {
+            struct Ty { }
+
+            trait Clone { }
+            impl Clone for Ty { }
+            opaque type T: Clone = Ty;
+        }
+
+        goal {
+            compatible { reveal { T = Ty, Compatible } }
+        } yields {
+            "Unique; substitution []"
+        }
+
+        goal {
+            reveal { compatible { T = Ty, Compatible } }
+        } yields {
+            "Unique; substitution []"
+        }
+
+        goal {
+            reveal { T = Ty, Compatible }
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            compatible { T = Ty, Compatible }
+        } yields {
+            "No possible solution"
+        }
+    }
+}
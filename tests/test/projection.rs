@@ -1107,3 +1107,463 @@ fn projection_to_opaque() {
         }
     }
 }
+
+/// Equating a type with a projection out of itself must terminate: the
+/// occurs check in unification rejects `T = S<T>`-style solutions, and
+/// truncation bounds the growth of `S<S<..>>`.
+#[test]
+fn self_referential_projection_eq() {
+    test! {
+        program {
+            struct S<T> { }
+            struct Foo { }
+            trait Trait { type Item; }
+            impl<T> Trait for S<T> { type Item = S<S<T>>; }
+            impl Trait for Foo { type Item = Foo; }
+        }
+
+        goal {
+            exists<T> { T = <T as Trait>::Item }
+        } yields {
+            "Unique; substitution [?0 := Foo], lifetime constraints []"
+        }
+
+        goal {
+            exists<T> { T = <S<T> as Trait>::Item }
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            exists<T> { S<T> = <T as Trait>::Item }
+        } yields {
+            "Unique; for<?U0> { substitution [?0 := S<^0.0>], lifetime constraints [] }"
+        }
+
+        goal {
+            forall<T> {
+                if (T: Trait) {
+                    T = <T as Trait>::Item
+                }
+            }
+        } yields {
+            "No possible solution"
+        }
+    }
+}
+
+/// Where clauses on the parameters of a generic associated type must hold
+/// in order to normalize it.
+#[test]
+fn normalize_gat_with_where_clauses() {
+    test! {
+        program {
+            trait Clone { }
+            struct Foo { }
+            struct Bar { }
+            struct Ref<'a, T> { }
+            impl Clone for Foo { }
+
+            trait Lending {
+                type Item<'a> where 'a: 'static;
+                type Cloned<U> where U: Clone;
+            }
+
+            impl Lending for Foo {
+                type Item<'a> = Ref<'a, Foo>;
+                type Cloned<U> = U;
+            }
+        }
+
+        goal {
+            forall<'a> {
+                exists<U> {
+                    Normalize(<Foo as Lending>::Item<'a> -> U)
+                }
+            }
+        } yields {
+            "Unique; substitution [?0 := Ref<'!1_0, Foo>], lifetime constraints [InEnvironment { environment: Env([]), goal: '!1_0: 'static }]"
+        }
+
+        goal {
+            exists<U> {
+                Normalize(<Foo as Lending>::Item<'static> -> U)
+            }
+        } yields {
+            "Unique; substitution [?0 := Ref<'static, Foo>], lifetime constraints [InEnvironment { environment: Env([]), goal: 'static: 'static }]"
+        }
+
+        goal {
+            exists<U> {
+                Normalize(<Foo as Lending>::Cloned<Foo> -> U)
+            }
+        } yields {
+            "Unique; substitution [?0 := Foo], lifetime constraints []"
+        }
+
+        goal {
+            exists<U> {
+                Normalize(<Foo as Lending>::Cloned<Bar> -> U)
+            }
+        } yields {
+            "No possible solution"
+        }
+    }
+}
+
+#[test]
+fn assoc_type_bound_sugar() {
+    test! {
+        program {
+            trait Iterator { type Item; }
+            trait Clone { }
+
+            struct Vec<T> { }
+            struct Foo { }
+            struct Bar { }
+
+            impl Clone for Foo { }
+            impl<T> Iterator for Vec<T> { type Item = T; }
+
+            trait Sugar { }
+            impl<I> Sugar for I where I: Iterator<Item: Clone> { }
+
+            trait Explicit { }
+            impl<I> Explicit for I where I: Iterator, <I as Iterator>::Item: Clone { }
+        }
+
+        goal {
+            Vec<Foo>: Sugar
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            Vec<Foo>: Explicit
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            Vec<Bar>: Sugar
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            Vec<Bar>: Explicit
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            Vec<Foo>: Iterator<Item: Clone>
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            forall<I> {
+                if (I: Iterator<Item: Clone>) {
+                    I: Explicit
+                }
+            }
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            forall<I> {
+                if (I: Iterator; <I as Iterator>::Item: Clone) {
+                    I: Sugar
+                }
+            }
+        } yields {
+            "Unique"
+        }
+    }
+}
+
+#[test]
+fn assoc_type_bindings_in_any_position() {
+    test! {
+        program {
+            trait Clone { }
+            trait Copy { }
+            trait Pair<T> { type First; type Second; }
+
+            struct Foo { }
+            struct Bar { }
+            struct Baz { }
+
+            impl Clone for Foo { }
+            impl Copy for Foo { }
+            impl Clone for Bar { }
+            impl Pair<Baz> for Foo { type First = Foo; type Second = Bar; }
+
+            trait Sugar { }
+            impl<I> Sugar for I where I: Pair<First: Clone + Copy, Baz, Second = Bar> { }
+
+            trait Explicit { }
+            impl<I> Explicit for I
+                where
+                    I: Pair<Baz, Second = Bar>,
+                    <I as Pair<Baz>>::First: Clone,
+                    <I as Pair<Baz>>::First: Copy
+            { }
+        }
+
+        goal {
+            Foo: Sugar
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            Foo: Pair<Second: Clone, Baz, First = Foo,>
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            Foo: Pair<Baz, Second: Copy>
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            Foo: Pair<First = Foo, Second = Foo, Baz>
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            forall<I> {
+                if (I: Pair<Second = Bar, First: Clone + Copy, Baz>) {
+                    I: Explicit
+                }
+            }
+        } yields {
+            "Unique"
+        }
+    }
+}
+
+#[test]
+fn normalize_with_value_where_clause() {
+    test! {
+        program {
+            trait Bound { }
+            trait Foo {
+                type Item;
+            }
+
+            struct Wrapper<T> { }
+            struct A { }
+            struct B { }
+            impl Bound for A { }
+            impl<T> Foo for Wrapper<T> {
+                type Item = T where T: Bound;
+            }
+        }
+
+        goal {
+            exists<U> {
+                Normalize(<Wrapper<A> as Foo>::Item -> U)
+            }
+        } yields {
+            "Unique; substitution [?0 := A]"
+        }
+
+        goal {
+            exists<U> {
+                Normalize(<Wrapper<B> as Foo>::Item -> U)
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            forall<T> {
+                if (T: Bound) {
+                    Normalize(<Wrapper<T> as Foo>::Item -> T)
+                }
+            }
+        } yields {
+            "Unique"
+        }
+    }
+}
+
+#[test]
+fn projection_eq_between_projections() {
+    test! {
+        program {
+            trait A { type X; }
+            trait B { type Y; }
+            trait Foo { }
+
+            struct Z { }
+            struct W { }
+            struct Int { }
+            struct Str { }
+
+            impl A for Z { type X = Int; }
+            impl B for Z { type Y = Int; }
+            impl A for W { type X = Int; }
+            impl B for W { type Y = Str; }
+
+            impl<T> Foo for T where T: A<X = <T as B>::Y>, T: B { }
+        }
+
+        goal {
+            Z: Foo
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            W: Foo
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            forall<T> {
+                if (T: A<X = <T as B>::Y>; T: B) {
+                    T: Foo
+                }
+            }
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            forall<T> {
+                if (T: A<X = <T as B>::Y>; T: B) {
+                    <T as A>::X = <T as B>::Y
+                }
+            }
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            forall<T> {
+                if (T: A; T: B) {
+                    <T as A>::X = <T as B>::Y
+                }
+            }
+        } yields {
+            "No possible solution"
+        }
+    }
+}
+
+/// Two projections with equal inputs are equal without normalizing either:
+/// `<T as Trait>::Item` unifies with itself through the placeholder
+/// associated type, even when no impl or where clause says what it is.
+#[test]
+fn projection_eq_without_normalization() {
+    test! {
+        program {
+            trait Trait { type Item; }
+            trait Other { type Item; }
+            struct Foo { }
+        }
+
+        goal {
+            forall<T> {
+                <T as Trait>::Item = <T as Trait>::Item
+            }
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            forall<T> {
+                if (T: Trait) {
+                    <T as Trait>::Item = <T as Trait>::Item
+                }
+            }
+        } yields {
+            "Unique"
+        }
+
+        // The inputs need not be syntactically identical, only equal.
+        goal {
+            forall<T> {
+                exists<U> {
+                    U = T, <T as Trait>::Item = <U as Trait>::Item
+                }
+            }
+        } yields {
+            "Unique; substitution [?0 := !1_0]"
+        }
+
+        goal {
+            forall<T, U> {
+                <T as Trait>::Item = <U as Trait>::Item
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            forall<T> {
+                <T as Trait>::Item = <T as Other>::Item
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            exists<U> {
+                forall<T> {
+                    <T as Trait>::Item = <U as Trait>::Item
+                }
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            <Foo as Trait>::Item = <Foo as Trait>::Item
+        } yields {
+            "Unique"
+        }
+    }
+}
+
+#[test]
+fn normalize_to_impl_parameter() {
+    test! {
+        program {
+            trait Iterator { type Item; }
+            struct Wrapper<T> { }
+            impl<T> Iterator for Wrapper<T> {
+                type Item = T;
+            }
+        }
+
+        goal {
+            Normalize(<Wrapper<u32> as Iterator>::Item -> u32)
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            exists<U> {
+                Normalize(<Wrapper<u32> as Iterator>::Item -> U)
+            }
+        } yields {
+            "Unique; substitution [?0 := Uint(U32)]"
+        }
+
+        goal {
+            Normalize(<Wrapper<u32> as Iterator>::Item -> i32)
+        } yields {
+            "No possible solution"
+        }
+    }
+}
@@ -0,0 +1,149 @@
+//! Tests for trait aliases, like `trait Alias = Foo + Bar;`.
+
+use super::*;
+
+#[test]
+fn alias_requires_all_bounds() {
+    test! {
+        program {
+            trait Foo { }
+            trait Bar { }
+            trait Alias = Foo + Bar;
+
+            struct Both { }
+            struct OnlyFoo { }
+
+            impl Foo for Both { }
+            impl Bar for Both { }
+            impl Foo for OnlyFoo { }
+        }
+
+        goal {
+            Both: Alias
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            OnlyFoo: Alias
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            forall<T> {
+                if (T: Foo; T: Bar) {
+                    T: Alias
+                }
+            }
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            forall<T> {
+                if (T: Foo) {
+                    T: Alias
+                }
+            }
+        } yields {
+            "No possible solution"
+        }
+    }
+}
+
+#[test]
+fn alias_from_env_implies_bounds() {
+    test! {
+        program {
+            trait Foo { }
+            trait Bar { }
+            trait Alias = Foo + Bar;
+        }
+
+        goal {
+            forall<T> {
+                if (FromEnv(T: Alias)) {
+                    T: Foo
+                }
+            }
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            forall<T> {
+                if (FromEnv(T: Alias)) {
+                    T: Bar
+                }
+            }
+        } yields {
+            "Unique"
+        }
+    }
+}
+
+#[test]
+fn generic_alias() {
+    test! {
+        program {
+            trait Foo<T> { }
+            trait Iterator {
+                type Item;
+            }
+            trait IterOf<T> = Iterator<Item = T> + Foo<T>;
+
+            struct S { }
+            struct Int { }
+            struct Uint { }
+
+            impl Iterator for S {
+                type Item = Int;
+            }
+            impl Foo<Int> for S { }
+            impl Foo<Uint> for S { }
+        }
+
+        goal {
+            S: IterOf<Int>
+        } yields {
+            "Unique"
+        }
+
+        goal {
+            S: IterOf<Uint>
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            exists<T> {
+                S: IterOf<T>
+            }
+        } yields {
+            "Unique; substitution [?0 := Int]"
+        }
+    }
+}
+
+#[test]
+fn alias_in_impl_where_clause() {
+    test! {
+        program {
+            trait Foo { }
+            trait Bar { }
+            trait Alias = Foo + Bar;
+            trait Baz { }
+            struct S { }
+            impl Foo for S { }
+            impl Bar for S { }
+            impl Baz for S where S: Alias { }
+        }
+
+        goal {
+            S: Baz
+        } yields {
+            "Unique"
+        }
+    }
+}
@@ -142,6 +142,117 @@ fn dyn_to_dyn_unsizing() {
     }
 }
 
+#[test]
+fn dyn_upcasting() {
+    test! {
+        program {
+            #[lang(unsize)]
+            trait Unsize<T> {}
+
+            #[object_safe]
+            trait Super {}
+            #[object_safe]
+            trait Sub where Self: Super {}
+            #[object_safe]
+            trait SubSub where Self: Sub {}
+            #[object_safe]
+            trait Unrelated {}
+
+            #[object_safe]
+            trait GenericSuper<T> {}
+            #[object_safe]
+            trait GenericSub where Self: GenericSuper<u32> {}
+
+            #[auto]
+            #[object_safe]
+            trait Auto1 {}
+
+            #[auto]
+            #[object_safe]
+            trait Auto2 {}
+        }
+
+        goal {
+            forall<'a> {
+                dyn Sub + 'a: Unsize<dyn Super + 'a>
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints [InEnvironment { environment: Env([]), goal: '!1_0: '!1_0 }]"
+        }
+
+        // Supertraits of supertraits
+        goal {
+            forall<'a> {
+                dyn SubSub + 'a: Unsize<dyn Super + 'a>
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints [InEnvironment { environment: Env([]), goal: '!1_0: '!1_0 }]"
+        }
+
+        // The lifetime may be shortened while upcasting
+        goal {
+            forall<'a> {
+                forall<'b> {
+                    dyn Sub + 'a: Unsize<dyn Super + 'b>
+                }
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints [InEnvironment { environment: Env([]), goal: '!1_0: '!2_0 }]"
+        }
+
+        // Auto traits may be dropped, but not added
+        goal {
+            forall<'a> {
+                dyn Sub + Auto1 + Auto2 + 'a: Unsize<dyn Super + Auto1 + 'a>
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints [InEnvironment { environment: Env([]), goal: '!1_0: '!1_0 }]"
+        }
+
+        goal {
+            forall<'a> {
+                dyn Sub + 'a: Unsize<dyn Super + Auto1 + 'a>
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        // Downcasting is not allowed
+        goal {
+            forall<'a> {
+                dyn Super + 'a: Unsize<dyn Sub + 'a>
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            forall<'a> {
+                dyn Sub + 'a: Unsize<dyn Unrelated + 'a>
+            }
+        } yields {
+            "No possible solution"
+        }
+
+        // The parameters of the supertrait must match
+        goal {
+            forall<'a> {
+                dyn GenericSub + 'a: Unsize<dyn GenericSuper<u32> + 'a>
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints [InEnvironment { environment: Env([]), goal: '!1_0: '!1_0 }]"
+        }
+
+        goal {
+            forall<'a> {
+                dyn GenericSub + 'a: Unsize<dyn GenericSuper<u64> + 'a>
+            }
+        } yields {
+            "No possible solution"
+        }
+    }
+}
+
 #[test]
 fn ty_to_dyn_unsizing() {
     test! {
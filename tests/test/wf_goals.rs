@@ -90,6 +90,74 @@ fn recursive_where_clause_on_type() {
     }
 }
 
+#[test]
+fn projection_wf() {
+    test! {
+        program {
+            trait Iterator where Self: Sized { type Item; }
+            trait Sized { }
+
+            struct Vec<T> { }
+            struct NotSized { }
+
+            impl<T> Sized for Vec<T> { }
+            impl<T> Iterator for Vec<T> { type Item = T; }
+        }
+
+        goal {
+            forall<T> {
+                WellFormed(<Vec<T> as Iterator>::Item)
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints []"
+        }
+
+        goal {
+            WellFormed(<NotSized as Iterator>::Item)
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            forall<T> {
+                if (FromEnv(T: Iterator)) {
+                    WellFormed(<T as Iterator>::Item)
+                }
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints []"
+        }
+    }
+}
+
+#[test]
+fn dyn_wf() {
+    test! {
+        program {
+            #[object_safe]
+            trait Foo { }
+
+            trait NotObjectSafe { }
+        }
+
+        goal {
+            forall<'a> {
+                WellFormed(dyn Foo + 'a)
+            }
+        } yields {
+            "Unique; substitution [], lifetime constraints []"
+        }
+
+        goal {
+            forall<'a> {
+                WellFormed(dyn NotObjectSafe + 'a)
+            }
+        } yields {
+            "No possible solution"
+        }
+    }
+}
+
 #[test]
 fn drop_compatible() {
     test! {
@@ -107,3 +175,91 @@ fn drop_compatible() {
         }
     }
 }
+
+#[test]
+fn maybe_sized_parameters() {
+    test! {
+        program {
+            #[lang(sized)]
+            trait Sized { }
+
+            trait Foo<T: ?Sized> { }
+
+            struct Ref<T: ?Sized> { }
+            struct Val<T> { }
+
+            impl<T: ?Sized> Foo<T> for Ref<T> { }
+        }
+
+        goal {
+            WellFormed(Ref<[u32]>)
+        } yields {
+            "Unique; substitution [], lifetime constraints []"
+        }
+
+        goal {
+            WellFormed(Val<[u32]>)
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            WellFormed(Val<u32>)
+        } yields {
+            "Unique; substitution [], lifetime constraints []"
+        }
+
+        goal {
+            Ref<[u32]>: Foo<[u32]>
+        } yields {
+            "Unique; substitution [], lifetime constraints []"
+        }
+    }
+}
+
+#[test]
+fn well_formed_trait_ref_requires_super_traits() {
+    test! {
+        program {
+            trait Foo { }
+            trait Clone where Self: Foo { }
+            trait Copy where Self: Clone { }
+
+            struct A { }
+            impl Foo for A { }
+            impl Clone for A { }
+            impl Copy for A { }
+
+            // `B` implements `Clone` and `Copy` but not `Foo`, so
+            // `B: Clone` is not well-formed. These are custom clauses
+            // rather than impls, as the impls would not be well-formed.
+            struct B { }
+            forall<> { B: Clone }
+            forall<> { B: Copy }
+        }
+
+        goal {
+            WellFormed(A: Copy)
+        } yields {
+            "Unique; substitution []"
+        }
+
+        goal {
+            B: Copy
+        } yields {
+            "Unique; substitution []"
+        }
+
+        goal {
+            WellFormed(B: Clone)
+        } yields {
+            "No possible solution"
+        }
+
+        goal {
+            WellFormed(B: Copy)
+        } yields {
+            "No possible solution"
+        }
+    }
+}
@@ -643,7 +643,7 @@ fn struct_sized_constraints() {
             #[lang(sized)]
             trait Sized { }
 
-            struct S<T> {
+            struct S<T: ?Sized> {
                 t1: T,
                 t2: T
             }
@@ -659,7 +659,7 @@ fn struct_sized_constraints() {
 
             struct Foo { }
 
-            struct S<T> {
+            struct S<T: ?Sized> {
                 t1: Foo,
                 t2: T
             }
@@ -671,7 +671,7 @@ fn struct_sized_constraints() {
             #[lang(sized)]
             trait Sized { }
 
-            struct S<T> where T: Sized {
+            struct S<T: ?Sized> where T: Sized {
                 t1: T,
                 t2: T
             }
@@ -685,12 +685,12 @@ fn struct_sized_constraints() {
 
             struct Foo {}
 
-            struct G<T> {
+            struct G<T: ?Sized> {
                 foo: S<S<Foo>>,
                 s: S<S<S<T>>>
             }
 
-            struct S<T> {
+            struct S<T: ?Sized> {
                 t1: T
             }
         }
@@ -718,7 +718,7 @@ fn enum_sized_constraints() {
             #[lang(sized)]
             trait Sized { }
 
-            enum E<T> {
+            enum E<T: ?Sized> {
                 A {
                     t1: T,
                     t2: T,
@@ -739,7 +739,7 @@ fn enum_sized_constraints() {
 
             struct Foo { }
 
-            enum E<T> {
+            enum E<T: ?Sized> {
                 A {
                     t1: Foo,
                     t2: T,
@@ -758,7 +758,7 @@ fn enum_sized_constraints() {
             #[lang(sized)]
             trait Sized { }
 
-            enum S<T> where T: Sized {
+            enum S<T: ?Sized> where T: Sized {
                 A {
                     t1: T,
                     t2: T,
@@ -1438,3 +1438,38 @@ fn coerce_unsized_struct() {
         }
     }
 }
+
+#[test]
+fn auto_trait_impl_where_clauses() {
+    lowering_success! {
+        program {
+            #[auto] trait Send { }
+            #[auto] trait Sync { }
+            struct Foo<T> { }
+
+            impl<T> Send for Foo<T> where T: Send, T: Sync { }
+        }
+    }
+
+    lowering_success! {
+        program {
+            #[auto] trait Send { }
+            trait Bar { }
+            struct Foo<T> { }
+
+            impl<T> !Send for Foo<T> where T: Bar { }
+        }
+    }
+
+    lowering_error! {
+        program {
+            #[auto] trait Send { }
+            trait Bar { }
+            struct Foo<T> { }
+
+            impl<T> Send for Foo<T> where T: Bar { }
+        } error_msg {
+            "trait impl for `Send` does not meet well-formedness requirements"
+        }
+    }
+}